@@ -0,0 +1,186 @@
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+use std::collections::{HashMap, HashSet};
+
+use crate::graph::{DataRef, DataTypes, Edge, EdgeIndex, Graph, Node, NodeIndex};
+use crate::lookup::NodeLookup;
+use crate::traits::{GraphBase, IntoNodeIdentifiers, Neighbors};
+
+/*
+Arena-backed graph for workloads that build and drop lots of short-lived
+graphs (fuzzing, enumeration loops...).
+
+Node payloads and both backing arrays live in a `bumpalo::Bump`, so building
+a graph doesn't hit the global allocator once per node, and dropping it is
+cheap (only the hash indexes used to deduplicate nodes and edges are on the
+heap). The caller owns the arena and can `reset()` it between iterations:
+
+    let mut bump = Bump::new();
+    loop {
+        let mut g = ArenaGraph::new_in(&bump);
+        // ... build and query g ...
+        drop(g);
+        bump.reset();
+    }
+
+The node/edge semantics are the same as `Graph` (nodes are deduplicated by
+value, edges too), and `to_graph()` copies everything into a regular `Graph`
+for the algorithms that only exist there.
+*/
+
+pub struct ArenaGraph<'bump> {
+    bump: &'bump Bump,
+    nodes: BumpVec<'bump, DataRef<'bump>>,
+    edges: BumpVec<'bump, Edge>,
+    node_lookup: NodeLookup,
+    edge_lookup: HashMap<(NodeIndex, NodeIndex), EdgeIndex>,
+}
+
+impl<'bump> ArenaGraph<'bump> {
+    pub fn new_in(bump: &'bump Bump) -> Self {
+        ArenaGraph {
+            bump,
+            nodes: BumpVec::new_in(bump),
+            edges: BumpVec::new_in(bump),
+            node_lookup: NodeLookup::default(),
+            edge_lookup: HashMap::new(),
+        }
+    }
+
+    // Copies the payload of `node` into the arena.
+    pub fn add_node(&mut self, node: &Node) -> NodeIndex {
+        match &node.data {
+            DataTypes::Text(v) => self.add_text(v),
+            DataTypes::Blob(v) => self.add_blob(v),
+            DataTypes::Integer(v) => self.add_integer(*v),
+        }
+    }
+
+    // Payloads are only copied into the arena when they aren't there yet,
+    // otherwise re-adding an existing node would leak its payload until the
+    // next reset.
+    pub fn add_text(&mut self, v: &str) -> NodeIndex {
+        match self.find_node_idx(DataRef::Text(v)) {
+            Some(idx) => idx,
+            None => self.push_node(DataRef::Text(self.bump.alloc_str(v))),
+        }
+    }
+
+    pub fn add_blob(&mut self, v: &[u8]) -> NodeIndex {
+        match self.find_node_idx(DataRef::Blob(v)) {
+            Some(idx) => idx,
+            None => self.push_node(DataRef::Blob(self.bump.alloc_slice_copy(v))),
+        }
+    }
+
+    pub fn add_integer(&mut self, v: isize) -> NodeIndex {
+        match self.find_node_idx(DataRef::Integer(v)) {
+            Some(idx) => idx,
+            None => self.push_node(DataRef::Integer(v)),
+        }
+    }
+
+    // Both ends have to exist, like in `Graph::add_edge`.
+    pub fn add_edge(&mut self, new_edge: Edge) -> EdgeIndex {
+        assert!(
+            new_edge.from < self.nodes.len() && new_edge.to < self.nodes.len(),
+            "edge {:?} points to a node that doesn't exist",
+            new_edge
+        );
+        let key = (new_edge.from, new_edge.to);
+        if let Some(idx) = self.edge_lookup.get(&key) {
+            return *idx;
+        }
+
+        self.edges.push(new_edge);
+        self.edge_lookup.insert(key, self.edges.len() - 1);
+        self.edges.len() - 1
+    }
+
+    pub fn node(&self, node_idx: NodeIndex) -> Option<DataRef<'bump>> {
+        self.nodes.get(node_idx).copied()
+    }
+
+    pub fn find_node_idx(&self, data: DataRef) -> Option<NodeIndex> {
+        self.node_lookup.find(&self.nodes, &data)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn reachable_nodes_from(&self, node_idx: NodeIndex) -> Vec<NodeIndex> {
+        self.edges
+            .iter()
+            .filter(|e| e.from == node_idx)
+            .map(|e| e.to)
+            .collect()
+    }
+
+    pub fn nodes_that_can_reach(&self, node_idx: NodeIndex) -> Vec<NodeIndex> {
+        self.edges
+            .iter()
+            .filter(|e| e.to == node_idx)
+            .map(|e| e.from)
+            .collect()
+    }
+
+    pub fn boundary(&self) -> Option<Vec<NodeIndex>> {
+        let froms: HashSet<usize> = self.edges.iter().map(|e| e.from).collect();
+
+        let b: Vec<NodeIndex> = (0..self.nodes.len())
+            .filter(|node_idx| !froms.contains(node_idx))
+            .collect();
+
+        if b.is_empty() {
+            None
+        } else {
+            Some(b)
+        }
+    }
+
+    // Copy the arena graph into a heap-allocated `Graph`. Node and edge
+    // indexes are preserved.
+    pub fn to_graph(&self) -> Graph {
+        let mut g = Graph::new();
+        for data in self.nodes.iter() {
+            g.add_node(data.to_node());
+        }
+        for edge in self.edges.iter() {
+            g.add_edge(Edge {
+                from: edge.from,
+                to: edge.to,
+            });
+        }
+        g
+    }
+
+    // `data` must not be in the graph yet.
+    fn push_node(&mut self, data: DataRef<'bump>) -> NodeIndex {
+        self.nodes.push(data);
+        self.node_lookup.catch_up(&self.nodes);
+        self.nodes.len() - 1
+    }
+}
+
+impl GraphBase for ArenaGraph<'_> {
+    fn node_bound(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+impl IntoNodeIdentifiers for ArenaGraph<'_> {}
+
+// Scans the edges like `reachable_nodes_from`, without collecting them.
+impl Neighbors for ArenaGraph<'_> {
+    fn neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.edges
+            .iter()
+            .filter(move |e| e.from == node_idx)
+            .map(|e| e.to)
+    }
+}
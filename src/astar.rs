@@ -0,0 +1,74 @@
+use std::collections::BinaryHeap;
+
+use crate::graph::{Edge, Graph, NodeIndex};
+use crate::measure::{Measure, MinScored};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    A* search from `start` to `goal`.
+
+    `edge_cost` gives the cost of going through an edge (it gets the edge and
+    its payload, so it works for unweighted graphs too), can be any `Measure`
+    and must not be negative. `heuristic` estimates the remaining cost from a node to `goal`,
+    it must never overestimate it or the returned path may not be the
+    shortest one. With `|_| 0` this is just Dijkstra.
+
+    Returns the total cost and the nodes of the path, `start` and `goal`
+    included. Nodes are expanded from the per-node edge lists as they come
+    off the heap, so a well guided search never looks at most of the graph.
+    */
+    pub fn astar<K: Measure>(
+        &self,
+        start: NodeIndex,
+        goal: NodeIndex,
+        edge_cost: impl Fn(&Edge, &E) -> K,
+        heuristic: impl Fn(NodeIndex) -> K,
+    ) -> Option<(K, Vec<NodeIndex>)> {
+        let n = self.nodes.len();
+        if start >= n || goal >= n {
+            return None;
+        }
+
+        // cost of the best known path from `start` to every node
+        let mut costs: Vec<Option<K>> = vec![None; n];
+        let mut predecessors: Vec<Option<NodeIndex>> = vec![None; n];
+        // ((estimated total cost, cost so far), node)
+        let mut heap: BinaryHeap<MinScored<(K, K)>> = BinaryHeap::new();
+
+        costs[start] = Some(K::zero());
+        heap.push(MinScored((heuristic(start), K::zero()), start));
+
+        while let Some(MinScored((_, cost), node_idx)) = heap.pop() {
+            if node_idx == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while let Some(pred) = predecessors[current] {
+                    path.push(pred);
+                    current = pred;
+                }
+                path.reverse();
+                return Some((cost, path));
+            }
+
+            // a better path to this node was found after this entry was pushed
+            if costs[node_idx].is_some_and(|best| cost > best) {
+                continue;
+            }
+
+            for (edge_idx, succ) in self.arcs_from(node_idx) {
+                let arc = Edge {
+                    from: node_idx,
+                    to: succ,
+                };
+                let candidate = cost.add(edge_cost(&arc, &self.edge_weights[edge_idx]));
+                if costs[succ].is_none_or(|current| candidate < current) {
+                    costs[succ] = Some(candidate);
+                    predecessors[succ] = Some(node_idx);
+                    heap.push(MinScored((candidate.add(heuristic(succ)), candidate), succ));
+                }
+            }
+        }
+
+        None
+    }
+}
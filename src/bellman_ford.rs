@@ -0,0 +1,119 @@
+use std::fmt;
+
+use crate::dijkstra::ShortestPaths;
+use crate::graph::{Graph, NodeIndex};
+use crate::measure::Measure;
+
+// A cycle with a negative total cost, reachable from the source. `cycle`
+// lists its nodes in edge order, the last one has an edge back to the first.
+#[derive(PartialEq, Eq, Debug)]
+pub struct NegativeCycle {
+    pub cycle: Vec<NodeIndex>,
+}
+
+impl fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "negative cycle through nodes {:?}", self.cycle)
+    }
+}
+
+impl std::error::Error for NegativeCycle {}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Measure,
+{
+    /*
+    Bellman-Ford, using the edge payloads as costs. Slower than `dijkstra`
+    (O(V * E)) but works with negative costs. Nodes that can't be reached
+    from `source` end up with a `None` distance.
+
+    If a negative cycle can be reached from `source` the distances are
+    meaningless, so one of those cycles is returned instead. Paths whose cost
+    overflows (see `Measure::checked_add`) are ignored.
+    */
+    pub fn bellman_ford(&self, source: NodeIndex) -> Result<ShortestPaths<E>, NegativeCycle> {
+        let n = self.nodes.len();
+        let mut distances: Vec<Option<E>> = vec![None; n];
+        let mut predecessors: Vec<Option<NodeIndex>> = vec![None; n];
+
+        if source < n {
+            distances[source] = Some(E::zero());
+        }
+
+        // after `i` rounds every shortest path with up to `i` edges is known,
+        // without negative cycles no shortest path has more than n - 1 edges
+        for _ in 1..n {
+            if self
+                .relax_edges(&mut distances, &mut predecessors, |weight| *weight)
+                .is_none()
+            {
+                break;
+            }
+        }
+
+        // one more round, anything that still improves is on (or after) a
+        // negative cycle
+        if let Some(node_idx) =
+            self.relax_edges(&mut distances, &mut predecessors, |weight| *weight)
+        {
+            return Err(NegativeCycle {
+                cycle: cycle_from(&predecessors, node_idx),
+            });
+        }
+
+        Ok(ShortestPaths {
+            source,
+            distances,
+            predecessors,
+        })
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // One pass over every edge, `cost` turning payloads into costs. Returns
+    // the last node whose distance changed, if any.
+    pub(crate) fn relax_edges<K: Measure>(
+        &self,
+        distances: &mut [Option<K>],
+        predecessors: &mut [Option<NodeIndex>],
+        cost: impl Fn(&E) -> K,
+    ) -> Option<NodeIndex> {
+        let mut changed = None;
+
+        for (edge_idx, arc) in self.arcs() {
+            let Some(from_cost) = distances[arc.from] else {
+                continue;
+            };
+            let Some(candidate) = from_cost.checked_add(cost(&self.edge_weights[edge_idx])) else {
+                continue;
+            };
+            if distances[arc.to].is_none_or(|current| candidate < current) {
+                distances[arc.to] = Some(candidate);
+                predecessors[arc.to] = Some(arc.from);
+                changed = Some(arc.to);
+            }
+        }
+
+        changed
+    }
+}
+
+// `start` was relaxed in the extra round, so following its predecessors
+// n times is guaranteed to land inside the cycle. From there, walk until
+// we get back to where we were.
+pub(crate) fn cycle_from(predecessors: &[Option<NodeIndex>], start: NodeIndex) -> Vec<NodeIndex> {
+    let mut inside = start;
+    for _ in 0..predecessors.len() {
+        inside = predecessors[inside].expect("relaxed nodes have a predecessor");
+    }
+
+    let mut cycle = vec![inside];
+    let mut current = predecessors[inside].unwrap();
+    while current != inside {
+        cycle.push(current);
+        current = predecessors[current].unwrap();
+    }
+    cycle.reverse();
+    cycle
+}
@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+
+use crate::graph::{Adjacency, Graph, NodeIndex};
+
+/*
+Lazy breadth-first traversal: yields nodes one at a time (start included),
+only exploring as far as it's pulled.
+
+The visited set outlives a single traversal: `move_to` starts again from
+another node but skips everything already yielded, so sweeping the whole
+graph component by component visits every node once:
+
+    let mut bfs = graph.bfs(0);
+    for start in 0..graph.node_count() {
+        bfs.move_to(start);
+        let component: Vec<NodeIndex> = bfs.by_ref().collect();
+        ...
+    }
+
+`reset` forgets the visited set. Borrows the graph, so it can't change while
+the traversal is alive.
+*/
+#[derive(Clone, Debug)]
+pub struct Bfs<'g, const A: usize> {
+    outgoing: &'g [Adjacency<A>],
+    visited: Vec<bool>,
+    frontier: VecDeque<NodeIndex>,
+}
+
+impl<'g, const A: usize> Bfs<'g, A> {
+    // Start a new traversal from `start`, keeping the visited set. Yields
+    // nothing if `start` was already visited (or doesn't exist).
+    pub fn move_to(&mut self, start: NodeIndex) {
+        self.frontier.clear();
+        if start < self.visited.len() && !self.visited[start] {
+            self.visited[start] = true;
+            self.frontier.push_back(start);
+        }
+    }
+
+    // Forget every visited node and stop the current traversal, call
+    // `move_to` to start another one.
+    pub fn reset(&mut self) {
+        self.visited.fill(false);
+        self.frontier.clear();
+    }
+
+    // Yielded, or queued to be.
+    pub fn is_visited(&self, node_idx: NodeIndex) -> bool {
+        self.visited.get(node_idx).copied().unwrap_or(false)
+    }
+}
+
+impl<'g, const A: usize> Iterator for Bfs<'g, A> {
+    type Item = NodeIndex;
+
+    fn next(&mut self) -> Option<NodeIndex> {
+        let node_idx = self.frontier.pop_front()?;
+        for succ in self.outgoing[node_idx].iter().copied() {
+            if !self.visited[succ] {
+                self.visited[succ] = true;
+                self.frontier.push_back(succ);
+            }
+        }
+        Some(node_idx)
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    pub fn bfs(&self, start: NodeIndex) -> Bfs<'_, A> {
+        let mut bfs = Bfs {
+            outgoing: &self.outgoing,
+            visited: vec![false; self.nodes.len()],
+            frontier: VecDeque::new(),
+        };
+        bfs.move_to(start);
+        bfs
+    }
+}
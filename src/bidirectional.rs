@@ -0,0 +1,242 @@
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::graph::{Adjacency, Graph, NodeIndex};
+use crate::measure::{Measure, MinScored};
+
+// One half of a bidirectional Dijkstra. `toward_root[node]` is the next node
+// on the way back to where this side started (the predecessor for the
+// forward search, the successor for the backward one).
+struct Frontier<K> {
+    distances: Vec<Option<K>>,
+    toward_root: Vec<Option<NodeIndex>>,
+    settled: Vec<bool>,
+    heap: BinaryHeap<MinScored<K>>,
+}
+
+impl<K: Measure> Frontier<K> {
+    fn new(n: usize, root: NodeIndex) -> Self {
+        let mut frontier = Frontier {
+            distances: vec![None; n],
+            toward_root: vec![None; n],
+            settled: vec![false; n],
+            heap: BinaryHeap::new(),
+        };
+        frontier.distances[root] = Some(K::zero());
+        frontier.heap.push(MinScored(K::zero(), root));
+        frontier
+    }
+
+    // Smallest tentative distance still in the heap, skipping stale entries.
+    fn peek(&mut self) -> Option<K> {
+        while let Some(MinScored(cost, node_idx)) = self.heap.peek() {
+            if !self.settled[*node_idx] {
+                return Some(*cost);
+            }
+            self.heap.pop();
+        }
+        None
+    }
+
+    // Settle the closest node and relax its edges. Every relaxed edge that
+    // lands on a node the other side already reached is a candidate path,
+    // `best` keeps the cheapest one as (cost, meeting node).
+    fn settle_next(
+        &mut self,
+        adjacency: &[Vec<(NodeIndex, K)>],
+        other: &Frontier<K>,
+        best: &mut Option<(K, NodeIndex)>,
+    ) {
+        let Some(MinScored(cost, node_idx)) = self.heap.pop() else {
+            return;
+        };
+        self.settled[node_idx] = true;
+
+        for (next, weight) in adjacency[node_idx].iter().copied() {
+            let candidate = cost.add(weight);
+            if self.distances[next].is_none_or(|current| candidate < current) {
+                self.distances[next] = Some(candidate);
+                self.toward_root[next] = Some(node_idx);
+                self.heap.push(MinScored(candidate, next));
+            }
+
+            if let Some(rest) = other.distances[next] {
+                let total = self.distances[next].unwrap().add(rest);
+                if best.is_none_or(|(best_cost, _)| total < best_cost) {
+                    *best = Some((total, next));
+                }
+            }
+        }
+    }
+}
+
+// `forward[node]` points back to `start` and `backward[node]` forward to `end`,
+// walk both from the meeting node and glue them together.
+fn stitch(
+    forward: &[Option<NodeIndex>],
+    backward: &[Option<NodeIndex>],
+    meeting: NodeIndex,
+) -> Vec<NodeIndex> {
+    let mut path = vec![meeting];
+    let mut current = meeting;
+    while let Some(pred) = forward[current] {
+        path.push(pred);
+        current = pred;
+    }
+    path.reverse();
+
+    current = meeting;
+    while let Some(succ) = backward[current] {
+        path.push(succ);
+        current = succ;
+    }
+    path
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Measure,
+{
+    /*
+    Same result as `dijkstra`, but searching forwards from `start` and
+    backwards from `end` (over the incoming edges) at the same time, and
+    stopping once the two searches can't find anything cheaper than the best
+    path through a node both of them reached. On large graphs each side only
+    has to explore around half the radius, which is a lot fewer nodes.
+
+    Costs must not be negative.
+    */
+    pub fn bidirectional_dijkstra(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+    ) -> Option<(E, Vec<NodeIndex>)> {
+        let n = self.nodes.len();
+        if start >= n || end >= n {
+            return None;
+        }
+        if start == end {
+            return Some((E::zero(), vec![start]));
+        }
+
+        let successors = self.weighted_successors();
+        let predecessors = self.weighted_predecessors();
+        let mut forward: Frontier<E> = Frontier::new(n, start);
+        let mut backward = Frontier::new(n, end);
+        let mut best: Option<(E, NodeIndex)> = None;
+
+        while let (Some(f), Some(b)) = (forward.peek(), backward.peek()) {
+            // any path not seen yet costs at least f + b
+            if best.is_some_and(|(cost, _)| f.add(b) >= cost) {
+                break;
+            }
+
+            if f <= b {
+                forward.settle_next(&successors, &backward, &mut best);
+            } else {
+                backward.settle_next(&predecessors, &forward, &mut best);
+            }
+        }
+
+        let (cost, meeting) = best?;
+        Some((
+            cost,
+            stitch(&forward.toward_root, &backward.toward_root, meeting),
+        ))
+    }
+
+    // (predecessor, cost) pairs of the incoming edges of every node.
+    pub(crate) fn weighted_predecessors(&self) -> Vec<Vec<(NodeIndex, E)>> {
+        let mut predecessors = vec![Vec::new(); self.nodes.len()];
+        for (edge_idx, arc) in self.arcs() {
+            predecessors[arc.to].push((arc.from, self.edge_weights[edge_idx]));
+        }
+        predecessors
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Fewest-edges path from `start` to `end`, both included, ignoring edge
+    payloads. A BFS from each end, always growing the smaller frontier by a
+    whole level, until they touch.
+    */
+    pub fn bidirectional_bfs(&self, start: NodeIndex, end: NodeIndex) -> Option<Vec<NodeIndex>> {
+        let n = self.nodes.len();
+        if start >= n || end >= n {
+            return None;
+        }
+        if start == end {
+            return Some(vec![start]);
+        }
+
+        let mut forward_depth: Vec<Option<usize>> = vec![None; n];
+        let mut backward_depth: Vec<Option<usize>> = vec![None; n];
+        let mut forward_parent: Vec<Option<NodeIndex>> = vec![None; n];
+        let mut backward_parent: Vec<Option<NodeIndex>> = vec![None; n];
+        forward_depth[start] = Some(0);
+        backward_depth[end] = Some(0);
+        let mut forward_queue = VecDeque::from([start]);
+        let mut backward_queue = VecDeque::from([end]);
+
+        while !forward_queue.is_empty() && !backward_queue.is_empty() {
+            let meeting = if forward_queue.len() <= backward_queue.len() {
+                bfs_level(
+                    &self.outgoing,
+                    &mut forward_queue,
+                    &mut forward_depth,
+                    &mut forward_parent,
+                    &backward_depth,
+                )
+            } else {
+                bfs_level(
+                    &self.incoming,
+                    &mut backward_queue,
+                    &mut backward_depth,
+                    &mut backward_parent,
+                    &forward_depth,
+                )
+            };
+
+            if let Some(meeting) = meeting {
+                return Some(stitch(&forward_parent, &backward_parent, meeting));
+            }
+        }
+
+        None
+    }
+}
+
+// Expand every node of the current level. Finishing the level before
+// returning matters: the first node seen by both sides isn't necessarily
+// on a shortest path, the one with the smallest total depth is.
+fn bfs_level<const A: usize>(
+    adjacency: &[Adjacency<A>],
+    queue: &mut VecDeque<NodeIndex>,
+    depth: &mut [Option<usize>],
+    parent: &mut [Option<NodeIndex>],
+    other_depth: &[Option<usize>],
+) -> Option<NodeIndex> {
+    let mut meeting: Option<(usize, NodeIndex)> = None;
+
+    for _ in 0..queue.len() {
+        let node_idx = queue.pop_front().unwrap();
+        let next_depth = depth[node_idx].unwrap() + 1;
+
+        for next in adjacency[node_idx].iter().copied() {
+            if depth[next].is_some() {
+                continue;
+            }
+            depth[next] = Some(next_depth);
+            parent[next] = Some(node_idx);
+            queue.push_back(next);
+
+            if let Some(rest) = other_depth[next] {
+                if meeting.is_none_or(|(total, _)| next_depth + rest < total) {
+                    meeting = Some((next_depth + rest, next));
+                }
+            }
+        }
+    }
+
+    meeting.map(|(_, node_idx)| node_idx)
+}
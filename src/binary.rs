@@ -0,0 +1,363 @@
+use std::fmt;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::Path;
+
+use crate::graph::{DataRef, Edge, EdgeIndex, Graph, GraphKind, Node};
+use crate::hash::fnv1a;
+
+/*
+Compact binary format, for graphs too big for the text formats:
+
+    magic "RGBF", version (1 byte), kind (1 byte)
+    node count, then every node payload
+    edge count, then every edge as (from delta, to), sorted
+    every edge payload, in the same order
+    attribute count, then every key and value
+    turn restriction count, then every (in edge, out edge)
+    FNV-1a 64 checksum of everything above (8 bytes, little-endian)
+
+Counts, indexes and lengths are LEB128 varints and payloads are length
+prefixed, so a reader can skip what it doesn't understand. Edges are sorted by
+`(from, to)` (parallel edges keep their order) and `from` is stored as the
+difference with the previous edge, `to` too when `from` didn't change, which
+makes most of them a couple of bytes. Sorting renumbers the edges: after
+loading, edge indexes (and turn restrictions, which follow their edges) are in
+that order. The checksum catches truncated and corrupted files, it's not
+meant to stop someone crafting one on purpose.
+*/
+
+const MAGIC: &[u8; 4] = b"RGBF";
+const VERSION: u8 = 1;
+
+// Node and edge payloads that can be written in the binary format.
+pub trait BinaryData: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    // `None` if the bytes aren't a valid payload.
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+impl BinaryData for () {
+    fn encode(&self, _: &mut Vec<u8>) {}
+
+    fn decode(bytes: &[u8]) -> Option<()> {
+        bytes.is_empty().then_some(())
+    }
+}
+
+impl BinaryData for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<String> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+// The layout of `Node::to_bytes`.
+impl BinaryData for Node {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.write_bytes(out);
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Node> {
+        DataRef::from_bytes(bytes).map(DataRef::to_node)
+    }
+}
+
+macro_rules! impl_binary_data {
+    ($($t:ty),*) => {
+        $(
+            impl BinaryData for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(bytes: &[u8]) -> Option<Self> {
+                    Some(<$t>::from_le_bytes(bytes.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_data!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+// Bytes `from_bytes` couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct BinaryError {
+    pub message: String,
+}
+
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid binary graph: {}", self.message)
+    }
+}
+
+impl std::error::Error for BinaryError {}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: BinaryData,
+    E: BinaryData,
+{
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(match self.kind {
+            GraphKind::Directed => 0,
+            GraphKind::Undirected => 1,
+        });
+
+        write_varint(&mut out, self.nodes.len() as u64);
+        let mut payload = Vec::new();
+        for node in self.nodes.iter() {
+            payload.clear();
+            node.encode(&mut payload);
+            write_bytes(&mut out, &payload);
+        }
+
+        let mut order: Vec<EdgeIndex> = (0..self.edges.len()).collect();
+        order.sort_by_key(|edge_idx| (self.edges[*edge_idx].from, self.edges[*edge_idx].to));
+        write_varint(&mut out, order.len() as u64);
+        let mut previous = Edge { from: 0, to: 0 };
+        for edge_idx in order.iter() {
+            let edge = &self.edges[*edge_idx];
+            write_varint(&mut out, (edge.from - previous.from) as u64);
+            if edge.from == previous.from {
+                write_varint(&mut out, (edge.to - previous.to) as u64);
+            } else {
+                write_varint(&mut out, edge.to as u64);
+            }
+            previous = Edge {
+                from: edge.from,
+                to: edge.to,
+            };
+        }
+        for edge_idx in order.iter() {
+            payload.clear();
+            self.edge_weights[*edge_idx].encode(&mut payload);
+            write_bytes(&mut out, &payload);
+        }
+
+        write_varint(&mut out, self.attrs.len() as u64);
+        for (key, value) in self.attrs.iter() {
+            write_bytes(&mut out, key.as_bytes());
+            write_bytes(&mut out, value.as_bytes());
+        }
+
+        let mut new_idx = vec![0; order.len()];
+        for (new, old) in order.iter().enumerate() {
+            new_idx[*old] = new;
+        }
+        let mut restrictions: Vec<(EdgeIndex, EdgeIndex)> = self
+            .turn_restrictions
+            .iter()
+            .map(|(in_edge, out_edge)| (new_idx[*in_edge], new_idx[*out_edge]))
+            .collect();
+        restrictions.sort_unstable();
+        write_varint(&mut out, restrictions.len() as u64);
+        for (in_edge, out_edge) in restrictions {
+            write_varint(&mut out, in_edge as u64);
+            write_varint(&mut out, out_edge as u64);
+        }
+
+        let checksum = fnv1a(&out);
+        out.extend_from_slice(&checksum.to_le_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BinaryError>
+    where
+        N: Hash,
+    {
+        read_graph(bytes, N::decode, E::decode)
+    }
+
+    // `to_bytes`, into a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    // `from_bytes`, from a file. A corrupted file is an `InvalidData` error.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self>
+    where
+        N: Hash,
+    {
+        let bytes = fs::read(path)?;
+        Self::from_bytes(&bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+// The nodes and edge payloads are decoded with `node` and `weight`, which
+// get slices of `bytes`.
+fn read_graph<'a, N: Hash, E, const A: usize>(
+    bytes: &'a [u8],
+    node: impl Fn(&'a [u8]) -> Option<N>,
+    weight: impl Fn(&'a [u8]) -> Option<E>,
+) -> Result<Graph<N, E, A>, BinaryError> {
+    let error = |message: &str| BinaryError {
+        message: message.to_string(),
+    };
+    if bytes.len() < MAGIC.len() + 2 + 8 || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(error("not a binary graph"));
+    }
+    let (content, checksum) = bytes.split_at(bytes.len() - 8);
+    if fnv1a(content).to_le_bytes() != checksum {
+        return Err(error("checksum mismatch, the data is corrupted"));
+    }
+
+    let mut reader = Reader {
+        bytes: content,
+        pos: MAGIC.len(),
+    };
+    if reader.byte()? != VERSION {
+        return Err(error("unsupported version"));
+    }
+    let kind = match reader.byte()? {
+        0 => GraphKind::Directed,
+        1 => GraphKind::Undirected,
+        _ => return Err(error("unknown graph kind")),
+    };
+    let mut graph = Graph::with_kind(kind);
+
+    let node_count = reader.count()?;
+    for _ in 0..node_count {
+        let node = node(reader.bytes()?).ok_or_else(|| error("invalid node payload"))?;
+        graph.nodes.push(node);
+    }
+    graph.node_lookup.catch_up(&graph.nodes);
+
+    let edge_count = reader.count()?;
+    let mut previous = Edge { from: 0, to: 0 };
+    for _ in 0..edge_count {
+        let from_delta = reader.varint()? as usize;
+        let to = reader.varint()? as usize;
+        let edge = Edge {
+            from: previous.from.saturating_add(from_delta),
+            to: if from_delta == 0 {
+                previous.to.saturating_add(to)
+            } else {
+                to
+            },
+        };
+        if edge.from >= node_count || edge.to >= node_count {
+            return Err(error("edge to a node that doesn't exist"));
+        }
+        previous = Edge {
+            from: edge.from,
+            to: edge.to,
+        };
+        graph.edges.push(edge);
+    }
+    for _ in 0..edge_count {
+        let weight = weight(reader.bytes()?).ok_or_else(|| error("invalid edge payload"))?;
+        graph.edge_weights.push(weight);
+    }
+
+    for _ in 0..reader.count()? {
+        let key = String::decode(reader.bytes()?);
+        let value = String::decode(reader.bytes()?);
+        match (key, value) {
+            (Some(key), Some(value)) => graph.attrs.insert(key, value),
+            _ => return Err(error("attribute that isn't UTF-8")),
+        };
+    }
+
+    for _ in 0..reader.count()? {
+        let in_edge = reader.varint()? as usize;
+        let out_edge = reader.varint()? as usize;
+        if in_edge >= edge_count || out_edge >= edge_count {
+            return Err(error("turn restriction on an edge that doesn't exist"));
+        }
+        if graph.edges[in_edge].to != graph.edges[out_edge].from {
+            return Err(error("turn restriction between edges that don't meet"));
+        }
+        graph.turn_restrictions.insert((in_edge, out_edge));
+    }
+
+    if reader.pos != content.len() {
+        return Err(error("trailing bytes"));
+    }
+    graph.rebuild_adjacency();
+    Ok(graph)
+}
+
+impl<'a, E, const A: usize> Graph<DataRef<'a>, E, A>
+where
+    E: BinaryData,
+{
+    // `from_bytes` for graphs of `Node`s, with text and blobs borrowed from
+    // `bytes` instead of copied (`from_bytes` and serde copy them), so
+    // loading a graph of big blobs doesn't need twice their size in memory.
+    pub fn from_bytes_borrowed(bytes: &'a [u8]) -> Result<Self, BinaryError> {
+        read_graph(bytes, DataRef::from_bytes, E::decode)
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, BinaryError> {
+        let byte = *self.bytes.get(self.pos).ok_or_else(truncated)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u64, BinaryError> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            v |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(BinaryError {
+            message: "varint too long".to_string(),
+        })
+    }
+
+    // A count of things that take at least a byte each, so a corrupted
+    // count can't make us allocate more than what's left.
+    fn count(&mut self) -> Result<usize, BinaryError> {
+        let count = self.varint()?;
+        if count > (self.bytes.len() - self.pos) as u64 {
+            return Err(truncated());
+        }
+        Ok(count as usize)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], BinaryError> {
+        let len = self.count()?;
+        let bytes = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+}
+
+fn truncated() -> BinaryError {
+    BinaryError {
+        message: "truncated data".to_string(),
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::hash::Hash;
+
+use crate::graph::{Edge, EdgeIndex, Graph, GraphKind, NodeIndex, DEFAULT_INLINE_ADJACENCY};
+
+// Payload of the nodes of `BipartiteGraph::graph()`.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum Side<L, R> {
+    Left(L),
+    Right(R),
+}
+
+// Handle to a left node of a `BipartiteGraph`, `index()` is its index in
+// `graph()`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct LeftNode(NodeIndex);
+
+// Handle to a right node of a `BipartiteGraph`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct RightNode(NodeIndex);
+
+impl LeftNode {
+    pub fn index(self) -> NodeIndex {
+        self.0
+    }
+}
+
+impl RightNode {
+    pub fn index(self) -> NodeIndex {
+        self.0
+    }
+}
+
+/*
+An undirected graph with two kinds of nodes (people and the groups they
+belong to, authors and papers...) where edges always go from a left node to a
+right node. `add_edge` takes a `LeftNode` and a `RightNode`, so an edge
+between two nodes of the same side doesn't compile, and the algorithms below
+know which side every node is on without having to guess a 2-coloring.
+
+Nodes are deduplicated by value like in `Graph` (per side) and there's no
+removal, so handles stay valid. `graph()` is the whole thing as a plain
+undirected `Graph` for everything else.
+*/
+#[derive(Debug)]
+pub struct BipartiteGraph<L, R, E = (), const A: usize = DEFAULT_INLINE_ADJACENCY> {
+    graph: Graph<Side<L, R>, E, A>,
+    left: Vec<NodeIndex>,
+    right: Vec<NodeIndex>,
+}
+
+impl<L, R, E, const A: usize> BipartiteGraph<L, R, E, A> {
+    pub fn new() -> Self {
+        BipartiteGraph {
+            graph: Graph::with_kind(GraphKind::Undirected),
+            left: Vec::new(),
+            right: Vec::new(),
+        }
+    }
+
+    pub fn graph(&self) -> &Graph<Side<L, R>, E, A> {
+        &self.graph
+    }
+
+    pub fn add_left(&mut self, node: L) -> LeftNode
+    where
+        L: Hash + PartialEq,
+        R: Hash + PartialEq,
+    {
+        let node_count = self.graph.nodes.len();
+        let node_idx = self.graph.add_node(Side::Left(node));
+        if node_idx == node_count {
+            self.left.push(node_idx);
+        }
+        LeftNode(node_idx)
+    }
+
+    pub fn add_right(&mut self, node: R) -> RightNode
+    where
+        L: Hash + PartialEq,
+        R: Hash + PartialEq,
+    {
+        let node_count = self.graph.nodes.len();
+        let node_idx = self.graph.add_node(Side::Right(node));
+        if node_idx == node_count {
+            self.right.push(node_idx);
+        }
+        RightNode(node_idx)
+    }
+
+    pub fn add_edge(&mut self, left: LeftNode, right: RightNode) -> EdgeIndex
+    where
+        E: Default,
+    {
+        self.graph.add_edge(Edge {
+            from: left.0,
+            to: right.0,
+        })
+    }
+
+    pub fn add_weighted_edge(&mut self, left: LeftNode, right: RightNode, weight: E) -> EdgeIndex {
+        self.graph.add_weighted_edge(
+            Edge {
+                from: left.0,
+                to: right.0,
+            },
+            weight,
+        )
+    }
+
+    pub fn left(&self, node: LeftNode) -> &L {
+        match &self.graph.nodes[node.0] {
+            Side::Left(payload) => payload,
+            Side::Right(_) => unreachable!("left handle to a right node"),
+        }
+    }
+
+    pub fn right(&self, node: RightNode) -> &R {
+        match &self.graph.nodes[node.0] {
+            Side::Right(payload) => payload,
+            Side::Left(_) => unreachable!("right handle to a left node"),
+        }
+    }
+
+    pub fn left_nodes(&self) -> impl Iterator<Item = LeftNode> + '_ {
+        self.left.iter().map(|node_idx| LeftNode(*node_idx))
+    }
+
+    pub fn right_nodes(&self) -> impl Iterator<Item = RightNode> + '_ {
+        self.right.iter().map(|node_idx| RightNode(*node_idx))
+    }
+
+    pub fn neighbors_of_left(&self, node: LeftNode) -> impl Iterator<Item = RightNode> + '_ {
+        self.graph.neighbors(node.0).map(RightNode)
+    }
+
+    pub fn neighbors_of_right(&self, node: RightNode) -> impl Iterator<Item = LeftNode> + '_ {
+        self.graph.neighbors(node.0).map(LeftNode)
+    }
+
+    /*
+    A maximum matching (as many edges as possible, no two sharing a node),
+    with Hopcroft-Karp in O(E * sqrt(V)). Pairs are ordered by left node.
+
+    Every phase finds the layers of the shortest augmenting paths with a BFS
+    from the unmatched left nodes, then flips disjoint augmenting paths
+    along those layers with a DFS (iterative, long paths don't blow the
+    stack), until no augmenting path is left.
+    */
+    pub fn maximum_matching(&self) -> Vec<(LeftNode, RightNode)> {
+        const UNREACHED: usize = usize::MAX;
+
+        // positions in `self.left` / `self.right` instead of graph indexes
+        let mut position = vec![0; self.graph.nodes.len()];
+        for (pos, node_idx) in self.left.iter().chain(&self.right).enumerate() {
+            position[*node_idx] = pos;
+        }
+        let adjacency: Vec<Vec<usize>> = self
+            .left
+            .iter()
+            .map(|node_idx| {
+                self.graph
+                    .neighbors(*node_idx)
+                    .map(|v| position[v] - self.left.len())
+                    .collect()
+            })
+            .collect();
+
+        let mut left_mate: Vec<Option<usize>> = vec![None; self.left.len()];
+        let mut right_mate: Vec<Option<usize>> = vec![None; self.right.len()];
+        let mut layer = vec![UNREACHED; self.left.len()];
+        loop {
+            let mut queue = VecDeque::new();
+            for (u, mate) in left_mate.iter().enumerate() {
+                layer[u] = match mate {
+                    None => {
+                        queue.push_back(u);
+                        0
+                    }
+                    Some(_) => UNREACHED,
+                };
+            }
+            let mut augmentable = false;
+            while let Some(u) = queue.pop_front() {
+                for v in &adjacency[u] {
+                    match right_mate[*v] {
+                        None => augmentable = true,
+                        Some(w) if layer[w] == UNREACHED => {
+                            layer[w] = layer[u] + 1;
+                            queue.push_back(w);
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+            if !augmentable {
+                break;
+            }
+
+            let mut next = vec![0; self.left.len()];
+            for start in 0..self.left.len() {
+                if left_mate[start].is_some() {
+                    continue;
+                }
+                // `stack[i]` was reached through the right node `via[i - 1]`
+                let mut stack = vec![start];
+                let mut via: Vec<usize> = Vec::new();
+                while let Some(&u) = stack.last() {
+                    let Some(&v) = adjacency[u].get(next[u]) else {
+                        // dead end, don't come back here this phase
+                        layer[u] = UNREACHED;
+                        stack.pop();
+                        via.pop();
+                        continue;
+                    };
+                    next[u] += 1;
+                    match right_mate[v] {
+                        None => {
+                            via.push(v);
+                            for (u, v) in stack.iter().zip(&via) {
+                                left_mate[*u] = Some(*v);
+                                right_mate[*v] = Some(*u);
+                            }
+                            break;
+                        }
+                        Some(w) if layer[w] == layer[u] + 1 => {
+                            stack.push(w);
+                            via.push(v);
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+
+        left_mate
+            .iter()
+            .enumerate()
+            .filter_map(|(u, v)| Some((LeftNode(self.left[u]), RightNode(self.right[(*v)?]))))
+            .collect()
+    }
+
+    /*
+    The left projection: left nodes linked when they share at least one right
+    neighbor, weighted by how many they share (two authors and the number of
+    papers they wrote together). The payload of every node is its handle.
+    */
+    pub fn project_left(&self) -> Graph<LeftNode, usize, A> {
+        project(&self.left, &self.right, &self.graph, LeftNode)
+    }
+
+    // `project_left`, the other way around.
+    pub fn project_right(&self) -> Graph<RightNode, usize, A> {
+        project(&self.right, &self.left, &self.graph, RightNode)
+    }
+}
+
+impl<L, R, E, const A: usize> Default for BipartiteGraph<L, R, E, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn project<N, P, E, const A: usize>(
+    side: &[NodeIndex],
+    other: &[NodeIndex],
+    graph: &Graph<P, E, A>,
+    handle: fn(NodeIndex) -> N,
+) -> Graph<N, usize, A> {
+    let mut position = vec![0; graph.nodes.len()];
+    for (pos, node_idx) in side.iter().enumerate() {
+        position[*node_idx] = pos;
+    }
+
+    let mut shared: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    for node_idx in other {
+        let mut neighbors: Vec<usize> = graph.neighbors(*node_idx).map(|v| position[v]).collect();
+        neighbors.sort_unstable();
+        for (i, a) in neighbors.iter().enumerate() {
+            for b in &neighbors[i + 1..] {
+                *shared.entry((*a, *b)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut projected = Graph::with_kind(GraphKind::Undirected);
+    projected.nodes = side.iter().map(|node_idx| handle(*node_idx)).collect();
+    for ((from, to), count) in shared {
+        projected.edges.push(Edge { from, to });
+        projected.edge_weights.push(count);
+    }
+    projected.rebuild_adjacency();
+    projected
+}
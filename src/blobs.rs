@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/*
+Content-addressed storage for big binary payloads that repeat a lot (the same
+artifact showing up in thousands of provenance records...). Identical bytes
+are stored once, every node gets a cheap `SharedBlob` handle to them:
+
+    struct Artifact {
+        step: String,
+        output: SharedBlob,
+    }
+
+    let mut store = BlobStore::new();
+    let idx = g.add_node_unchecked(Artifact {
+        step: "build".to_string(),
+        output: store.intern(&bytes),
+    });
+    ...
+    store.release(g.remove_node(idx).unwrap().output);
+
+The store counts the handles still around, a blob is freed when its last
+handle is `release`d. Handles that are simply dropped (with the graph, say)
+are cleaned up by the next `purge`.
+*/
+#[derive(Debug, Default)]
+pub struct BlobStore {
+    blobs: HashSet<Arc<[u8]>>,
+}
+
+// A reference to bytes kept in a `BlobStore`. Compares, hashes and orders
+// by content.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct SharedBlob(Arc<[u8]>);
+
+impl Deref for SharedBlob {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl BlobStore {
+    pub fn new() -> Self {
+        BlobStore::default()
+    }
+
+    // A handle to `bytes`, copying them into the store only if they aren't
+    // there yet.
+    pub fn intern(&mut self, bytes: &[u8]) -> SharedBlob {
+        match self.blobs.get(bytes) {
+            Some(blob) => SharedBlob(Arc::clone(blob)),
+            None => {
+                let blob: Arc<[u8]> = Arc::from(bytes);
+                self.blobs.insert(Arc::clone(&blob));
+                SharedBlob(blob)
+            }
+        }
+    }
+
+    // Give a handle back, returns `true` if it was the last one and the blob
+    // was freed. Handles from another store (even to the same bytes) are
+    // just dropped.
+    pub fn release(&mut self, blob: SharedBlob) -> bool {
+        let SharedBlob(blob) = blob;
+        let Some(stored) = self.blobs.get(&blob[..]) else {
+            return false;
+        };
+        // one reference for the store, one for the handle being released
+        if !Arc::ptr_eq(stored, &blob) || Arc::strong_count(&blob) > 2 {
+            return false;
+        }
+        self.blobs.remove(&blob[..])
+    }
+
+    // Free every blob without handles left, returns how many were freed.
+    pub fn purge(&mut self) -> usize {
+        let before = self.blobs.len();
+        self.blobs.retain(|blob| Arc::strong_count(blob) > 1);
+        before - self.blobs.len()
+    }
+
+    // How many handles to `bytes` are alive, 0 if they aren't stored.
+    pub fn references(&self, bytes: &[u8]) -> usize {
+        self.blobs
+            .get(bytes)
+            .map_or(0, |blob| Arc::strong_count(blob) - 1)
+    }
+
+    pub fn contains(&self, bytes: &[u8]) -> bool {
+        self.blobs.contains(bytes)
+    }
+
+    // Distinct blobs stored.
+    pub fn len(&self) -> usize {
+        self.blobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty()
+    }
+
+    // Total size of the stored blobs, each counted once.
+    pub fn stored_bytes(&self) -> usize {
+        self.blobs.iter().map(|blob| blob.len()).sum()
+    }
+}
@@ -0,0 +1,100 @@
+use std::hash::Hash;
+
+use crate::graph::{Edge, Graph, GraphKind, DEFAULT_INLINE_ADJACENCY};
+
+/*
+Fluent construction of a `Graph`, nodes and edges named by payload:
+
+    let g: Graph = GraphBuilder::new()
+        .directed(false)
+        .node("lonely")
+        .edge("a", "b")
+        .edge("b", "c")
+        .build();
+
+Nodes get their indexes in order of first appearance, in `node` or in an
+edge, and are deduplicated like in `Graph::add_node`. Nothing is built
+before `build`, so `directed` can come anywhere in the chain.
+*/
+#[derive(Debug)]
+pub struct GraphBuilder<N, E = (), const A: usize = DEFAULT_INLINE_ADJACENCY> {
+    kind: GraphKind,
+    items: Vec<Item<N, E>>,
+}
+
+#[derive(Debug)]
+enum Item<N, E> {
+    Node(N),
+    Edge(N, N, Option<E>),
+}
+
+impl<N, E, const A: usize> GraphBuilder<N, E, A> {
+    pub fn new() -> Self {
+        GraphBuilder {
+            kind: GraphKind::Directed,
+            items: Vec::new(),
+        }
+    }
+
+    // Directed by default.
+    pub fn directed(mut self, directed: bool) -> Self {
+        self.kind = if directed {
+            GraphKind::Directed
+        } else {
+            GraphKind::Undirected
+        };
+        self
+    }
+
+    // A node without edges, or a node that should get its index before the
+    // edges that mention it.
+    pub fn node(mut self, node: impl Into<N>) -> Self {
+        self.items.push(Item::Node(node.into()));
+        self
+    }
+
+    // An edge with a default payload, see `Graph::add_edge`.
+    pub fn edge(mut self, from: impl Into<N>, to: impl Into<N>) -> Self {
+        self.items.push(Item::Edge(from.into(), to.into(), None));
+        self
+    }
+
+    // See `Graph::add_weighted_edge`, a repeated edge keeps the last weight.
+    pub fn weighted_edge(mut self, from: impl Into<N>, to: impl Into<N>, weight: E) -> Self {
+        self.items
+            .push(Item::Edge(from.into(), to.into(), Some(weight)));
+        self
+    }
+
+    pub fn build(self) -> Graph<N, E, A>
+    where
+        N: Hash + PartialEq,
+        E: Default,
+    {
+        let mut g = Graph::with_kind(self.kind);
+        for item in self.items {
+            match item {
+                Item::Node(node) => {
+                    g.add_node(node);
+                }
+                Item::Edge(from, to, weight) => {
+                    let edge = Edge {
+                        from: g.add_node(from),
+                        to: g.add_node(to),
+                    };
+                    match weight {
+                        Some(weight) => g.add_weighted_edge(edge, weight),
+                        None => g.add_edge(edge),
+                    };
+                }
+            }
+        }
+        g
+    }
+}
+
+impl<N, E, const A: usize> Default for GraphBuilder<N, E, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::graph::{Edge, EdgeIndex, Graph, GraphKind, NodeIndex};
+use crate::measure::Measure;
+
+// Edge payload of `aggregate_parallel_edges`: the payloads of all the edges
+// that were merged, in edge order.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bundle<E> {
+    pub weights: Vec<E>,
+}
+
+impl<E> Bundle<E> {
+    // How many edges were merged, e.g. for a "x3" label.
+    pub fn count(&self) -> usize {
+        self.weights.len()
+    }
+}
+
+impl<E: Measure> Bundle<E> {
+    pub fn total(&self) -> E {
+        self.weights
+            .iter()
+            .fold(E::zero(), |total, weight| total.add(*weight))
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Merge every set of parallel edges (same `from` and `to`, or the same two
+    nodes either way in an undirected graph) into a single edge that keeps
+    all their payloads, so dense multigraphs stay readable once drawn. Nodes
+    and the graph kind are copied as they are, edges keep the position of
+    the first edge of their bundle.
+
+    `add_edge` never creates parallel edges, but graphs built in other ways
+    can have them (`collapse_linear_chains`, switching a graph with `a -> b`
+    and `b -> a` to undirected...).
+    */
+    pub fn aggregate_parallel_edges(&self) -> Graph<N, Bundle<E>, A>
+    where
+        N: Clone,
+        E: Clone,
+    {
+        let mut bundled: Graph<N, Bundle<E>, A> = Graph::with_kind(self.kind);
+        bundled.nodes = self.nodes.clone();
+        for group in self.parallel_edge_groups() {
+            let edge = &self.edges[group[0]];
+            bundled.edges.push(Edge {
+                from: edge.from,
+                to: edge.to,
+            });
+            bundled.edge_weights.push(Bundle {
+                weights: group
+                    .iter()
+                    .map(|edge_idx| self.edge_weights[*edge_idx].clone())
+                    .collect(),
+            });
+        }
+
+        bundled.rebuild_adjacency();
+        bundled
+    }
+
+    // Indexes of the edges `aggregate_parallel_edges` merges together, groups
+    // ordered by their first edge.
+    pub(crate) fn parallel_edge_groups(&self) -> Vec<Vec<EdgeIndex>> {
+        let mut groups: Vec<Vec<EdgeIndex>> = Vec::new();
+        let mut position: HashMap<(NodeIndex, NodeIndex), usize> = HashMap::new();
+        for (edge_idx, edge) in self.edges.iter().enumerate() {
+            let key = match self.kind {
+                GraphKind::Directed => (edge.from, edge.to),
+                GraphKind::Undirected => (edge.from.min(edge.to), edge.from.max(edge.to)),
+            };
+            match position.get(&key) {
+                Some(group) => groups[*group].push(edge_idx),
+                None => {
+                    position.insert(key, groups.len());
+                    groups.push(vec![edge_idx]);
+                }
+            }
+        }
+        groups
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use serde_json::Value;
+
+use crate::graph::{Edge, Graph, NodeIndex};
+
+/*
+`cargo metadata --format-version 1` output (the `json` feature), the crates
+of a workspace and everything they depend on:
+
+    {
+        "packages": [{ "id": "...", "name": "serde", "version": "1.0.0", "dependencies": [...] }, ...],
+        "workspace_members": ["..."],
+        "resolve": {
+            "nodes": [{ "id": "...", "deps": [{ "name": "serde", "pkg": "...", "dep_kinds": [...] }] }, ...]
+        },
+        "workspace_root": "/path/to/workspace"
+    }
+*/
+
+// A `cargo metadata` document `from_cargo_metadata` couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct CargoMetadataError {
+    pub message: String,
+}
+
+impl fmt::Display for CargoMetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid cargo metadata: {}", self.message)
+    }
+}
+
+impl std::error::Error for CargoMetadataError {}
+
+// A node of `from_cargo_metadata`, displayed as `name version`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CargoPackage {
+    // the package ID cargo uses, unique even when names and versions aren't
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub workspace_member: bool,
+}
+
+impl Display for CargoPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.version)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DependencyKind {
+    Normal,
+    Dev,
+    Build,
+}
+
+// One way a crate depends on another: its kind and the platform it's
+// limited to (`cfg(windows)`, a target triple), if any.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DependencyUse {
+    pub kind: DependencyKind,
+    pub target: Option<String>,
+}
+
+/*
+An edge of `from_cargo_metadata`. `name` is what the dependent crate calls
+it (a `package = "..."` rename, with `-` as `_` when read from `resolve`),
+`uses` every kind and target it's declared with: a crate can be both a
+normal and a dev dependency of another, that's still one edge.
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CargoDependency {
+    pub name: String,
+    pub uses: Vec<DependencyUse>,
+}
+
+impl Graph<CargoPackage, CargoDependency> {
+    /*
+    Read the output of `cargo metadata`: a node per package in `packages`
+    order, an edge from every crate to each crate it depends on. The
+    `workspace_root` becomes a graph attribute.
+
+    Edges come from the resolved graph (`resolve`), so they point to the
+    exact version picked. With `--no-deps` there's none and the declared
+    `dependencies` of every package are used instead, only those on other
+    packages of the output, found by name.
+    */
+    pub fn from_cargo_metadata(json: &str) -> Result<Self, CargoMetadataError> {
+        let error = |message: String| CargoMetadataError { message };
+        let document: Value = serde_json::from_str(json).map_err(|err| error(err.to_string()))?;
+
+        let members: Vec<&str> = match document.get("workspace_members") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(members)) => members.iter().filter_map(Value::as_str).collect(),
+            Some(_) => return Err(error("`workspace_members` should be an array".to_string())),
+        };
+        let packages = match document.get("packages") {
+            Some(Value::Array(packages)) => packages,
+            _ => return Err(error("no `packages` array".to_string())),
+        };
+
+        let mut graph = Graph::with_inline_adjacency();
+        if let Some(root) = document.get("workspace_root").and_then(Value::as_str) {
+            graph.set_attr("workspace_root", root);
+        }
+
+        let mut ids: HashMap<&str, NodeIndex> = HashMap::new();
+        let mut names: HashMap<&str, NodeIndex> = HashMap::new();
+        for package in packages {
+            let field = |name: &str| {
+                package
+                    .get(name)
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| error(format!("package without a {}: {}", name, package)))
+            };
+            let id = field("id")?;
+            let name = field("name")?;
+            if ids.contains_key(id) {
+                return Err(error(format!("duplicate package id {:?}", id)));
+            }
+            let node_idx = graph.add_node_unchecked(CargoPackage {
+                id: id.to_string(),
+                name: name.to_string(),
+                version: field("version")?.to_string(),
+                workspace_member: members.contains(&id),
+            });
+            ids.insert(id, node_idx);
+            names.entry(name).or_insert(node_idx);
+        }
+
+        match document.get("resolve") {
+            None | Some(Value::Null) => {
+                for (from, package) in packages.iter().enumerate() {
+                    let dependencies = match package.get("dependencies") {
+                        None | Some(Value::Null) => continue,
+                        Some(Value::Array(dependencies)) => dependencies,
+                        Some(_) => return Err(error("`dependencies` should be an array".into())),
+                    };
+                    for dependency in dependencies {
+                        let Some(name) = dependency.get("name").and_then(Value::as_str) else {
+                            return Err(error(format!(
+                                "dependency without a name: {}",
+                                dependency
+                            )));
+                        };
+                        let Some(to) = names.get(name).copied() else {
+                            continue;
+                        };
+                        let alias = match dependency.get("rename").and_then(Value::as_str) {
+                            Some(rename) => rename,
+                            None => name,
+                        };
+                        let used = dependency_use(dependency).map_err(error)?;
+                        graph.add_dependency(Edge { from, to }, alias, used);
+                    }
+                }
+            }
+            Some(resolve) => {
+                let nodes = match resolve.get("nodes") {
+                    Some(Value::Array(nodes)) => nodes,
+                    _ => return Err(error("`resolve` without `nodes`".to_string())),
+                };
+                let package = |id: Option<&Value>| {
+                    let id = id.and_then(Value::as_str).unwrap_or_default();
+                    ids.get(id)
+                        .copied()
+                        .ok_or_else(|| error(format!("unknown package id {:?}", id)))
+                };
+                for node in nodes {
+                    let from = package(node.get("id"))?;
+                    let Some(Value::Array(deps)) = node.get("deps") else {
+                        continue;
+                    };
+                    for dep in deps {
+                        let to = package(dep.get("pkg"))?;
+                        let alias = dep.get("name").and_then(Value::as_str).unwrap_or_default();
+                        let kinds = match dep.get("dep_kinds") {
+                            Some(Value::Array(kinds)) => kinds.as_slice(),
+                            // cargo before 1.41 only lists `dependencies`
+                            _ => &[],
+                        };
+                        if kinds.is_empty() {
+                            let used = DependencyUse {
+                                kind: DependencyKind::Normal,
+                                target: None,
+                            };
+                            graph.add_dependency(Edge { from, to }, alias, used);
+                        }
+                        for kind in kinds {
+                            let used = dependency_use(kind).map_err(error)?;
+                            graph.add_dependency(Edge { from, to }, alias, used);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(graph)
+    }
+
+    fn add_dependency(&mut self, edge: Edge, name: &str, used: DependencyUse) {
+        match self.find_edge(edge.from, edge.to) {
+            Some(edge_idx) => {
+                let uses = &mut self.edge_weights[edge_idx].uses;
+                if !uses.contains(&used) {
+                    uses.push(used);
+                }
+            }
+            None => {
+                self.add_weighted_edge(
+                    edge,
+                    CargoDependency {
+                        name: name.to_string(),
+                        uses: vec![used],
+                    },
+                );
+            }
+        }
+    }
+}
+
+// The `kind` and `target` of a dependency or of an entry of `dep_kinds`,
+// a missing or null kind is a normal dependency.
+fn dependency_use(json: &Value) -> Result<DependencyUse, String> {
+    let kind = match json.get("kind") {
+        None | Some(Value::Null) => DependencyKind::Normal,
+        Some(Value::String(kind)) if kind == "normal" => DependencyKind::Normal,
+        Some(Value::String(kind)) if kind == "dev" => DependencyKind::Dev,
+        Some(Value::String(kind)) if kind == "build" => DependencyKind::Build,
+        Some(other) => return Err(format!("unknown dependency kind {}", other)),
+    };
+    let target = json
+        .get("target")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    Ok(DependencyUse { kind, target })
+}
@@ -0,0 +1,118 @@
+use crate::graph::{Edge, EdgeIndex, Graph, NodeIndex};
+use crate::measure::Measure;
+
+// Edge payload of `collapse_linear_chains`: what an edge of the simplified
+// graph stands for.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Chain<E> {
+    // the collapsed nodes, in path order (original indexes)
+    pub inner: Vec<NodeIndex>,
+    // payloads of the original edges along the chain, one more than `inner`
+    pub weights: Vec<E>,
+}
+
+impl<E: Measure> Chain<E> {
+    // Cost of going through the whole chain.
+    pub fn cost(&self) -> E {
+        self.weights
+            .iter()
+            .fold(E::zero(), |total, weight| total.add(*weight))
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Replace every maximal path whose inner nodes have exactly one incoming
+    and one outgoing edge (`a -> x -> y -> b`) by a single edge (`a -> b`)
+    that remembers the nodes and edge payloads it replaced, so the cost of
+    going from one kept node to another doesn't change.
+
+    The payload of every node of the result is its index in this graph,
+    nodes keep their relative order. Two chains between the same nodes
+    become two parallel edges. A cycle made only of such nodes is kept as
+    its lowest node with a self-loop.
+    */
+    pub fn collapse_linear_chains(&self) -> Graph<NodeIndex, Chain<E>>
+    where
+        E: Clone,
+    {
+        let n = self.nodes.len();
+        let mut out_edges: Vec<Vec<EdgeIndex>> = vec![Vec::new(); n];
+        let mut in_degrees = vec![0; n];
+        for (edge_idx, edge) in self.edges.iter().enumerate() {
+            out_edges[edge.from].push(edge_idx);
+            in_degrees[edge.to] += 1;
+        }
+
+        let interior: Vec<bool> = (0..n)
+            .map(|node_idx| {
+                in_degrees[node_idx] == 1
+                    && out_edges[node_idx].len() == 1
+                    && self.edges[out_edges[node_idx][0]].to != node_idx
+            })
+            .collect();
+
+        // follow a chain starting with `edge_idx` up to the next kept node
+        let walk = |edge_idx: EdgeIndex, kept: &[bool]| {
+            let mut edges = vec![edge_idx];
+            let mut inner = Vec::new();
+            let mut current = self.edges[edge_idx].to;
+            while !kept[current] {
+                inner.push(current);
+                let next = out_edges[current][0];
+                edges.push(next);
+                current = self.edges[next].to;
+            }
+            (current, inner, edges)
+        };
+
+        // interior nodes that no kept node leads to are on a cycle of their
+        // own, keep the lowest one of each
+        let mut kept: Vec<bool> = interior.iter().map(|i| !i).collect();
+        let mut reached = kept.clone();
+        for start in (0..n).filter(|node_idx| kept[*node_idx]) {
+            for edge_idx in out_edges[start].iter().copied() {
+                for node_idx in walk(edge_idx, &kept).1 {
+                    reached[node_idx] = true;
+                }
+            }
+        }
+        for start in 0..n {
+            if reached[start] {
+                continue;
+            }
+            kept[start] = true;
+            reached[start] = true;
+            for node_idx in walk(out_edges[start][0], &kept).1 {
+                reached[node_idx] = true;
+            }
+        }
+
+        let mut collapsed = Graph::with_inline_adjacency();
+        let mut new_idx = vec![0; n];
+        for node_idx in (0..n).filter(|node_idx| kept[*node_idx]) {
+            new_idx[node_idx] = collapsed.nodes.len();
+            collapsed.nodes.push(node_idx);
+        }
+
+        for from in (0..n).filter(|node_idx| kept[*node_idx]) {
+            for edge_idx in out_edges[from].iter().copied() {
+                let (to, inner, edges) = walk(edge_idx, &kept);
+                collapsed.edges.push(Edge {
+                    from: new_idx[from],
+                    to: new_idx[to],
+                });
+                collapsed.edge_weights.push(Chain {
+                    inner,
+                    weights: edges
+                        .iter()
+                        .map(|e| self.edge_weights[*e].clone())
+                        .collect(),
+                });
+            }
+        }
+
+        collapsed.rebuild_adjacency();
+        collapsed
+    }
+}
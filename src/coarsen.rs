@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use crate::graph::{Edge, Graph, NodeIndex};
+
+#[derive(Debug)]
+pub struct CoarseLevel {
+    // nodes carry their own index as payload, edge weights are the amount of
+    // original edges that were merged into each edge
+    pub graph: Graph<NodeIndex, usize>,
+    // how many original nodes were merged into each node
+    pub node_weights: Vec<usize>,
+    // `mapping[fine_idx]` is the node of this level that the node `fine_idx`
+    // of the previous level (the original graph for the first one) ended up in
+    pub mapping: Vec<NodeIndex>,
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Multilevel summary of the graph. Every level is built from the previous one
+    by contracting a heavy-edge matching:
+
+    1. Visit the nodes by increasing weight (lighter nodes first, to keep the
+       coarse nodes balanced). Match every unmatched node with the unmatched
+       neighbour it shares the heaviest edge(s) with, ignoring direction.
+    2. Merge every matched pair (and every unmatched node on its own) into a
+       single node. Edges between merged nodes are aggregated, their weights
+       added. Edges inside a merged pair disappear.
+
+    Stops before `levels` if a round can't merge anything else.
+    */
+    pub fn coarsen(&self, levels: usize) -> Vec<CoarseLevel> {
+        let mut result: Vec<CoarseLevel> = Vec::new();
+
+        let mut node_weights = vec![1; self.nodes.len()];
+        let mut weighted_edges: Vec<(NodeIndex, NodeIndex, usize)> =
+            self.edges.iter().map(|e| (e.from, e.to, 1)).collect();
+
+        for _ in 0..levels {
+            let level = contract(&node_weights, &weighted_edges);
+            if level.node_weights.len() == node_weights.len() {
+                break;
+            }
+
+            node_weights = level.node_weights.clone();
+            weighted_edges = level
+                .graph
+                .edges
+                .iter()
+                .zip(level.graph.edge_weights.iter())
+                .map(|(e, w)| (e.from, e.to, *w))
+                .collect();
+            result.push(level);
+        }
+
+        result
+    }
+}
+
+fn contract(node_weights: &[usize], edges: &[(NodeIndex, NodeIndex, usize)]) -> CoarseLevel {
+    let n = node_weights.len();
+
+    // undirected neighbour weights
+    let mut neighbours: Vec<HashMap<NodeIndex, usize>> = vec![HashMap::new(); n];
+    for (from, to, weight) in edges.iter().copied() {
+        if from == to {
+            continue;
+        }
+        *neighbours[from].entry(to).or_insert(0) += weight;
+        *neighbours[to].entry(from).or_insert(0) += weight;
+    }
+
+    let mut order: Vec<NodeIndex> = (0..n).collect();
+    order.sort_by_key(|node_idx| node_weights[*node_idx]);
+
+    let mut mapping = vec![usize::MAX; n];
+    let mut coarse_weights: Vec<usize> = Vec::new();
+
+    for u in order {
+        if mapping[u] != usize::MAX {
+            continue;
+        }
+
+        let mate = neighbours[u]
+            .iter()
+            .filter(|(v, _)| mapping[**v] == usize::MAX)
+            .max_by_key(|(v, weight)| (**weight, std::cmp::Reverse(**v)))
+            .map(|(v, _)| *v);
+
+        mapping[u] = coarse_weights.len();
+        let mut weight = node_weights[u];
+        if let Some(v) = mate {
+            mapping[v] = coarse_weights.len();
+            weight += node_weights[v];
+        }
+        coarse_weights.push(weight);
+    }
+
+    let mut graph: Graph<NodeIndex, usize> = Graph::default();
+    for coarse_idx in 0..coarse_weights.len() {
+        graph.add_node(coarse_idx);
+    }
+
+    for (from, to, weight) in edges.iter().copied() {
+        let (from, to) = (mapping[from], mapping[to]);
+        if from == to {
+            continue;
+        }
+
+        let edge_idx = graph.add_edge(Edge { from, to });
+        graph.edge_weights[edge_idx] += weight;
+    }
+
+    CoarseLevel {
+        graph,
+        node_weights: coarse_weights,
+        mapping,
+    }
+}
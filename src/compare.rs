@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::graph::{Edge, Graph, GraphKind, NodeIndex};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Structural equality between two graphs that were built independently.
+
+    Nodes are matched by payload (payloads are unique inside a graph, so this
+    gives a single candidate mapping), then both edge sets are compared under
+    that mapping. Node and edge insertion order don't matter, and neither
+    does the direction of an edge in undirected graphs. A directed graph never
+    has the same topology as an undirected one. Nodes added with
+    `add_node_unchecked` that share a payload all map to the first of them in
+    `other`.
+    */
+    pub fn same_topology<E2, const B: usize>(&self, other: &Graph<N, E2, B>) -> bool
+    where
+        N: Eq + Hash,
+    {
+        if self.kind != other.kind
+            || self.nodes.len() != other.nodes.len()
+            || self.edges.len() != other.edges.len()
+        {
+            return false;
+        }
+
+        let mut by_payload: HashMap<&N, NodeIndex> = HashMap::with_capacity(other.nodes.len());
+        for (other_idx, node) in other.nodes.iter().enumerate() {
+            by_payload.entry(node).or_insert(other_idx);
+        }
+        let mut mapping: Vec<NodeIndex> = Vec::with_capacity(self.nodes.len());
+        for node in self.nodes.iter() {
+            match by_payload.get(node) {
+                Some(other_idx) => mapping.push(*other_idx),
+                None => return false,
+            }
+        }
+
+        let undirected = self.kind == GraphKind::Undirected;
+        let mut mapped: Vec<(NodeIndex, NodeIndex)> = self
+            .edges
+            .iter()
+            .map(|e| edge_key(mapping[e.from], mapping[e.to], undirected))
+            .collect();
+        mapped.sort();
+
+        let mut theirs: Vec<(NodeIndex, NodeIndex)> = other
+            .edges
+            .iter()
+            .map(|e| edge_key(e.from, e.to, undirected))
+            .collect();
+        theirs.sort();
+
+        mapped == theirs
+    }
+}
+
+// Most local search passes (sweeps over every node) `ged_upper_bound` does
+// after the assignment, so its cost doesn't depend on how bad that was.
+const GED_PASSES: usize = 8;
+
+/*
+Result of `ged_upper_bound`: a way to edit one graph into the other and what
+it costs. `alignment[u]` is the node of the other graph `u` becomes, `None`
+when it's deleted, `inserted` the nodes of the other graph nothing maps to.
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EditDistance {
+    pub cost: usize,
+    pub alignment: Vec<Option<NodeIndex>>,
+    pub inserted: Vec<NodeIndex>,
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    An upper bound of the graph edit distance to `other`: the number of node
+    insertions, deletions and relabelings (payloads that aren't equal) plus
+    edge insertions and deletions it takes to turn this graph into `other`.
+    Edge payloads are ignored, parallel edges count once per copy, in
+    undirected graphs (either of them) `a -> b` and `b -> a` are the same
+    edge.
+
+    The exact distance is NP-hard, this is the bipartite approximation of
+    Riesen and Bunke: nodes are paired up with the Hungarian algorithm using
+    the payload and the degrees as costs, then pairs are swapped as long as
+    that lowers the real cost of the edit, for at most `GED_PASSES` passes
+    over the nodes. With n = both node counts together and e = both edge
+    counts, the assignment is O(n^3) and every pass tries O(n^2) edits
+    costing O(n + e) each, O(n^2 (n + e)) a pass. Meant for small graphs
+    (schemas, ASTs...). 0 means the graphs are isomorphic, the reverse isn't
+    guaranteed.
+    */
+    pub fn ged_upper_bound<E2, const B: usize>(&self, other: &Graph<N, E2, B>) -> EditDistance
+    where
+        N: PartialEq,
+    {
+        let (n, m) = (self.nodes.len(), other.nodes.len());
+        let undirected = self.kind == GraphKind::Undirected || other.kind == GraphKind::Undirected;
+        let ours = edge_counts(&self.edges, undirected);
+        let theirs = edge_counts(&other.edges, undirected);
+
+        // (n + m) x (n + m): a node of ours to one of theirs, deleted
+        // (diagonal of the top right block), inserted (diagonal of the bottom
+        // left block), or nothing to nothing
+        let forbidden = (2 * (self.edges.len() + other.edges.len()) + n + m + 1) as f64;
+        let substitute = |u: NodeIndex, v: NodeIndex| {
+            let relabel = usize::from(self.nodes[u] != other.nodes[v]);
+            let out = self.out_degree(u).abs_diff(other.out_degree(v));
+            let into = self.in_degree(u).abs_diff(other.in_degree(v));
+            relabel as f64 + (out + into) as f64 / 2.0
+        };
+        let mut costs: Vec<Vec<f64>> = Vec::with_capacity(n + m);
+        for u in 0..n {
+            let delete = 1.0 + (self.out_degree(u) + self.in_degree(u)) as f64 / 2.0;
+            let mut row: Vec<f64> = (0..m).map(|v| substitute(u, v)).collect();
+            row.extend((0..n).map(|k| if k == u { delete } else { forbidden }));
+            costs.push(row);
+        }
+        for v in 0..m {
+            let insert = 1.0 + (other.out_degree(v) + other.in_degree(v)) as f64 / 2.0;
+            let mut row: Vec<f64> = (0..m)
+                .map(|k| if k == v { insert } else { forbidden })
+                .collect();
+            row.resize(n + m, 0.0);
+            costs.push(row);
+        }
+
+        let assignment = hungarian(&costs);
+        let mut alignment: Vec<Option<NodeIndex>> = assignment[..n]
+            .iter()
+            .map(|v| (*v < m).then_some(*v))
+            .collect();
+
+        let edit_cost = |alignment: &[Option<NodeIndex>]| {
+            let mut cost = 0;
+            let mut used = vec![false; m];
+            for (u, v) in alignment.iter().enumerate() {
+                match v {
+                    Some(v) => {
+                        used[*v] = true;
+                        cost += usize::from(self.nodes[u] != other.nodes[*v]);
+                    }
+                    None => cost += 1,
+                }
+            }
+            cost += used.iter().filter(|used| !**used).count();
+
+            let mut matched = 0;
+            for ((from, to), count) in ours.iter() {
+                if let (Some(from), Some(to)) = (alignment[*from], alignment[*to]) {
+                    let key = edge_key(from, to, undirected);
+                    matched += (*count).min(theirs.get(&key).copied().unwrap_or(0));
+                }
+            }
+            cost + self.edges.len() + other.edges.len() - 2 * matched
+        };
+
+        let mut cost = edit_cost(&alignment);
+        let mut improved = true;
+        let mut passes = 0;
+        while improved && passes < GED_PASSES {
+            improved = false;
+            passes += 1;
+            for u in 0..n {
+                // swap with another node of ours, move to a node of theirs
+                // that's still free, or get deleted
+                let mut free = vec![true; m];
+                for v in alignment.iter().flatten() {
+                    free[*v] = false;
+                }
+                let swaps = (0..n).filter(|w| *w != u).map(|w| (Some(w), None));
+                let moves = (0..m).filter(|v| free[*v]).map(|v| (None, Some(v)));
+                let candidates: Vec<_> = swaps.chain(moves).chain([(None, None)]).collect();
+                for (swap_with, target) in candidates {
+                    let mut next = alignment.clone();
+                    match swap_with {
+                        Some(w) => next.swap(u, w),
+                        None => next[u] = target,
+                    }
+                    let next_cost = edit_cost(&next);
+                    if next_cost < cost {
+                        cost = next_cost;
+                        alignment = next;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        let mut inserted = vec![true; m];
+        for v in alignment.iter().flatten() {
+            inserted[*v] = false;
+        }
+        EditDistance {
+            cost,
+            alignment,
+            inserted: (0..m).filter(|v| inserted[*v]).collect(),
+        }
+    }
+}
+
+fn edge_key(from: NodeIndex, to: NodeIndex, undirected: bool) -> (NodeIndex, NodeIndex) {
+    if undirected && to < from {
+        (to, from)
+    } else {
+        (from, to)
+    }
+}
+
+fn edge_counts(edges: &[Edge], undirected: bool) -> HashMap<(NodeIndex, NodeIndex), usize> {
+    let mut counts = HashMap::new();
+    for edge in edges {
+        *counts
+            .entry(edge_key(edge.from, edge.to, undirected))
+            .or_insert(0) += 1;
+    }
+    counts
+}
+
+// Minimum cost assignment of a square matrix, `result[row] = column`.
+// Hungarian algorithm with potentials, O(n^3).
+fn hungarian(costs: &[Vec<f64>]) -> Vec<usize> {
+    let n = costs.len();
+    // 1-based, row 0 and column 0 are the dummy start
+    let mut row_potential = vec![0.0; n + 1];
+    let mut column_potential = vec![0.0; n + 1];
+    let mut row_of = vec![0; n + 1];
+    let mut way = vec![0; n + 1];
+
+    for row in 1..=n {
+        row_of[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![f64::INFINITY; n + 1];
+        let mut done = vec![false; n + 1];
+        loop {
+            done[column] = true;
+            let current_row = row_of[column];
+            let mut delta = f64::INFINITY;
+            let mut next_column = 0;
+            for j in 1..=n {
+                if done[j] {
+                    continue;
+                }
+                let slack = costs[current_row - 1][j - 1]
+                    - row_potential[current_row]
+                    - column_potential[j];
+                if slack < min_slack[j] {
+                    min_slack[j] = slack;
+                    way[j] = column;
+                }
+                if min_slack[j] < delta {
+                    delta = min_slack[j];
+                    next_column = j;
+                }
+            }
+            for j in 0..=n {
+                if done[j] {
+                    row_potential[row_of[j]] += delta;
+                    column_potential[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+            column = next_column;
+            if row_of[column] == 0 {
+                break;
+            }
+        }
+        while column != 0 {
+            let previous = way[column];
+            row_of[column] = row_of[previous];
+            column = previous;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for j in 1..=n {
+        if row_of[j] != 0 {
+            assignment[row_of[j] - 1] = j - 1;
+        }
+    }
+    assignment
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::graph::{Edge, Graph, GraphKind};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // Build a graph from a `node -> neighbours` map. Nodes that only show up
+    // as neighbours are created too. `HashMap` iteration order is random, so
+    // don't rely on the node indexes of the result, look them up with
+    // `find_node_idx` instead.
+    pub fn from_adjacency_map<K>(map: HashMap<K, Vec<K>>) -> Self
+    where
+        K: Into<N> + Eq + Hash,
+        N: Hash + PartialEq,
+        E: Default,
+    {
+        let mut g = Graph::with_inline_adjacency();
+
+        for (node, neighbours) in map {
+            let from = g.add_node(node.into());
+            for neighbour in neighbours {
+                let to = g.add_node(neighbour.into());
+                g.add_edge(Edge { from, to });
+            }
+        }
+
+        g
+    }
+
+    // Build a graph from `(from, to)` payload pairs, creating the nodes the
+    // first time they show up, so node indexes follow the order of the
+    // edges. Repeated edges are added once.
+    pub fn from_edges<K>(edges: impl IntoIterator<Item = (K, K)>) -> Self
+    where
+        K: Into<N>,
+        N: Hash + PartialEq,
+        E: Default,
+    {
+        let mut g = Graph::with_inline_adjacency();
+        for (from, to) in edges {
+            let from = g.add_node(from.into());
+            let to = g.add_node(to.into());
+            g.add_edge(Edge { from, to });
+        }
+        g
+    }
+
+    // The inverse of `from_adjacency_map`. Every node is a key, nodes without
+    // outgoing edges map to an empty `Vec`.
+    pub fn to_adjacency_map(&self) -> HashMap<&N, Vec<&N>>
+    where
+        N: Eq + Hash,
+    {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| {
+                let neighbours = self.outgoing[idx]
+                    .iter()
+                    .map(|neighbour| &self.nodes[*neighbour])
+                    .collect();
+                (node, neighbours)
+            })
+            .collect()
+    }
+
+    // All the edges as `(from, to)` payload pairs, sorted. Two graphs with the
+    // same nodes and edges produce the same list no matter the order in which
+    // they were inserted, which makes it a good fit for snapshot tests. In
+    // undirected graphs the smaller payload of every pair comes first, `a -- b`
+    // and `b -- a` are the same edge.
+    pub fn canonical_edge_list(&self) -> Vec<(&N, &N)>
+    where
+        N: Ord,
+    {
+        let mut edges: Vec<(&N, &N)> = self
+            .edges
+            .iter()
+            .map(|e| {
+                let (from, to) = (&self.nodes[e.from], &self.nodes[e.to]);
+                match self.kind {
+                    GraphKind::Undirected if to < from => (to, from),
+                    _ => (from, to),
+                }
+            })
+            .collect();
+        edges.sort();
+        edges
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashSet;
+
+use crate::graph::{Edge, Graph, NodeIndex};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // Tarjan's algorithm. Components come out in reverse topological order:
+    // no component has edges towards the ones after it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeIndex>> {
+        self.tarjan(|_| true)
+    }
+
+    /*
+    Condensation: every strongly connected component becomes a single node,
+    whose payload is the (sorted) indexes of its members, and all the edges
+    from one component to another are merged into a single edge. Edges inside
+    a component are dropped, so the result is always a DAG.
+
+    Components are numbered in topological order, every edge goes from a
+    lower index to a higher one.
+    */
+    pub fn condensation(&self) -> Graph<Vec<NodeIndex>> {
+        let mut components = self.strongly_connected_components();
+        components.reverse();
+
+        let mut component_of = vec![0; self.nodes.len()];
+        for (component_idx, members) in components.iter_mut().enumerate() {
+            members.sort_unstable();
+            for node_idx in members.iter() {
+                component_of[*node_idx] = component_idx;
+            }
+        }
+
+        let mut condensed = Graph::new();
+        condensed.nodes = components;
+        let mut seen: HashSet<(NodeIndex, NodeIndex)> = HashSet::new();
+        for edge in self.edges.iter() {
+            let from = component_of[edge.from];
+            let to = component_of[edge.to];
+            if from != to && seen.insert((from, to)) {
+                condensed.edges.push(Edge { from, to });
+                condensed.edge_weights.push(());
+            }
+        }
+        condensed.rebuild_adjacency();
+        condensed
+    }
+
+    /*
+    The strongly connected components with no edges leaving them: once a walk
+    gets into one it can never get out, so they're where everything
+    eventually ends up. On a DAG these are the `boundary` nodes, each one on
+    its own.
+
+    Members are sorted, components are ordered by their lowest member.
+    */
+    pub fn terminal_components(&self) -> Vec<Vec<NodeIndex>> {
+        let components = self.strongly_connected_components();
+        let mut component_of = vec![0; self.nodes.len()];
+        for (component_idx, members) in components.iter().enumerate() {
+            for node_idx in members.iter() {
+                component_of[*node_idx] = component_idx;
+            }
+        }
+
+        let mut terminal = vec![true; components.len()];
+        for edge in self.edges.iter() {
+            if component_of[edge.from] != component_of[edge.to] {
+                terminal[component_of[edge.from]] = false;
+            }
+        }
+
+        let mut result: Vec<Vec<NodeIndex>> = components
+            .into_iter()
+            .zip(terminal)
+            .filter(|(_, terminal)| *terminal)
+            .map(|(mut members, _)| {
+                members.sort_unstable();
+                members
+            })
+            .collect();
+        result.sort_unstable_by_key(|members| members[0]);
+        result
+    }
+
+    /*
+    All the elementary cycles of the graph, using Johnson's algorithm. Every
+    cycle is returned once, starting from its lowest node index and without
+    repeating the first node at the end (a self-loop is `[n]`).
+
+    The amount of cycles can be exponential in the size of the graph, `limit`
+    stops the enumeration after that many cycles have been found.
+
+    Strategy: for every node `s`, in increasing order, look at the subgraph
+    made of the nodes >= s. Find the strongly connected component of `s` in
+    there, all cycles that start at `s` live inside it. Then DFS from `s`
+    inside the component, "blocking" nodes that are on the current path or
+    that can't reach `s` anymore. A blocked node is only unblocked once a path
+    through it leads back to `s`, which is what keeps the search from exploring
+    the same dead ends over and over.
+    */
+    pub fn simple_cycles(&self, limit: Option<usize>) -> Vec<Vec<NodeIndex>> {
+        let mut search = CycleSearch {
+            component: vec![false; self.nodes.len()],
+            blocked: vec![false; self.nodes.len()],
+            blocked_by: vec![HashSet::new(); self.nodes.len()],
+            path: Vec::new(),
+            cycles: Vec::new(),
+            limit: limit.unwrap_or(usize::MAX),
+        };
+
+        for start in 0..self.nodes.len() {
+            if search.cycles.len() >= search.limit {
+                break;
+            }
+
+            let component = match self
+                .tarjan(|node_idx| node_idx >= start)
+                .into_iter()
+                .find(|c| c.contains(&start))
+            {
+                Some(component) => component,
+                None => continue,
+            };
+
+            for node_idx in component.iter() {
+                search.component[*node_idx] = true;
+                search.blocked[*node_idx] = false;
+                search.blocked_by[*node_idx].clear();
+            }
+
+            search.circuit(self, start);
+
+            for node_idx in component.iter() {
+                search.component[*node_idx] = false;
+            }
+        }
+
+        search.cycles
+    }
+
+    pub(crate) fn tarjan(&self, include: impl Fn(NodeIndex) -> bool) -> Vec<Vec<NodeIndex>> {
+        let n = self.nodes.len();
+        let mut next_index = 0;
+        let mut indices: Vec<Option<usize>> = vec![None; n];
+        let mut lowlink = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack: Vec<NodeIndex> = Vec::new();
+        let mut components = Vec::new();
+
+        // (node, position of the next child to look at)
+        let mut call_stack: Vec<(NodeIndex, usize)> = Vec::new();
+
+        for root in (0..n).filter(|v| include(*v)) {
+            if indices[root].is_some() {
+                continue;
+            }
+
+            indices[root] = Some(next_index);
+            lowlink[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            call_stack.push((root, 0));
+
+            while let Some((v, child_pos)) = call_stack.last_mut() {
+                let v = *v;
+
+                if let Some(w) = self.outgoing[v].get(*child_pos).copied() {
+                    *child_pos += 1;
+
+                    if !include(w) {
+                        continue;
+                    }
+
+                    match indices[w] {
+                        None => {
+                            indices[w] = Some(next_index);
+                            lowlink[w] = next_index;
+                            next_index += 1;
+                            stack.push(w);
+                            on_stack[w] = true;
+                            call_stack.push((w, 0));
+                        }
+                        Some(w_index) if on_stack[w] => {
+                            lowlink[v] = lowlink[v].min(w_index);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                // all children of v are done
+                call_stack.pop();
+                if let Some((parent, _)) = call_stack.last() {
+                    lowlink[*parent] = lowlink[*parent].min(lowlink[v]);
+                }
+
+                if Some(lowlink[v]) == indices[v] {
+                    let mut component = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack[w] = false;
+                        component.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    component.reverse();
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+}
+
+struct CycleSearch {
+    component: Vec<bool>,
+    blocked: Vec<bool>,
+    blocked_by: Vec<HashSet<NodeIndex>>,
+    path: Vec<NodeIndex>,
+    cycles: Vec<Vec<NodeIndex>>,
+    limit: usize,
+}
+
+impl CycleSearch {
+    // Every cycle through `start`. Iterative so long paths don't overflow
+    // the stack.
+    fn circuit<N, E, const A: usize>(&mut self, g: &Graph<N, E, A>, start: NodeIndex) {
+        // (node, position of the next child to look at, found a cycle through it)
+        let mut call_stack: Vec<(NodeIndex, usize, bool)> = vec![(start, 0, false)];
+        self.path.push(start);
+        self.blocked[start] = true;
+
+        while let Some((v, child_pos, found)) = call_stack.last_mut() {
+            let v = *v;
+
+            let next = match self.cycles.len() < self.limit {
+                true => g.outgoing[v].get(*child_pos).copied(),
+                false => None,
+            };
+            if let Some(w) = next {
+                *child_pos += 1;
+
+                if !self.component[w] {
+                    continue;
+                }
+                if w == start {
+                    self.cycles.push(self.path.clone());
+                    *found = true;
+                } else if !self.blocked[w] {
+                    self.path.push(w);
+                    self.blocked[w] = true;
+                    call_stack.push((w, 0, false));
+                }
+                continue;
+            }
+
+            // all children of v are done
+            let found = *found;
+            call_stack.pop();
+            if found {
+                self.unblock(v);
+                if let Some((_, _, parent_found)) = call_stack.last_mut() {
+                    *parent_found = true;
+                }
+            } else {
+                for w in g.outgoing[v].iter().copied() {
+                    if self.component[w] {
+                        self.blocked_by[w].insert(v);
+                    }
+                }
+            }
+            self.path.pop();
+        }
+    }
+
+    fn unblock(&mut self, v: NodeIndex) {
+        let mut pending = vec![v];
+
+        while let Some(u) = pending.pop() {
+            self.blocked[u] = false;
+            for w in std::mem::take(&mut self.blocked_by[u]) {
+                if self.blocked[w] {
+                    pending.push(w);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::graph::{Edge, Graph, Node, NodeIndex};
+
+/*
+Cytoscape.js elements JSON (the `json` feature), what `cy.json()` gives and
+`cytoscape({ elements })` takes:
+
+    {
+        "data": { <graph attributes> },
+        "elements": {
+            "nodes": [{ "data": { "id": "0", "label": "hello" } }, ...],
+            "edges": [{ "data": { "id": "e0", "source": "0", "target": "1", "weight": 3 } }, ...]
+        }
+    }
+
+Node IDs are node indexes and edge IDs edge indexes with an `e` in front.
+*/
+
+// A Cytoscape.js document `from_cytoscape_json` couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct CytoscapeError {
+    pub message: String,
+}
+
+impl fmt::Display for CytoscapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid Cytoscape.js JSON: {}", self.message)
+    }
+}
+
+impl std::error::Error for CytoscapeError {}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Display,
+    E: Serialize,
+{
+    // Labels come from `Display`, edge payloads are written as `weight`
+    // unless they serialize to nothing (`()`).
+    pub fn to_cytoscape_json(&self) -> String {
+        let nodes: Vec<Value> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(node_idx, node)| {
+                json!({ "data": { "id": node_idx.to_string(), "label": node.to_string() } })
+            })
+            .collect();
+
+        let edges: Vec<Value> = self
+            .edges
+            .iter()
+            .zip(self.edge_weights.iter())
+            .enumerate()
+            .map(|(edge_idx, (edge, weight))| {
+                let mut data = Map::new();
+                data.insert("id".to_string(), json!(format!("e{}", edge_idx)));
+                data.insert("source".to_string(), json!(edge.from.to_string()));
+                data.insert("target".to_string(), json!(edge.to.to_string()));
+                match serde_json::to_value(weight) {
+                    Ok(Value::Null) | Err(_) => {}
+                    Ok(weight) => {
+                        data.insert("weight".to_string(), weight);
+                    }
+                }
+                json!({ "data": data })
+            })
+            .collect();
+
+        json!({
+            "data": self.attrs,
+            "elements": { "nodes": nodes, "edges": edges },
+        })
+        .to_string()
+    }
+}
+
+impl Graph {
+    /*
+    Read Cytoscape.js elements: the document above, just its `elements`, or
+    a flat array of elements (edges being the ones with a `source`). Nodes
+    get their `label` as `DataTypes::Text`, or their ID when they have none,
+    in document order, and are matched by ID. Every other field is
+    skipped, string values of the top-level `data` become graph attributes.
+    */
+    pub fn from_cytoscape_json(json: &str) -> Result<Graph, CytoscapeError> {
+        let error = |message: String| CytoscapeError { message };
+        let document: Value = serde_json::from_str(json).map_err(|err| error(err.to_string()))?;
+
+        let mut graph = Graph::new();
+        if let Some(Value::Object(data)) = document.get("data") {
+            for (key, value) in data.iter() {
+                if let Value::String(value) = value {
+                    graph.set_attr(key.clone(), value.clone());
+                }
+            }
+        }
+
+        let elements = document.get("elements").unwrap_or(&document);
+        let (nodes, edges): (Vec<&Value>, Vec<&Value>) = match elements {
+            Value::Array(elements) => elements
+                .iter()
+                .partition(|element| element["data"].get("source").is_none()),
+            Value::Object(groups) => (
+                array(groups.get("nodes")).map_err(|m| error(format!("nodes {}", m)))?,
+                array(groups.get("edges")).map_err(|m| error(format!("edges {}", m)))?,
+            ),
+            _ => {
+                return Err(error(
+                    "expected an object or an array of elements".to_string(),
+                ))
+            }
+        };
+
+        let mut ids: HashMap<&str, NodeIndex> = HashMap::new();
+        for node in nodes {
+            let id = string(&node["data"], "id")
+                .ok_or_else(|| error(format!("node without an id: {}", node)))?;
+            if ids.contains_key(id) {
+                return Err(error(format!("duplicate node id {:?}", id)));
+            }
+            let label = string(&node["data"], "label").unwrap_or(id);
+            ids.insert(id, graph.add_node_unchecked(Node::from(label)));
+        }
+
+        for edge in edges {
+            let endpoint = |field: &str| {
+                let id = string(&edge["data"], field)
+                    .ok_or_else(|| error(format!("edge without a {}: {}", field, edge)))?;
+                ids.get(id)
+                    .copied()
+                    .ok_or_else(|| error(format!("edge to an unknown node {:?}", id)))
+            };
+            let from = endpoint("source")?;
+            let to = endpoint("target")?;
+            graph.add_edge(Edge { from, to });
+        }
+
+        Ok(graph)
+    }
+}
+
+// A missing group is an empty one.
+fn array(value: Option<&Value>) -> Result<Vec<&Value>, &'static str> {
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(values)) => Ok(values.iter().collect()),
+        Some(_) => Err("should be an array"),
+    }
+}
+
+fn string<'v>(data: &'v Value, field: &str) -> Option<&'v str> {
+    data.get(field).and_then(|value| value.as_str())
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::dot::{arrow, DotOptions};
+use crate::graph::{Graph, GraphKind, NodeIndex};
+use crate::hash::fnv1a;
+
+/*
+Incremental DOT export, for pushing a graph that changes a bit at a time to a
+viewer without resending all of it every tick.
+
+A `DotSnapshot` is the signature of what `write_dot_with` would write: a hash
+of the attributes of every node and edge, no payloads. `write_dot_delta`
+writes what changed since a snapshot, one line per change, with the names and
+attributes of the DOT export, and returns the snapshot to diff the next call
+against:
+
+    - 4 -> 5;               edge removed
+    - 5;                    node removed
+    + 3 [label="c"];        node added
+    ~ 1 [label="B"];        node attributes changed
+    + 0 -> 3;               edge added
+    ~ 0 -> 1 [color=red];   edge attributes changed
+
+Removals come first, so the lines can be applied in order. Nodes are named by
+index like in the DOT export, `remove_node` moving the last node into the hole
+shows up as that node changing and the last one going away. Edges are named
+by their ends, parallel edges are always bundled (see `bundle_parallel_edges`).
+Graph attributes and the kind aren't part of the delta.
+*/
+#[derive(Clone, Default, Debug)]
+pub struct DotSnapshot {
+    nodes: Vec<u64>,
+    edges: HashMap<(NodeIndex, NodeIndex), u64>,
+}
+
+impl DotSnapshot {
+    // The snapshot of an empty graph, diffing against it writes everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Display,
+{
+    pub fn dot_snapshot(&self, options: &DotOptions<'_, N, E>) -> DotSnapshot {
+        self.write_dot_delta(&mut io::sink(), &DotSnapshot::new(), options)
+            .expect("writing to a sink doesn't fail")
+    }
+
+    pub fn write_dot_delta(
+        &self,
+        out: &mut impl Write,
+        previous: &DotSnapshot,
+        options: &DotOptions<'_, N, E>,
+    ) -> io::Result<DotSnapshot> {
+        let arrow = arrow(self.kind);
+
+        let mut snapshot = DotSnapshot::new();
+        let mut node_lines = Vec::new();
+        for node_idx in 0..self.nodes.len() {
+            let attrs = self.dot_node_attrs(node_idx, options);
+            let hash = fnv1a(attrs.as_bytes());
+            match previous.nodes.get(node_idx) {
+                Some(old) if *old == hash => {}
+                Some(_) => node_lines.push(format!("~ {} [{}];", node_idx, attrs)),
+                None => node_lines.push(format!("+ {} [{}];", node_idx, attrs)),
+            }
+            snapshot.nodes.push(hash);
+        }
+
+        let mut edge_lines = Vec::new();
+        for group in self.parallel_edge_groups() {
+            let edge = &self.edges[group[0]];
+            let key = match self.kind {
+                GraphKind::Directed => (edge.from, edge.to),
+                GraphKind::Undirected => (edge.from.min(edge.to), edge.from.max(edge.to)),
+            };
+            let attrs = self.dot_edge_attrs(&group, options);
+            let hash = fnv1a(attrs.as_bytes());
+            let change = match previous.edges.get(&key) {
+                Some(old) if *old == hash => None,
+                Some(_) => Some('~'),
+                None => Some('+'),
+            };
+            if let Some(change) = change {
+                let mut line = format!("{} {} {} {}", change, edge.from, arrow, edge.to);
+                if !attrs.is_empty() {
+                    line.push_str(&format!(" [{}]", attrs));
+                }
+                line.push(';');
+                edge_lines.push(line);
+            }
+            snapshot.edges.insert(key, hash);
+        }
+
+        let mut removed_edges: Vec<&(NodeIndex, NodeIndex)> = previous
+            .edges
+            .keys()
+            .filter(|key| !snapshot.edges.contains_key(key))
+            .collect();
+        removed_edges.sort();
+        for (from, to) in removed_edges {
+            writeln!(out, "- {} {} {};", from, arrow, to)?;
+        }
+        for node_idx in self.nodes.len()..previous.nodes.len() {
+            writeln!(out, "- {};", node_idx)?;
+        }
+        for line in node_lines.iter().chain(&edge_lines) {
+            writeln!(out, "{}", line)?;
+        }
+
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::BinaryHeap;
+
+use crate::graph::{Cost, Graph, NodeIndex};
+use crate::measure::{Measure, MinScored};
+use crate::trace::{snapshot, Trace, TraceEvent};
+use crate::traits::WeightedNeighbors;
+
+// Distances from a single source to every node, and the predecessor of every
+// node in the shortest path tree (`None` for the source and for unreachable nodes).
+// `K` is the cost type, see `Measure`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct ShortestPaths<K = Cost> {
+    pub source: NodeIndex,
+    pub distances: Vec<Option<K>>,
+    pub predecessors: Vec<Option<NodeIndex>>,
+}
+
+impl<K> ShortestPaths<K> {
+    // Path from the source to `target`, both included.
+    pub fn path_to(&self, target: NodeIndex) -> Option<Vec<NodeIndex>> {
+        self.distances.get(target)?.as_ref()?;
+
+        let mut path = vec![target];
+        let mut current = target;
+        while let Some(pred) = self.predecessors[current] {
+            path.push(pred);
+            current = pred;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Measure,
+{
+    /*
+    Dijkstra's algorithm, using the edge payloads as costs (any `Measure`:
+    integers, floats, durations...). Costs must not be negative (use
+    `bellman_ford` for that).
+
+    Returns the total cost and the nodes of the path, `start` and `end`
+    included. Stops as soon as `end` is settled.
+    */
+    pub fn dijkstra(&self, start: NodeIndex, end: NodeIndex) -> Option<(E, Vec<NodeIndex>)> {
+        let paths = dijkstra_search(&self.weighted_successors(), start, Some(end), None);
+        let cost = paths.distances.get(end).copied().flatten()?;
+        Some((cost, paths.path_to(end)?))
+    }
+
+    // Shortest paths from `start` to every node.
+    pub fn dijkstra_all(&self, start: NodeIndex) -> ShortestPaths<E> {
+        dijkstra_search(&self.weighted_successors(), start, None, None)
+    }
+
+    // (successor, cost) pairs of the outgoing edges of every node, both ways
+    // for undirected edges, built once per search from the edge array. The
+    // searches that stop early expand nodes from `arcs_from` instead.
+    pub(crate) fn weighted_successors(&self) -> Vec<Vec<(NodeIndex, E)>> {
+        let mut successors = vec![Vec::new(); self.nodes.len()];
+        for (edge_idx, arc) in self.arcs() {
+            successors[arc.from].push((arc.to, self.edge_weights[edge_idx]));
+        }
+        successors
+    }
+}
+
+// Dijkstra over any `WeightedNeighbors`, precomputed `successors[node] =
+// [(successor, cost)]` lists included, so callers can reweight the edges (see
+// `johnson`). Stops once `end` is settled. Every step is recorded in `trace`,
+// if any.
+pub(crate) fn dijkstra_search<G, K>(
+    successors: &G,
+    start: NodeIndex,
+    end: Option<NodeIndex>,
+    mut trace: Option<&mut Trace>,
+) -> ShortestPaths<K>
+where
+    G: WeightedNeighbors<Weight = K> + ?Sized,
+    K: Measure,
+{
+    let n = successors.node_bound();
+    let mut distances: Vec<Option<K>> = vec![None; n];
+    let mut predecessors: Vec<Option<NodeIndex>> = vec![None; n];
+    let mut settled = vec![false; n];
+    let mut heap: BinaryHeap<MinScored<K>> = BinaryHeap::new();
+
+    if start < n {
+        distances[start] = Some(K::zero());
+        heap.push(MinScored(K::zero(), start));
+    }
+
+    while let Some(MinScored(cost, node_idx)) = heap.pop() {
+        if settled[node_idx] {
+            continue;
+        }
+        settled[node_idx] = true;
+
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.push(TraceEvent::Expand {
+                node: node_idx,
+                frontier: snapshot(
+                    heap.iter()
+                        .map(|MinScored(_, idx)| *idx)
+                        .filter(|idx| !settled[*idx]),
+                ),
+                visited: snapshot((0..n).filter(|idx| settled[*idx])),
+            });
+        }
+
+        if Some(node_idx) == end {
+            break;
+        }
+
+        for (succ, weight) in successors.weighted_neighbors(node_idx) {
+            let candidate = cost.add(weight);
+            let improved = distances[succ].is_none_or(|current| candidate < current);
+            if improved {
+                distances[succ] = Some(candidate);
+                predecessors[succ] = Some(node_idx);
+                heap.push(MinScored(candidate, succ));
+            }
+
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.push(TraceEvent::Edge {
+                    from: node_idx,
+                    to: succ,
+                    chosen: improved,
+                });
+            }
+        }
+    }
+
+    ShortestPaths {
+        source: start,
+        distances,
+        predecessors,
+    }
+}
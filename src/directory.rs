@@ -0,0 +1,75 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::graph::{Edge, Graph};
+
+// Edge payload of `from_directory`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FsEdge {
+    // directory -> entry
+    Contains,
+    // link -> target
+    Symlink,
+}
+
+impl Graph<PathBuf, FsEdge> {
+    /*
+    The tree under `path` (the `fs` feature): a node per file, directory and
+    symlink, an `FsEdge::Contains` edge from every directory to each of its
+    entries (sorted by name) and an `FsEdge::Symlink` edge from every
+    symlink to its target.
+
+    `path` is canonicalized first and the root is node 0. Symlinks aren't
+    followed, so links to a parent can't make the scan loop. Their target
+    is resolved against the link's directory and canonicalized when it
+    exists, so a link into the tree points to the node the scan made for its
+    target, a link outside of it or a broken one gets a node of its own with
+    no children.
+    */
+    pub fn from_directory(path: impl AsRef<Path>) -> io::Result<Self> {
+        let root = fs::canonicalize(path)?;
+        let mut graph = Graph::with_inline_adjacency();
+        let root_idx = graph.add_node(root);
+
+        let mut stack = vec![root_idx];
+        while let Some(dir_idx) = stack.pop() {
+            let mut entries: Vec<fs::DirEntry> =
+                fs::read_dir(&graph.nodes[dir_idx])?.collect::<io::Result<_>>()?;
+            entries.sort_by_key(|entry| entry.file_name());
+
+            for entry in entries {
+                let entry_path = entry.path();
+                let file_type = entry.file_type()?;
+                let entry_idx = graph.add_node(entry_path.clone());
+                graph.add_weighted_edge(
+                    Edge {
+                        from: dir_idx,
+                        to: entry_idx,
+                    },
+                    FsEdge::Contains,
+                );
+
+                if file_type.is_dir() {
+                    stack.push(entry_idx);
+                } else if file_type.is_symlink() {
+                    let target = fs::read_link(&entry_path)?;
+                    let target = match entry_path.parent() {
+                        Some(parent) => parent.join(target),
+                        None => target,
+                    };
+                    let target = fs::canonicalize(&target).unwrap_or(target);
+                    let target_idx = graph.add_node(target);
+                    graph.add_weighted_edge(
+                        Edge {
+                            from: entry_idx,
+                            to: target_idx,
+                        },
+                        FsEdge::Symlink,
+                    );
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
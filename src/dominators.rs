@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+
+use crate::graph::{Graph, NodeIndex};
+
+#[derive(Debug)]
+pub struct Dominators {
+    root: NodeIndex,
+    // `None` for nodes that can't be reached from the root, the root is its
+    // own immediate dominator
+    idom: Vec<Option<NodeIndex>>,
+}
+
+impl Dominators {
+    pub fn root(&self) -> NodeIndex {
+        self.root
+    }
+
+    // `None` for the root and for nodes that can't be reached from it.
+    pub fn immediate_dominator(&self, node_idx: NodeIndex) -> Option<NodeIndex> {
+        if node_idx == self.root {
+            return None;
+        }
+        self.idom.get(node_idx).copied().flatten()
+    }
+
+    // Whether `node_idx` can be reached from the root.
+    pub fn is_reachable(&self, node_idx: NodeIndex) -> bool {
+        matches!(self.idom.get(node_idx), Some(Some(_)))
+    }
+
+    // Every node dominates itself.
+    pub fn dominates(&self, dominator: NodeIndex, node_idx: NodeIndex) -> bool {
+        match self.dominators(node_idx) {
+            Some(mut chain) => chain.any(|d| d == dominator),
+            None => false,
+        }
+    }
+
+    // Walks the dominator tree up from `node_idx` (included) to the root.
+    pub fn dominators(&self, node_idx: NodeIndex) -> Option<impl Iterator<Item = NodeIndex> + '_> {
+        self.idom.get(node_idx).copied().flatten()?;
+
+        let mut next = Some(node_idx);
+        Some(std::iter::from_fn(move || {
+            let current = next?;
+            next = self.immediate_dominator(current);
+            Some(current)
+        }))
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Dominator tree using the Cooper, Harvey and Kennedy algorithm ("A Simple,
+    Fast Dominance Algorithm"):
+
+    1. Number the nodes reachable from `root` in reverse postorder.
+    2. Walk them in that order, the immediate dominator of a node is the
+       common ancestor (in the tree built so far) of all its already
+       processed predecessors.
+    3. Repeat until nothing changes. Usually 2 passes are enough.
+    */
+    pub fn dominators(&self, root: NodeIndex) -> Dominators {
+        let n = self.nodes.len();
+        let mut idom: Vec<Option<NodeIndex>> = vec![None; n];
+
+        if root >= n {
+            return Dominators { root, idom };
+        }
+
+        let postorder = self.postorder(root);
+        let mut postorder_number = vec![usize::MAX; n];
+        for (number, node_idx) in postorder.iter().enumerate() {
+            postorder_number[*node_idx] = number;
+        }
+
+        idom[root] = Some(root);
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for node_idx in postorder.iter().rev().skip(1) {
+                let mut new_idom: Option<NodeIndex> = None;
+
+                for pred in self.incoming[*node_idx].iter().copied() {
+                    if idom[pred].is_none() {
+                        continue;
+                    }
+
+                    new_idom = match new_idom {
+                        None => Some(pred),
+                        Some(current) => Some(intersect(&idom, &postorder_number, pred, current)),
+                    };
+                }
+
+                if new_idom.is_some() && idom[*node_idx] != new_idom {
+                    idom[*node_idx] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        Dominators { root, idom }
+    }
+
+    /*
+    The dominance frontier of `n` is the set of nodes where the dominance of
+    `n` stops: nodes that have a predecessor dominated by `n` but that aren't
+    strictly dominated by `n` themselves. That's where SSA construction places
+    phi functions.
+
+    For every edge p -> b, walk up the dominator tree from `p` until reaching
+    the immediate dominator of `b`, every node on the way has `b` in its
+    frontier. Nodes that aren't reachable from `root` get an empty frontier.
+    */
+    pub fn dominance_frontiers(&self, root: NodeIndex) -> Vec<HashSet<NodeIndex>> {
+        let dominators = self.dominators(root);
+        let mut frontiers = vec![HashSet::new(); self.nodes.len()];
+
+        for (b, b_idom) in dominators.idom.iter().enumerate() {
+            let b_idom = match b_idom {
+                Some(b_idom) => *b_idom,
+                None => continue,
+            };
+
+            for pred in self.incoming[b].iter().copied() {
+                if dominators.idom[pred].is_none() {
+                    continue;
+                }
+
+                let mut runner = pred;
+                loop {
+                    if runner == b_idom && b != root {
+                        break;
+                    }
+                    frontiers[runner].insert(b);
+                    if runner == root {
+                        break;
+                    }
+                    runner = dominators.idom[runner].unwrap();
+                }
+            }
+        }
+
+        frontiers
+    }
+
+    pub(crate) fn postorder(&self, root: NodeIndex) -> Vec<NodeIndex> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut postorder = Vec::new();
+        let mut stack: Vec<(NodeIndex, usize)> = vec![(root, 0)];
+        visited[root] = true;
+
+        while let Some((node_idx, child_pos)) = stack.last_mut() {
+            let node_idx = *node_idx;
+
+            match self.outgoing[node_idx].get(*child_pos).copied() {
+                Some(child) => {
+                    *child_pos += 1;
+                    if !visited[child] {
+                        visited[child] = true;
+                        stack.push((child, 0));
+                    }
+                }
+                None => {
+                    stack.pop();
+                    postorder.push(node_idx);
+                }
+            }
+        }
+
+        postorder
+    }
+}
+
+fn intersect(
+    idom: &[Option<NodeIndex>],
+    postorder_number: &[usize],
+    mut a: NodeIndex,
+    mut b: NodeIndex,
+) -> NodeIndex {
+    while a != b {
+        while postorder_number[a] < postorder_number[b] {
+            a = idom[a].unwrap();
+        }
+        while postorder_number[b] < postorder_number[a] {
+            b = idom[b].unwrap();
+        }
+    }
+    a
+}
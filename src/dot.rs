@@ -0,0 +1,555 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+use crate::graph::{DataTypes, Edge, EdgeIndex, Graph, GraphKind, Node, NodeIndex};
+use crate::merge::MergePolicy;
+
+// Node labels in exports: text as is, integers in decimal, blobs by size.
+impl Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.data {
+            DataTypes::Text(text) => f.write_str(text),
+            DataTypes::Integer(v) => write!(f, "{}", v),
+            DataTypes::Blob(bytes) => write!(f, "<{} bytes>", bytes.len()),
+        }
+    }
+}
+
+type NodeAttrs<'a, N> = &'a dyn Fn(NodeIndex, &N) -> String;
+type EdgeAttrs<'a, E> = &'a dyn Fn(EdgeIndex, &E) -> String;
+
+/*
+Styling for `write_dot_with`. The hooks return extra DOT attributes for a node
+or an edge (`color=red, shape=box`), written after the default label so they
+can override it. An empty string adds nothing.
+
+With `bundle_parallel_edges`, the edges `aggregate_parallel_edges` would merge
+are written once, labeled with how many there are ("x3"). The edge hook then
+gets the first edge of each bundle.
+*/
+pub struct DotOptions<'a, N, E> {
+    pub node_attrs: Option<NodeAttrs<'a, N>>,
+    pub edge_attrs: Option<EdgeAttrs<'a, E>>,
+    pub bundle_parallel_edges: bool,
+}
+
+impl<N, E> Default for DotOptions<'_, N, E> {
+    fn default() -> Self {
+        DotOptions {
+            node_attrs: None,
+            edge_attrs: None,
+            bundle_parallel_edges: false,
+        }
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Display,
+{
+    // The graph in Graphviz DOT, nodes named by index and labeled with their
+    // payload, graph attributes (`set_attr`) included.
+    pub fn to_dot(&self) -> String {
+        let mut out = Vec::new();
+        self.write_dot(&mut out)
+            .expect("writing to a Vec doesn't fail");
+        String::from_utf8(out).expect("the DOT output is built from strings")
+    }
+
+    pub fn write_dot(&self, out: &mut impl Write) -> io::Result<()> {
+        self.write_dot_with(out, &DotOptions::default())
+    }
+
+    pub fn write_dot_with(
+        &self,
+        out: &mut impl Write,
+        options: &DotOptions<'_, N, E>,
+    ) -> io::Result<()> {
+        let keyword = match self.kind {
+            GraphKind::Directed => "digraph",
+            GraphKind::Undirected => "graph",
+        };
+        let arrow = arrow(self.kind);
+        writeln!(out, "{} {{", keyword)?;
+
+        for (key, value) in self.attrs.iter() {
+            writeln!(out, "    {}={};", quote(key), quote(value))?;
+        }
+
+        for node_idx in 0..self.nodes.len() {
+            writeln!(
+                out,
+                "    {} [{}];",
+                node_idx,
+                self.dot_node_attrs(node_idx, options)
+            )?;
+        }
+
+        let groups: Vec<Vec<EdgeIndex>> = if options.bundle_parallel_edges {
+            self.parallel_edge_groups()
+        } else {
+            (0..self.edges.len())
+                .map(|edge_idx| vec![edge_idx])
+                .collect()
+        };
+        for group in groups {
+            let edge = &self.edges[group[0]];
+            let attrs = self.dot_edge_attrs(&group, options);
+            if attrs.is_empty() {
+                writeln!(out, "    {} {} {};", edge.from, arrow, edge.to)?;
+            } else {
+                writeln!(out, "    {} {} {} [{}];", edge.from, arrow, edge.to, attrs)?;
+            }
+        }
+
+        writeln!(out, "}}")
+    }
+
+    pub(crate) fn dot_node_attrs(
+        &self,
+        node_idx: NodeIndex,
+        options: &DotOptions<'_, N, E>,
+    ) -> String {
+        let node = &self.nodes[node_idx];
+        let extra = options
+            .node_attrs
+            .map(|attrs| attrs(node_idx, node))
+            .unwrap_or_default();
+        join_attrs(format!("label={}", quote(&node.to_string())), &extra)
+    }
+
+    // Attributes of a bundle of parallel edges (a single edge when not
+    // bundling), empty if there are none.
+    pub(crate) fn dot_edge_attrs(
+        &self,
+        group: &[EdgeIndex],
+        options: &DotOptions<'_, N, E>,
+    ) -> String {
+        let edge_idx = group[0];
+        let label = if group.len() > 1 {
+            format!("label=\"x{}\"", group.len())
+        } else {
+            String::new()
+        };
+        let extra = options
+            .edge_attrs
+            .map(|attrs| attrs(edge_idx, &self.edge_weights[edge_idx]))
+            .unwrap_or_default();
+        join_attrs(label, &extra)
+    }
+}
+
+pub(crate) fn arrow(kind: GraphKind) -> &'static str {
+    match kind {
+        GraphKind::Directed => "->",
+        GraphKind::Undirected => "--",
+    }
+}
+
+fn join_attrs(default: String, extra: &str) -> String {
+    match (default.is_empty(), extra.trim().is_empty()) {
+        (_, true) => default,
+        (true, false) => extra.to_string(),
+        (false, false) => format!("{}, {}", default, extra),
+    }
+}
+
+// A DOT double-quoted string.
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// A DOT file `from_dot` couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct DotError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DOT line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for DotError {}
+
+#[derive(PartialEq, Eq, Debug)]
+enum Token {
+    Id(String),
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Equals,
+    Semicolon,
+    Comma,
+    Colon,
+    Arrow(GraphKind),
+}
+
+impl Graph {
+    /*
+    Read a graph written in the Graphviz DOT language. The supported subset:
+    - `digraph` or `graph` (`strict` and the graph name are ignored)
+    - node statements, `a [label="A"]`
+    - edge statements and chains, `a -> b -> c`
+    - graph attributes, `name="x"` or `graph [name="x"]`, which end up in
+      `attr`
+    - comments
+
+    Every node is `DataTypes::Text` with its `label`, or its ID when it has
+    none, in order of first appearance. Nodes are matched by ID, so two nodes
+    with the same label stay apart. Other attributes, `node [...]` and
+    `edge [...]` defaults are skipped, subgraphs and ports are errors.
+    */
+    pub fn from_dot(dot: &str) -> Result<Graph, DotError> {
+        let weighted = DotParser::new(dot, None)?.parse()?;
+        let mut graph = Graph::with_kind(weighted.kind);
+        graph.attrs = weighted.attrs;
+        graph.nodes = weighted.nodes;
+        graph.edge_weights = vec![(); weighted.edges.len()];
+        graph.edges = weighted.edges;
+        graph.rebuild_adjacency();
+        Ok(graph)
+    }
+}
+
+impl Graph<Node, f64> {
+    /*
+    `from_dot`, keeping the `weight` attribute of edges (1 when missing).
+    Edges listed more than once are combined with `policy` instead of only
+    keeping the first one, `MergePolicy::Error` points at the line of the
+    duplicate.
+    */
+    pub fn from_dot_weighted(dot: &str, policy: MergePolicy) -> Result<Self, DotError> {
+        DotParser::new(dot, Some(policy))?.parse()
+    }
+}
+
+struct DotParser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    // DOT ID -> node
+    ids: HashMap<String, NodeIndex>,
+    graph: Graph<Node, f64>,
+    // `None` ignores weights, duplicates keep the first edge
+    policy: Option<MergePolicy>,
+}
+
+impl DotParser {
+    fn new(dot: &str, policy: Option<MergePolicy>) -> Result<Self, DotError> {
+        Ok(DotParser {
+            tokens: tokenize(dot)?,
+            pos: 0,
+            ids: HashMap::new(),
+            graph: Graph::default(),
+            policy,
+        })
+    }
+
+    fn parse(mut self) -> Result<Graph<Node, f64>, DotError> {
+        if self.peek_keyword("strict") {
+            self.pos += 1;
+        }
+        let kind = if self.peek_keyword("digraph") {
+            GraphKind::Directed
+        } else if self.peek_keyword("graph") {
+            GraphKind::Undirected
+        } else {
+            return Err(self.error("expected `digraph` or `graph`"));
+        };
+        self.pos += 1;
+        self.graph.set_kind(kind);
+
+        if let Some(Token::Id(_)) = self.peek() {
+            self.pos += 1;
+        }
+        self.expect(Token::LBrace)?;
+
+        loop {
+            match self.peek() {
+                Some(Token::RBrace) => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(Token::Semicolon) => self.pos += 1,
+                Some(_) => self.statement()?,
+                None => return Err(self.error("missing `}`")),
+            }
+        }
+
+        if self.pos < self.tokens.len() {
+            return Err(self.error("unexpected content after the graph"));
+        }
+        Ok(self.graph)
+    }
+
+    fn statement(&mut self) -> Result<(), DotError> {
+        if self.peek() == Some(&Token::LBrace) || self.peek_keyword("subgraph") {
+            return Err(self.error("subgraphs aren't supported"));
+        }
+        let start = self.pos;
+        let id = self.id()?;
+
+        match id.as_str() {
+            "graph" => {
+                for (key, value) in self.attr_list()? {
+                    self.graph.set_attr(key, value);
+                }
+                return Ok(());
+            }
+            "node" | "edge" => {
+                self.attr_list()?;
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        if self.peek() == Some(&Token::Equals) {
+            self.pos += 1;
+            let value = self.id()?;
+            self.graph.set_attr(id, value);
+            return Ok(());
+        }
+        if self.peek() == Some(&Token::Colon) {
+            return Err(self.error("ports aren't supported"));
+        }
+
+        let mut chain = vec![self.node(&id)];
+        let mut names = vec![id];
+        while let Some(Token::Arrow(kind)) = self.peek() {
+            if *kind != self.graph.kind() {
+                let arrow = arrow(self.graph.kind());
+                return Err(self.error(&format!("edges in this graph are written `{}`", arrow)));
+            }
+            self.pos += 1;
+            let next = self.id()?;
+            chain.push(self.node(&next));
+            names.push(next);
+        }
+
+        let attrs = self.attr_list()?;
+        if chain.len() == 1 {
+            if let Some((_, label)) = attrs.into_iter().rev().find(|(key, _)| key == "label") {
+                self.graph[chain[0]] = Node::from(label);
+            }
+        } else {
+            let weight = match self.policy {
+                Some(_) => match attrs.iter().rev().find(|(key, _)| key == "weight") {
+                    Some((_, weight)) => weight
+                        .parse()
+                        .map_err(|_| self.error(&format!("invalid weight `{}`", weight)))?,
+                    None => 1.0,
+                },
+                None => 1.0,
+            };
+            for (i, pair) in chain.windows(2).enumerate() {
+                let edge = Edge {
+                    from: pair[0],
+                    to: pair[1],
+                };
+                let policy = self.policy.unwrap_or(MergePolicy::KeepFirst);
+                if self
+                    .graph
+                    .add_weighted_edge_merging(edge, weight, policy)
+                    .is_err()
+                {
+                    return Err(DotError {
+                        line: self.tokens[start].1,
+                        message: format!(
+                            "duplicate edge `{} {} {}`",
+                            names[i],
+                            arrow(self.graph.kind()),
+                            names[i + 1]
+                        ),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Zero or more `[a=b, c=d]` lists.
+    fn attr_list(&mut self) -> Result<Vec<(String, String)>, DotError> {
+        let mut attrs = Vec::new();
+        while self.peek() == Some(&Token::LBracket) {
+            self.pos += 1;
+            loop {
+                match self.peek() {
+                    Some(Token::RBracket) => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(Token::Comma) | Some(Token::Semicolon) => self.pos += 1,
+                    _ => {
+                        let key = self.id()?;
+                        self.expect(Token::Equals)?;
+                        let value = self.id()?;
+                        attrs.push((key, value));
+                    }
+                }
+            }
+        }
+        Ok(attrs)
+    }
+
+    fn node(&mut self, id: &str) -> NodeIndex {
+        match self.ids.get(id) {
+            Some(node_idx) => *node_idx,
+            None => {
+                let node_idx = self.graph.add_node_unchecked(Node::from(id));
+                self.ids.insert(id.to_string(), node_idx);
+                node_idx
+            }
+        }
+    }
+
+    fn id(&mut self) -> Result<String, DotError> {
+        match self.tokens.get(self.pos) {
+            Some((Token::Id(id), _)) => {
+                self.pos += 1;
+                Ok(id.clone())
+            }
+            _ => Err(self.error("expected an ID")),
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), DotError> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {:?}", token)))
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    // Keywords are case-insensitive in DOT.
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword))
+    }
+
+    fn error(&self, message: &str) -> DotError {
+        let line = match self.tokens.get(self.pos) {
+            Some((_, line)) => *line,
+            None => self.tokens.last().map_or(1, |(_, line)| *line),
+        };
+        DotError {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+fn tokenize(dot: &str) -> Result<Vec<(Token, usize)>, DotError> {
+    let mut tokens = Vec::new();
+    let mut chars = dot.chars().peekable();
+    let mut line = 1;
+    let error = |line: usize, message: &str| DotError {
+        line,
+        message: message.to_string(),
+    };
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '#' => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.next_if_eq(&'/').is_some() => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            previous = c;
+                        }
+                        None => return Err(error(line, "unterminated comment")),
+                    }
+                }
+                continue;
+            }
+            '{' => Token::LBrace,
+            '}' => Token::RBrace,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '=' => Token::Equals,
+            ';' => Token::Semicolon,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '-' if chars.next_if_eq(&'>').is_some() => Token::Arrow(GraphKind::Directed),
+            '-' if chars.next_if_eq(&'-').is_some() => Token::Arrow(GraphKind::Undirected),
+            '<' => return Err(error(line, "HTML strings aren't supported")),
+            '"' => {
+                let start = line;
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('"') => id.push('"'),
+                            Some('\\') => id.push('\\'),
+                            Some('n') => id.push('\n'),
+                            // line continuation
+                            Some('\n') => line += 1,
+                            Some(c) => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                            None => return Err(error(start, "unterminated string")),
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            id.push(c);
+                        }
+                        None => return Err(error(start, "unterminated string")),
+                    }
+                }
+                Token::Id(id)
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+                {
+                    id.push(c);
+                }
+                Token::Id(id)
+            }
+            c => return Err(error(line, &format!("unexpected `{}`", c))),
+        };
+        tokens.push((token, line));
+    }
+
+    Ok(tokens)
+}
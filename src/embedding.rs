@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+
+use crate::graph::{Graph, NodeIndex};
+use crate::rng::Rng;
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Spectral embedding (Laplacian eigenmaps): `dims` coordinates per node
+    such that connected nodes end up close to each other, e.g. as features
+    for a model or to lay the graph out.
+
+    Edges are read in both directions and without weights. Takes the
+    eigenvectors of the normalized Laplacian `I - D^-1/2 A D^-1/2` with the
+    smallest eigenvalues, skipping the first one (constant on every
+    component), and scales them back by `D^-1/2`. Every vector's sign is
+    fixed so its largest component is positive, so results are reproducible.
+    Asking for more dimensions than `nodes - 1` returns fewer.
+
+    Dense eigendecomposition, cubic in the number of nodes.
+    */
+    #[cfg(feature = "nalgebra")]
+    pub fn spectral_embedding(&self, dims: usize) -> Vec<Vec<f64>> {
+        use nalgebra::{DMatrix, SymmetricEigen};
+
+        let n = self.nodes.len();
+        let mut adjacency = DMatrix::<f64>::zeros(n, n);
+        for edge in self.edges.iter().filter(|e| e.from != e.to) {
+            adjacency[(edge.from, edge.to)] = 1.0;
+            adjacency[(edge.to, edge.from)] = 1.0;
+        }
+        let scale: Vec<f64> = adjacency
+            .row_iter()
+            .map(|row| {
+                let degree = row.sum();
+                if degree > 0.0 {
+                    1.0 / degree.sqrt()
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        let laplacian = DMatrix::from_fn(n, n, |i, j| {
+            let identity = if i == j { 1.0 } else { 0.0 };
+            identity - scale[i] * adjacency[(i, j)] * scale[j]
+        });
+        let eigen = SymmetricEigen::new(laplacian);
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|a, b| eigen.eigenvalues[*a].total_cmp(&eigen.eigenvalues[*b]));
+
+        let mut embedding = vec![Vec::new(); n];
+        for column in order.into_iter().skip(1).take(dims) {
+            let vector = eigen.eigenvectors.column(column);
+            let largest = vector
+                .iter()
+                .copied()
+                .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+                .unwrap_or(1.0);
+            let sign = if largest < 0.0 { -1.0 } else { 1.0 };
+            for (node_idx, coordinates) in embedding.iter_mut().enumerate() {
+                coordinates.push(sign * vector[node_idx] * scale[node_idx]);
+            }
+        }
+        embedding
+    }
+
+    /*
+    DeepWalk-style corpus: `walks_per_node` random walks of up to
+    `walk_length` nodes from every node, written one walk per line as
+    space-separated node indexes, which is the plain text format word2vec
+    (and gensim, fastText...) train on. Walks follow edge direction and stop
+    early at nodes without outgoing edges.
+
+    Walks are written round by round, every round starting once from every
+    node in index order. The same `seed` always writes the same corpus.
+    */
+    pub fn write_random_walks(
+        &self,
+        mut out: impl Write,
+        walks_per_node: usize,
+        walk_length: usize,
+        seed: u64,
+    ) -> io::Result<()> {
+        let mut rng = Rng::new(seed);
+        let mut walk: Vec<NodeIndex> = Vec::with_capacity(walk_length);
+
+        for _ in 0..walks_per_node {
+            for start in 0..self.nodes.len() {
+                walk.clear();
+                let mut current = start;
+                while walk.len() < walk_length {
+                    walk.push(current);
+                    let successors = &self.outgoing[current];
+                    if successors.is_empty() {
+                        break;
+                    }
+                    current = successors[rng.below(successors.len())];
+                }
+
+                let line: Vec<String> = walk.iter().map(|n| n.to_string()).collect();
+                writeln!(out, "{}", line.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+}
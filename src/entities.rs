@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::graph::{Edge, Graph, GraphKind, Node, NodeIndex};
+
+/*
+A small JSON schema (the `json` feature) for tools that want to hand a graph
+over without knowing any graph format, call graphs, import graphs, service
+maps:
+
+    {
+        "directed": true,
+        "attributes": { "tool": "pyan", "root": "src/" },
+        "entities": [
+            { "id": "app.main" },
+            { "id": "app.utils.load", "kind": "function" },
+            { "id": 42 }
+        ],
+        "relations": [
+            { "from": "app.main", "to": "app.utils.load", "kind": "calls", "weight": 3 },
+            { "from": "app.main", "to": "json", "kind": "imports" }
+        ]
+    }
+
+Everything but `from` and `to` is optional:
+- `directed` defaults to true, false makes an undirected graph
+- `attributes` are graph attributes, strings only
+- `entities` lists the nodes, with a unique string or integer `id`, other
+  fields are ignored. Entities only named by relations (`json` above, an
+  external module) don't have to be listed, they're added after the listed
+  ones, in order of appearance
+- `relations` are edges, `kind` defaults to an empty string and `weight`
+  to 1, other fields are ignored
+*/
+
+// A document `from_entities_json` couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct EntitiesError {
+    pub message: String,
+}
+
+impl fmt::Display for EntitiesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid entities JSON: {}", self.message)
+    }
+}
+
+impl std::error::Error for EntitiesError {}
+
+// Edge payload of `from_entities_json`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Relation {
+    pub kind: String,
+    pub weight: f64,
+}
+
+impl Graph<Node, Relation> {
+    /*
+    Read an entities and relations document (see the top of `entities.rs`).
+    Nodes are `DataTypes::Text` or `DataTypes::Integer` with their `id`.
+    Every relation is an edge, several relations between the same entities
+    (a call and an import) stay separate parallel edges.
+    */
+    pub fn from_entities_json(json: &str) -> Result<Self, EntitiesError> {
+        let error = |message: String| EntitiesError { message };
+        let document: Value = serde_json::from_str(json).map_err(|err| error(err.to_string()))?;
+        if !document.is_object() {
+            return Err(error("the document should be an object".to_string()));
+        }
+
+        let kind = match document.get("directed") {
+            None | Some(Value::Null) | Some(Value::Bool(true)) => GraphKind::Directed,
+            Some(Value::Bool(false)) => GraphKind::Undirected,
+            Some(_) => return Err(error("`directed` should be a boolean".to_string())),
+        };
+        let mut graph = Graph::with_kind(kind);
+        match document.get("attributes") {
+            None | Some(Value::Null) => {}
+            Some(Value::Object(attributes)) => {
+                for (key, value) in attributes {
+                    let Value::String(value) = value else {
+                        return Err(error(format!("attribute {:?} isn't a string", key)));
+                    };
+                    graph.set_attr(key.clone(), value.clone());
+                }
+            }
+            Some(_) => return Err(error("`attributes` should be an object".to_string())),
+        }
+
+        let list = |field: &str| match document.get(field) {
+            None | Some(Value::Null) => Ok(&[][..]),
+            Some(Value::Array(items)) => Ok(items.as_slice()),
+            Some(_) => Err(error(format!("`{}` should be an array", field))),
+        };
+        let entities = list("entities")?;
+        let relations = list("relations")?;
+
+        let mut ids: HashMap<&Value, NodeIndex> = HashMap::new();
+        for entity in entities {
+            let id = entity_id(entity.get("id")).ok_or_else(|| {
+                error(format!("entity without a string or integer id: {}", entity))
+            })?;
+            if ids.contains_key(id) {
+                return Err(error(format!("duplicate entity id {}", id)));
+            }
+            ids.insert(id, graph.add_node_unchecked(node(id)));
+        }
+
+        for relation in relations {
+            let mut endpoint = |field: &str| {
+                let id = entity_id(relation.get(field)).ok_or_else(|| {
+                    error(format!(
+                        "relation without a string or integer {}: {}",
+                        field, relation
+                    ))
+                })?;
+                Ok(*ids
+                    .entry(id)
+                    .or_insert_with(|| graph.add_node_unchecked(node(id))))
+            };
+            let from = endpoint("from")?;
+            let to = endpoint("to")?;
+
+            let kind = match relation.get("kind") {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(kind)) => kind.clone(),
+                Some(other) => return Err(error(format!("invalid relation kind {}", other))),
+            };
+            let weight = match relation.get("weight") {
+                None | Some(Value::Null) => 1.0,
+                Some(weight) => weight
+                    .as_f64()
+                    .ok_or_else(|| error(format!("invalid relation weight {}", weight)))?,
+            };
+            graph.push_edge(Edge { from, to }, Relation { kind, weight });
+        }
+
+        Ok(graph)
+    }
+}
+
+fn entity_id(id: Option<&Value>) -> Option<&Value> {
+    match id {
+        Some(Value::String(_)) => id,
+        Some(Value::Number(number)) if number.as_i64().is_some() => id,
+        _ => None,
+    }
+}
+
+fn node(id: &Value) -> Node {
+    match id {
+        Value::String(id) => Node::from(id.as_str()),
+        // `entity_id` only lets integers through
+        id => Node::from(id.as_i64().unwrap_or_default() as isize),
+    }
+}
@@ -0,0 +1,86 @@
+use crate::graph::{EdgeIndex, Graph, NodeIndex};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    A feedback arc set: edges that leave the graph acyclic once removed (or
+    reversed). Finding the smallest one is NP-hard, this is the greedy
+    heuristic by Eades, Lin and Smyth:
+    1. Peel off sinks to the end of an ordering and sources to its start.
+    2. When there's neither, move the node with the biggest out - in degree
+       to the start (ties go to the lowest index).
+    3. The edges going backwards in that ordering are the set.
+
+    Self-loops are always part of it. Edges are read as stored, also in
+    undirected graphs. Sorted by index, O(n^2 + m).
+    */
+    pub fn feedback_arc_set(&self) -> Vec<EdgeIndex> {
+        let n = self.nodes.len();
+        let mut out_edges: Vec<Vec<NodeIndex>> = vec![Vec::new(); n];
+        let mut in_edges: Vec<Vec<NodeIndex>> = vec![Vec::new(); n];
+        for edge in self.edges.iter().filter(|edge| edge.from != edge.to) {
+            out_edges[edge.from].push(edge.to);
+            in_edges[edge.to].push(edge.from);
+        }
+        let mut out_degrees: Vec<usize> = out_edges.iter().map(|e| e.len()).collect();
+        let mut in_degrees: Vec<usize> = in_edges.iter().map(|e| e.len()).collect();
+
+        let mut removed = vec![false; n];
+        let mut start = Vec::with_capacity(n);
+        let mut end = Vec::new();
+
+        for _ in 0..n {
+            let remaining: Vec<NodeIndex> = (0..n).filter(|v| !removed[*v]).collect();
+            let sink = remaining.iter().copied().find(|v| out_degrees[*v] == 0);
+            let source = remaining.iter().copied().find(|v| in_degrees[*v] == 0);
+            let node_idx = match (sink, source) {
+                (Some(sink), _) => {
+                    end.push(sink);
+                    sink
+                }
+                (None, Some(source)) => {
+                    start.push(source);
+                    source
+                }
+                (None, None) => {
+                    let mut best = remaining[0];
+                    for v in remaining.iter().copied() {
+                        if out_degrees[v] + in_degrees[best] > out_degrees[best] + in_degrees[v] {
+                            best = v;
+                        }
+                    }
+                    start.push(best);
+                    best
+                }
+            };
+
+            removed[node_idx] = true;
+            for to in out_edges[node_idx].iter() {
+                in_degrees[*to] -= 1;
+            }
+            for from in in_edges[node_idx].iter() {
+                out_degrees[*from] -= 1;
+            }
+        }
+
+        let mut position = vec![0; n];
+        for (pos, node_idx) in start.into_iter().chain(end.into_iter().rev()).enumerate() {
+            position[node_idx] = pos;
+        }
+        (0..self.edges.len())
+            .filter(|edge_idx| {
+                let edge = &self.edges[*edge_idx];
+                position[edge.from] >= position[edge.to]
+            })
+            .collect()
+    }
+
+    // Fraction of the edges in `feedback_arc_set`: 0 for a DAG, higher the
+    // less hierarchical the graph is. An upper bound, the heuristic can pick
+    // more edges than needed. 0 for a graph without edges.
+    pub fn dagness(&self) -> f64 {
+        if self.edges.is_empty() {
+            return 0.0;
+        }
+        self.feedback_arc_set().len() as f64 / self.edges.len() as f64
+    }
+}
@@ -0,0 +1,122 @@
+use crate::graph::{Cost, Edge, Graph, NodeIndex};
+use crate::measure::Measure;
+
+// All-pairs shortest path costs, plus the first hop of every shortest path so
+// paths can be rebuilt without running anything again. `K` is the cost type,
+// see `Measure`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct DistanceMatrix<K = Cost> {
+    node_count: usize,
+    // row-major, `distances[from * node_count + to]`
+    distances: Vec<Option<K>>,
+    next_hops: Vec<Option<NodeIndex>>,
+}
+
+impl<K: Measure> DistanceMatrix<K> {
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    // `None` if `to` can't be reached from `from`.
+    pub fn distance(&self, from: NodeIndex, to: NodeIndex) -> Option<K> {
+        self.distances.get(self.cell(from, to)?).copied().flatten()
+    }
+
+    // Path from `from` to `to`, both included.
+    pub fn path(&self, from: NodeIndex, to: NodeIndex) -> Option<Vec<NodeIndex>> {
+        self.distance(from, to)?;
+
+        let mut path = vec![from];
+        let mut current = from;
+        while current != to {
+            current = self.next_hops[current * self.node_count + to]?;
+            path.push(current);
+            // only possible with negative cycles
+            if path.len() > self.node_count {
+                return None;
+            }
+        }
+        Some(path)
+    }
+
+    // With negative cycles some distances are meaningless (they could be made
+    // as small as wanted by going around the cycle).
+    pub fn has_negative_cycle(&self) -> bool {
+        (0..self.node_count).any(|n| self.distance(n, n).is_some_and(|d| d < K::zero()))
+    }
+
+    fn cell(&self, from: NodeIndex, to: NodeIndex) -> Option<usize> {
+        if from < self.node_count && to < self.node_count {
+            Some(from * self.node_count + to)
+        } else {
+            None
+        }
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // Floyd-Warshall using the edge payloads as costs.
+    pub fn floyd_warshall(&self) -> DistanceMatrix<E>
+    where
+        E: Measure,
+    {
+        self.floyd_warshall_by(|_, weight| *weight)
+    }
+
+    /*
+    Floyd-Warshall, O(V^3) time and O(V^2) memory no matter how many edges
+    there are, so it's best for small and dense graphs. For sparse ones
+    running `dijkstra_all` from every node is usually faster.
+
+    `edge_cost` gives the cost of every edge (`|_, _| 1` for hop counts).
+    Negative costs are fine, see `DistanceMatrix::has_negative_cycle`. Paths
+    whose cost overflows (see `Measure::checked_add`) are ignored.
+    */
+    pub fn floyd_warshall_by<K: Measure>(
+        &self,
+        edge_cost: impl Fn(&Edge, &E) -> K,
+    ) -> DistanceMatrix<K> {
+        let n = self.nodes.len();
+        let mut distances: Vec<Option<K>> = vec![None; n * n];
+        let mut next_hops: Vec<Option<NodeIndex>> = vec![None; n * n];
+
+        for node_idx in 0..n {
+            distances[node_idx * n + node_idx] = Some(K::zero());
+            next_hops[node_idx * n + node_idx] = Some(node_idx);
+        }
+        for (edge_idx, arc) in self.arcs() {
+            let cell = arc.from * n + arc.to;
+            let cost = edge_cost(&arc, &self.edge_weights[edge_idx]);
+            if distances[cell].is_none_or(|current| cost < current) {
+                distances[cell] = Some(cost);
+                next_hops[cell] = Some(arc.to);
+            }
+        }
+
+        for k in 0..n {
+            for i in 0..n {
+                let Some(i_to_k) = distances[i * n + k] else {
+                    continue;
+                };
+                for j in 0..n {
+                    let Some(k_to_j) = distances[k * n + j] else {
+                        continue;
+                    };
+                    let Some(candidate) = i_to_k.checked_add(k_to_j) else {
+                        continue;
+                    };
+                    if distances[i * n + j].is_none_or(|current| candidate < current) {
+                        distances[i * n + j] = Some(candidate);
+                        next_hops[i * n + j] = next_hops[i * n + k];
+                    }
+                }
+            }
+        }
+
+        DistanceMatrix {
+            node_count: n,
+            distances,
+            next_hops,
+        }
+    }
+}
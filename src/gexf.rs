@@ -0,0 +1,141 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::graph::{EdgeIndex, EdgeWeight, Graph, GraphKind, NodeIndex};
+use crate::xml;
+
+// When a node or edge exists, for dynamic graphs. `None` is unbounded.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct Spell {
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+}
+
+type NodeSpell<'a, N> = &'a dyn Fn(NodeIndex, &N) -> Spell;
+type EdgeSpell<'a, E> = &'a dyn Fn(EdgeIndex, &E) -> Spell;
+
+/*
+Extras for `write_gexf_with`:
+- `edge_weight`: the `weight` of every edge, e.g. `&|_, w| *w as f64`
+- `node_spell` and `edge_spell`: when nodes and edges exist. With either of
+  them the graph is written as dynamic (`timeformat="double"`), Gephi can
+  then play it back on its timeline.
+*/
+pub struct GexfOptions<'a, N, E> {
+    pub edge_weight: Option<EdgeWeight<'a, E>>,
+    pub node_spell: Option<NodeSpell<'a, N>>,
+    pub edge_spell: Option<EdgeSpell<'a, E>>,
+}
+
+impl<N, E> Default for GexfOptions<'_, N, E> {
+    fn default() -> Self {
+        GexfOptions {
+            edge_weight: None,
+            node_spell: None,
+            edge_spell: None,
+        }
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Display,
+{
+    /*
+    The graph in GEXF 1.3, for Gephi. Nodes and edges are identified by
+    index and labeled with `Display`. The `creator`, `description` and
+    `keywords` graph attributes (`set_attr`) go into the file's `meta`.
+    */
+    pub fn write_gexf(&self, out: &mut impl Write) -> io::Result<()> {
+        self.write_gexf_with(out, &GexfOptions::default())
+    }
+
+    pub fn write_gexf_with(
+        &self,
+        out: &mut impl Write,
+        options: &GexfOptions<'_, N, E>,
+    ) -> io::Result<()> {
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<gexf xmlns="http://gexf.net/1.3" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://gexf.net/1.3 http://gexf.net/1.3/gexf.xsd" version="1.3">"#
+        )?;
+
+        let meta: Vec<(&str, &String)> = ["creator", "description", "keywords"]
+            .into_iter()
+            .filter_map(|key| Some((key, self.attrs.get(key)?)))
+            .collect();
+        if !meta.is_empty() {
+            writeln!(out, "  <meta>")?;
+            for (key, value) in meta {
+                writeln!(out, "    <{0}>{1}</{0}>", key, xml::escape(value))?;
+            }
+            writeln!(out, "  </meta>")?;
+        }
+
+        let edge_type = match self.kind {
+            GraphKind::Directed => "directed",
+            GraphKind::Undirected => "undirected",
+        };
+        let mode = if options.node_spell.is_some() || options.edge_spell.is_some() {
+            r#"mode="dynamic" timeformat="double""#
+        } else {
+            r#"mode="static""#
+        };
+        writeln!(out, r#"  <graph defaultedgetype="{}" {}>"#, edge_type, mode)?;
+
+        writeln!(out, "    <nodes>")?;
+        for (node_idx, node) in self.nodes.iter().enumerate() {
+            let spell = options
+                .node_spell
+                .map(|spell| spell(node_idx, node))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                r#"      <node id="{}" label="{}"{}/>"#,
+                node_idx,
+                xml::escape(&node.to_string()),
+                spell_attrs(spell)
+            )?;
+        }
+        writeln!(out, "    </nodes>")?;
+
+        writeln!(out, "    <edges>")?;
+        for (edge_idx, (edge, weight)) in
+            self.edges.iter().zip(self.edge_weights.iter()).enumerate()
+        {
+            let spell = options
+                .edge_spell
+                .map(|spell| spell(edge_idx, weight))
+                .unwrap_or_default();
+            let weight = options
+                .edge_weight
+                .map(|edge_weight| format!(r#" weight="{}""#, edge_weight(edge_idx, weight)))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                r#"      <edge id="{}" source="{}" target="{}"{}{}/>"#,
+                edge_idx,
+                edge.from,
+                edge.to,
+                weight,
+                spell_attrs(spell)
+            )?;
+        }
+        writeln!(out, "    </edges>")?;
+
+        writeln!(out, "  </graph>")?;
+        writeln!(out, "</gexf>")
+    }
+}
+
+fn spell_attrs(spell: Spell) -> String {
+    let mut attrs = String::new();
+    if let Some(start) = spell.start {
+        attrs.push_str(&format!(r#" start="{}""#, start));
+    }
+    if let Some(end) = spell.end {
+        attrs.push_str(&format!(r#" end="{}""#, end));
+    }
+    attrs
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+use crate::graph::{Edge, EdgeWeight, Graph, GraphKind, Node, NodeIndex};
+
+/*
+GML, the `key value` format many classic network datasets (karate club,
+football, dolphins...) are distributed in:
+
+    graph [
+      directed 1
+      node [ id 1 label "a" ]
+      node [ id 2 label "b" ]
+      edge [ source 1 target 2 weight 0.5 ]
+    ]
+
+Values are numbers, "strings" (no escapes, `&quot;` style entities instead)
+or `[ ... ]` lists of more pairs, `#` starts a comment line.
+*/
+
+// Lists nested deeper than this are an error, real files stop at 3 and
+// parsing recurses once per level.
+const MAX_DEPTH: usize = 128;
+
+// A GML file `read_gml` couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct GmlError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for GmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GML line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for GmlError {}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Display,
+{
+    // Node `i` gets id `i` and is labeled with `Display`, graph attributes
+    // (`set_attr`) are written as strings.
+    pub fn write_gml(&self, out: &mut impl Write) -> io::Result<()> {
+        self.write_gml_edges(out, None)
+    }
+
+    // `write_gml`, with a `weight` for every edge, e.g. `&|_, w| *w as f64`.
+    pub fn write_gml_weighted(
+        &self,
+        out: &mut impl Write,
+        edge_weight: EdgeWeight<'_, E>,
+    ) -> io::Result<()> {
+        self.write_gml_edges(out, Some(edge_weight))
+    }
+
+    fn write_gml_edges(
+        &self,
+        out: &mut impl Write,
+        edge_weight: Option<EdgeWeight<'_, E>>,
+    ) -> io::Result<()> {
+        writeln!(out, "graph [")?;
+        let directed = match self.kind {
+            GraphKind::Directed => 1,
+            GraphKind::Undirected => 0,
+        };
+        writeln!(out, "  directed {}", directed)?;
+        for (key, value) in self.attrs.iter() {
+            if is_key(key) && key != "directed" {
+                writeln!(out, "  {} {}", key, quote(value))?;
+            }
+        }
+
+        for (node_idx, node) in self.nodes.iter().enumerate() {
+            writeln!(
+                out,
+                "  node [ id {} label {} ]",
+                node_idx,
+                quote(&node.to_string())
+            )?;
+        }
+        for (edge_idx, edge) in self.edges.iter().enumerate() {
+            match edge_weight {
+                Some(weight) => writeln!(
+                    out,
+                    "  edge [ source {} target {} weight {} ]",
+                    edge.from,
+                    edge.to,
+                    weight(edge_idx, &self.edge_weights[edge_idx])
+                )?,
+                None => writeln!(out, "  edge [ source {} target {} ]", edge.from, edge.to)?,
+            }
+        }
+        writeln!(out, "]")
+    }
+}
+
+impl Graph<Node, f64> {
+    /*
+    Read the first `graph` of a GML file. Nodes are `DataTypes::Text` with
+    their `label`, or `DataTypes::Integer` with their id when they have none,
+    in file order. Edges keep their `weight` (or `value`, 1 when there's
+    neither) and their order, parallel edges included (`merge_parallel_edges`
+    combines them). `directed 1` makes a directed graph, the default is
+    undirected. Other graph-level numbers and strings end up in `attr`, the
+    rest (`graphics`, extra node and edge keys...) is skipped.
+    */
+    pub fn read_gml(gml: &str) -> Result<Self, GmlError> {
+        let tokens = tokenize(gml)?;
+        let mut pos = 0;
+        let document = parse_list(&tokens, &mut pos, 0)?;
+
+        let Some((_, Value::List(pairs))) = document.iter().find(|(key, _)| key.text == "graph")
+        else {
+            return Err(GmlError {
+                line: 1,
+                message: "no `graph [ ... ]`".to_string(),
+            });
+        };
+
+        let directed = pairs
+            .iter()
+            .find(|(key, _)| key.text == "directed")
+            .is_some_and(|(_, value)| matches!(value, Value::Scalar(v) if v.text == "1"));
+        let kind = if directed {
+            GraphKind::Directed
+        } else {
+            GraphKind::Undirected
+        };
+        let mut graph = Graph::with_kind(kind);
+
+        let mut ids: HashMap<&str, NodeIndex> = HashMap::new();
+        for (key, value) in pairs {
+            match (key.text.as_str(), value) {
+                ("node", Value::List(fields)) => {
+                    let id = scalar(fields, "id")
+                        .ok_or_else(|| key.error("node without an id".to_string()))?;
+                    if ids.contains_key(id.text.as_str()) {
+                        return Err(id.error(format!("duplicate node id {}", id.text)));
+                    }
+                    let node = match scalar(fields, "label") {
+                        Some(label) => Node::from(label.text.as_str()),
+                        None => match id.text.parse::<isize>() {
+                            Ok(number) => Node::from(number),
+                            Err(_) => Node::from(id.text.as_str()),
+                        },
+                    };
+                    ids.insert(&id.text, graph.nodes.len());
+                    graph.nodes.push(node);
+                }
+                ("directed", _) | ("edge", _) => {}
+                (_, Value::Scalar(scalar)) => {
+                    graph.set_attr(key.text.as_str(), scalar.text.as_str());
+                }
+                _ => {}
+            }
+        }
+
+        for (key, value) in pairs {
+            let ("edge", Value::List(fields)) = (key.text.as_str(), value) else {
+                continue;
+            };
+            let endpoint = |field: &str| {
+                let id = scalar(fields, field)
+                    .ok_or_else(|| key.error(format!("edge without a {}", field)))?;
+                ids.get(id.text.as_str())
+                    .copied()
+                    .ok_or_else(|| id.error(format!("edge to an unknown node {}", id.text)))
+            };
+            let edge = Edge {
+                from: endpoint("source")?,
+                to: endpoint("target")?,
+            };
+            let weight = match scalar(fields, "weight").or_else(|| scalar(fields, "value")) {
+                Some(weight) => weight
+                    .text
+                    .parse()
+                    .map_err(|_| weight.error(format!("invalid weight {}", weight.text)))?,
+                None => 1.0,
+            };
+            graph.edges.push(edge);
+            graph.edge_weights.push(weight);
+        }
+
+        graph.node_lookup.catch_up(&graph.nodes);
+        graph.rebuild_adjacency();
+        Ok(graph)
+    }
+}
+
+// A key or a number/string value, strings already unquoted.
+#[derive(Debug)]
+struct Token {
+    text: String,
+    line: usize,
+    kind: TokenKind,
+}
+
+#[derive(PartialEq, Debug)]
+enum TokenKind {
+    Word,
+    Str,
+    Open,
+    Close,
+}
+
+impl Token {
+    fn error(&self, message: String) -> GmlError {
+        GmlError {
+            line: self.line,
+            message,
+        }
+    }
+}
+
+enum Value<'a> {
+    Scalar(&'a Token),
+    List(Vec<(&'a Token, Value<'a>)>),
+}
+
+// First scalar value of `key` in a list.
+fn scalar<'a>(pairs: &[(&'a Token, Value<'a>)], key: &str) -> Option<&'a Token> {
+    pairs.iter().find_map(|(k, value)| match value {
+        Value::Scalar(token) if k.text == key => Some(*token),
+        _ => None,
+    })
+}
+
+fn parse_list<'a>(
+    tokens: &'a [Token],
+    pos: &mut usize,
+    depth: usize,
+) -> Result<Vec<(&'a Token, Value<'a>)>, GmlError> {
+    let nested = depth > 0;
+    let mut pairs = Vec::new();
+    loop {
+        let Some(key) = tokens.get(*pos) else {
+            if nested {
+                let line = tokens.last().map_or(1, |token| token.line);
+                return Err(GmlError {
+                    line,
+                    message: "missing `]`".to_string(),
+                });
+            }
+            return Ok(pairs);
+        };
+        *pos += 1;
+        match key.kind {
+            TokenKind::Close if nested => return Ok(pairs),
+            TokenKind::Word => {}
+            _ => return Err(key.error("expected a key".to_string())),
+        }
+
+        let value = tokens
+            .get(*pos)
+            .ok_or_else(|| key.error(format!("`{}` without a value", key.text)))?;
+        *pos += 1;
+        let value = match value.kind {
+            TokenKind::Open if depth == MAX_DEPTH => {
+                return Err(value.error(format!("lists nested deeper than {}", MAX_DEPTH)))
+            }
+            TokenKind::Open => Value::List(parse_list(tokens, pos, depth + 1)?),
+            TokenKind::Word | TokenKind::Str => Value::Scalar(value),
+            TokenKind::Close => return Err(value.error(format!("`{}` without a value", key.text))),
+        };
+        pairs.push((key, value));
+    }
+}
+
+fn tokenize(gml: &str) -> Result<Vec<Token>, GmlError> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut chars = gml.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '#' => while chars.next_if(|c| *c != '\n').is_some() {},
+            '[' | ']' => tokens.push(Token {
+                text: c.to_string(),
+                line,
+                kind: if c == '[' {
+                    TokenKind::Open
+                } else {
+                    TokenKind::Close
+                },
+            }),
+            '"' => {
+                let start = line;
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            text.push(c);
+                        }
+                        None => {
+                            return Err(GmlError {
+                                line: start,
+                                message: "unterminated string".to_string(),
+                            })
+                        }
+                    }
+                }
+                tokens.push(Token {
+                    text: unescape(&text),
+                    line: start,
+                    kind: TokenKind::Str,
+                });
+            }
+            c => {
+                let mut text = c.to_string();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !"[]\"".contains(*c)) {
+                    text.push(c);
+                }
+                tokens.push(Token {
+                    text,
+                    line,
+                    kind: TokenKind::Word,
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// GML strings can't hold `"`, writers use HTML entities instead.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('&', "&amp;").replace('"', "&quot;"))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&quot;", "\"").replace("&amp;", "&")
+}
+
+// GML keys are letters followed by letters and digits.
+fn is_key(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric())
+}
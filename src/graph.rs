@@ -1,25 +1,43 @@
 #![allow(dead_code, unused_variables, unused_labels)]
 
 use core::panic;
-use std::collections::{HashSet, VecDeque};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::convert::From;
-
-type NodeIndex = usize;
-type EdgeIndex = usize;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Index, IndexMut};
+
+use smallvec::SmallVec;
+
+use crate::limits::{self, Limit, LimitExceeded, Limits};
+use crate::lookup::NodeLookup;
+use crate::trace::{snapshot, Trace, TraceEvent};
+
+pub type NodeIndex = usize;
+pub type EdgeIndex = usize;
+// Path lengths for Johnson's algorithm (`all_pairs_shortest_paths_sparse`),
+// which has to subtract costs: edge payloads are converted into this with
+// `Into<Cost>`. The other shortest path algorithms take any `Measure`.
+pub type Cost = i64;
+// How the exporters (GEXF, GML, Pajek, Matrix Market...) and the matrix
+// conversions turn an edge payload into a number, e.g. `&|_, w| *w as f64`.
+pub(crate) type EdgeWeight<'a, E> = &'a dyn Fn(EdgeIndex, &E) -> f64;
 
 #[derive(PartialEq, Eq, Hash, Debug)]
 struct PathStep(NodeIndex, EdgeIndex);
 type Path = Vec<PathStep>;
 // type NodeData = String;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataTypes {
     Text(String),
     Blob(Vec<u8>),
     Integer(isize),
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     pub data: DataTypes,
 }
@@ -56,75 +74,564 @@ impl From<Vec<u8>> for Node {
     }
 }
 
+/*
+A node payload borrowed from somewhere else (an arena, a buffer it was read
+from) instead of owned, so big text and blobs aren't copied. `to_node` copies
+one out when it has to outlive what it borrows from.
+
+`Node::to_bytes` writes a payload as bytes and `DataRef::from_bytes` reads it
+back without copying: a blob read that way points into the buffer.
+*/
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub enum DataRef<'a> {
+    Text(&'a str),
+    Blob(&'a [u8]),
+    Integer(isize),
+}
+
+impl<'a> DataRef<'a> {
+    pub fn to_node(self) -> Node {
+        match self {
+            DataRef::Text(v) => Node::from(v),
+            DataRef::Blob(v) => Node::from(v.to_vec()),
+            DataRef::Integer(v) => Node::from(v),
+        }
+    }
+
+    // `None` if the bytes weren't written by `Node::to_bytes`.
+    pub fn from_bytes(bytes: &'a [u8]) -> Option<DataRef<'a>> {
+        let (tag, value) = bytes.split_first()?;
+        match tag {
+            0 => std::str::from_utf8(value).ok().map(DataRef::Text),
+            1 => Some(DataRef::Integer(
+                i64::from_le_bytes(value.try_into().ok()?) as isize
+            )),
+            2 => Some(DataRef::Blob(value)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<&'a Node> for DataRef<'a> {
+    fn from(node: &'a Node) -> DataRef<'a> {
+        match &node.data {
+            DataTypes::Text(v) => DataRef::Text(v),
+            DataTypes::Blob(v) => DataRef::Blob(v),
+            DataTypes::Integer(v) => DataRef::Integer(*v),
+        }
+    }
+}
+
 impl Node {
-    fn extract_int(&self) -> isize {
-        match self.data {
-            DataTypes::Integer(x) => x,
-            _ => panic!("Method only available for int data"),
+    // A tag byte for the `DataTypes` variant, then the value (integers as
+    // 64-bit little-endian). See `DataRef::from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_bytes(&mut out);
+        out
+    }
+
+    pub(crate) fn write_bytes(&self, out: &mut Vec<u8>) {
+        match &self.data {
+            DataTypes::Text(text) => {
+                out.push(0);
+                out.extend_from_slice(text.as_bytes());
+            }
+            DataTypes::Integer(v) => {
+                out.push(1);
+                out.extend_from_slice(&(*v as i64).to_le_bytes());
+            }
+            DataTypes::Blob(bytes) => {
+                out.push(2);
+                out.extend_from_slice(bytes);
+            }
         }
     }
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge {
     pub from: NodeIndex,
     pub to: NodeIndex,
 }
 
+/*
+How edges are read. In an `Undirected` graph an edge connects both of its
+nodes both ways: the neighbour queries (`reachable_nodes_from`,
+`nodes_that_can_reach`), `bfs_distance`, `shortest_path` and `boundary` treat
+`a -> b` as `b -> a` too, and adding `b -> a` when `a -> b` exists is adding the
+same edge again. Edges are still stored once, with the orientation they were
+added with, and the weighted searches (`dijkstra`, `bellman_ford`,
+`floyd_warshall`...) walk them both ways too.
+*/
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GraphKind {
+    #[default]
+    Directed,
+    Undirected,
+}
+
+// A node payload rejected by the graph's node validator, see
+// `Graph::set_node_validator`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct InvalidNode {
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid node: {}", self.reason)
+    }
+}
+
+impl std::error::Error for InvalidNode {}
+
+// Boxed so `Graph` doesn't need a type parameter for the closure, the error is
+// turned into a string when the validator is set.
+type ValidatorFn<N> = dyn Fn(&N) -> Result<(), String> + Send + Sync;
+
+pub(crate) struct NodeValidator<N>(Box<ValidatorFn<N>>);
+
+impl<N> fmt::Debug for NodeValidator<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NodeValidator")
+    }
+}
+
+// Inline capacity of the per-node adjacency lists. Nodes with up to this many
+// outgoing (or incoming) neighbours don't need a heap allocation for them.
+pub const DEFAULT_INLINE_ADJACENCY: usize = 4;
+
+pub(crate) type Adjacency<const A: usize> = SmallVec<[NodeIndex; A]>;
+pub(crate) type EdgeList<const A: usize> = SmallVec<[EdgeIndex; A]>;
+
+/*
+`N` is the node payload and `E` the edge payload (the "weight"), the defaults
+being the `Node` enum wrapper and no data at all.
+
+`edges` is the source of truth, `edge_weights[i]` is the payload of `edges[i]`,
+and `outgoing` and `incoming` are per-node adjacency lists derived from the
+edges so that neighbour queries don't need to scan every edge in the graph.
+`out_edges` has the indexes of the edges behind `outgoing`, for the searches
+that need the payloads. `A` is the inline capacity of those lists, bump it if
+most of your nodes have a bigger degree.
+
+`Graph::new()` builds a graph without edge payloads, for anything else
+use `Default`:
+
+    let g: Graph<City, u32> = Graph::default();
+    let g: Graph<Node, (), 8> = Graph::default();
+
+Edges are directed, `Graph::with_kind(GraphKind::Undirected)` builds a graph
+where they go both ways (see `GraphKind`).
+*/
 #[derive(Debug)]
-pub struct Graph {
-    nodes: Vec<Node>,
-    edges: Vec<Edge>,
+pub struct Graph<N = Node, E = (), const A: usize = DEFAULT_INLINE_ADJACENCY> {
+    pub(crate) nodes: Vec<N>,
+    // payload -> index, to deduplicate nodes without scanning them all
+    pub(crate) node_lookup: NodeLookup,
+    pub(crate) edges: Vec<Edge>,
+    pub(crate) edge_weights: Vec<E>,
+    pub(crate) outgoing: Vec<Adjacency<A>>,
+    pub(crate) incoming: Vec<Adjacency<A>>,
+    // edges leaving every node (every edge it's on in undirected graphs)
+    pub(crate) out_edges: Vec<EdgeList<A>>,
+    // graph-level metadata (name, provenance...), kept sorted so exports are
+    // deterministic
+    pub(crate) attrs: BTreeMap<String, String>,
+    pub(crate) node_validator: Option<NodeValidator<N>>,
+    // forbidden (in edge, out edge) transitions, see `add_turn_restriction`
+    pub(crate) turn_restrictions: HashSet<(EdgeIndex, EdgeIndex)>,
+    pub(crate) kind: GraphKind,
+    pub(crate) limits: Limits,
+    #[cfg(feature = "serve")]
+    pub(crate) live_view: Option<crate::serve::LiveView<N, E, A>>,
 }
 
-impl Graph {
+impl<N> Graph<N> {
     pub fn new() -> Self {
+        Graph::with_inline_adjacency()
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    pub fn with_inline_adjacency() -> Self {
         Graph {
             nodes: Vec::new(),
+            node_lookup: NodeLookup::default(),
             edges: Vec::new(),
+            edge_weights: Vec::new(),
+            outgoing: Vec::new(),
+            incoming: Vec::new(),
+            out_edges: Vec::new(),
+            attrs: BTreeMap::new(),
+            node_validator: None,
+            turn_restrictions: HashSet::new(),
+            kind: GraphKind::Directed,
+            limits: Limits::default(),
+            #[cfg(feature = "serve")]
+            live_view: None,
         }
     }
 
-    pub fn add_node(&mut self, new_node: Node) -> NodeIndex {
-        for (idx, node) in self.nodes.iter().enumerate() {
-            if node == &new_node {
-                return idx;
+    pub fn with_kind(kind: GraphKind) -> Self {
+        Graph {
+            kind,
+            ..Graph::with_inline_adjacency()
+        }
+    }
+
+    pub fn kind(&self) -> GraphKind {
+        self.kind
+    }
+
+    // Switch between directed and undirected, the edges stay as they are. When
+    // going undirected, `a -> b` and `b -> a` both stay in the edge list but
+    // only make `a` and `b` neighbours once.
+    pub fn set_kind(&mut self, kind: GraphKind) {
+        self.kind = kind;
+        self.rebuild_adjacency();
+    }
+
+    /*
+    Check every node payload added (or replaced) from now on with `validator`,
+    e.g. to reject empty labels before they end up in an analysis. Nodes
+    already in the graph aren't checked. Replaces any previous validator.
+
+    `add_node` panics on rejected payloads, use `try_add_node` to get the
+    error back instead.
+    */
+    pub fn set_node_validator<F, Err>(&mut self, validator: F)
+    where
+        F: Fn(&N) -> Result<(), Err> + Send + Sync + 'static,
+        Err: fmt::Display,
+    {
+        self.node_validator = Some(NodeValidator(Box::new(move |node| {
+            validator(node).map_err(|err| err.to_string())
+        })));
+    }
+
+    pub fn clear_node_validator(&mut self) {
+        self.node_validator = None;
+    }
+
+    fn validate_node(&self, node: &N) -> Result<(), InvalidNode> {
+        match &self.node_validator {
+            Some(NodeValidator(validator)) => {
+                validator(node).map_err(|reason| InvalidNode { reason })
             }
+            None => Ok(()),
+        }
+    }
+
+    /*
+    A node is identified by its index. By default payloads are also kept
+    unique: `add_node` returns the index of an existing node with an equal
+    payload instead of adding a new one. Use `add_node_unchecked` when two
+    distinct nodes can share a payload (e.g. two people with the same name).
+
+    Everything works with duplicate payloads except what looks nodes up by
+    payload:
+    - `add_node`, `try_add_node` and `find_node_idx` pick one of the
+      duplicates
+    - `same_topology` matches nodes by payload, so it can't tell duplicates
+      apart
+    - `to_adjacency_map` is keyed by payload, duplicates collapse into a
+      single key
+    */
+    pub fn add_node(&mut self, new_node: N) -> NodeIndex
+    where
+        N: Hash + PartialEq,
+    {
+        self.try_add_node(new_node)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_add_node(&mut self, new_node: N) -> Result<NodeIndex, InvalidNode>
+    where
+        N: Hash + PartialEq,
+    {
+        self.validate_node(&new_node)?;
+
+        self.node_lookup.catch_up(&self.nodes);
+        if let Some(idx) = self.node_lookup.find(&self.nodes, &new_node) {
+            return Ok(idx);
         }
+        self.check_node_limit()?;
+
+        let node_idx = self.push_node(new_node);
+        self.node_lookup.catch_up(&self.nodes);
+        Ok(node_idx)
+    }
 
+    // Always add a new node, even if another one already has an equal
+    // payload. Still checked by the node validator (panics if rejected).
+    pub fn add_node_unchecked(&mut self, new_node: N) -> NodeIndex {
+        self.validate_node(&new_node)
+            .and_then(|_| self.check_node_limit())
+            .unwrap_or_else(|err| panic!("{}", err));
+        self.push_node(new_node)
+    }
+
+    // A full graph rejects new nodes like the validator does, see `Limits`.
+    fn check_node_limit(&self) -> Result<(), InvalidNode> {
+        limits::check(Limit::Nodes, self.limits.max_nodes, self.nodes.len() + 1).map_err(|err| {
+            InvalidNode {
+                reason: err.to_string(),
+            }
+        })
+    }
+
+    fn push_node(&mut self, new_node: N) -> NodeIndex {
         self.nodes.push(new_node);
+        self.outgoing.push(Adjacency::new());
+        self.incoming.push(Adjacency::new());
+        self.out_edges.push(EdgeList::new());
+        self.changed();
         self.nodes.len() - 1
     }
 
-    pub fn add_edge(&mut self, new_edge: Edge) -> EdgeIndex {
-        for (idx, edge) in self.edges.iter().enumerate() {
-            if edge == &new_edge {
-                return idx;
-            }
+    // Replace the payload of an existing node, returning the old one. Edges
+    // are left untouched. A node that doesn't exist is an `InvalidNode` too.
+    pub fn replace_node(&mut self, node_idx: NodeIndex, node: N) -> Result<N, InvalidNode> {
+        if node_idx >= self.nodes.len() {
+            return Err(InvalidNode {
+                reason: format!("node {} doesn't exist", node_idx),
+            });
+        }
+        self.validate_node(&node)?;
+
+        let old = std::mem::replace(&mut self.nodes[node_idx], node);
+        // no `Hash` bound here, the node is indexed again on the next
+        // `add_node`
+        self.node_lookup.invalidate(node_idx);
+        self.changed();
+        Ok(old)
+    }
+
+    // Adding an edge that already exists leaves its weight untouched. Panics
+    // past `max_edges` (see `Limits`), `try_add_edge` doesn't.
+    pub fn add_edge(&mut self, new_edge: Edge) -> EdgeIndex
+    where
+        E: Default,
+    {
+        self.try_add_edge(new_edge)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // Adding an edge that already exists replaces its weight.
+    pub fn add_weighted_edge(&mut self, new_edge: Edge, weight: E) -> EdgeIndex {
+        self.try_add_weighted_edge(new_edge, weight)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // Whether there's an edge `from -> to` (or `to -> from` in undirected
+    // graphs), in O(degree) from the adjacency lists. `false` for nodes that
+    // don't exist.
+    pub fn has_edge(&self, from: NodeIndex, to: NodeIndex) -> bool {
+        self.outgoing
+            .get(from)
+            .is_some_and(|adjacency| adjacency.contains(&to))
+    }
+
+    // Index of the edge `from -> to`, see `has_edge`. The adjacency lists
+    // answer whether the edge exists, the edge list is only scanned for the
+    // index of edges that do.
+    pub fn find_edge(&self, from: NodeIndex, to: NodeIndex) -> Option<EdgeIndex> {
+        if !self.has_edge(from, to) {
+            return None;
         }
 
+        self.edges.iter().position(|edge| {
+            (edge.from == from && edge.to == to)
+                || (self.kind == GraphKind::Undirected && edge.from == to && edge.to == from)
+        })
+    }
+
+    // Every edge in the direction(s) it can be walked, with its index: all the
+    // edges as stored, then, in undirected graphs, every edge but self-loops
+    // reversed. Weighted searches scan this instead of `edges` so they agree
+    // with the adjacency lists.
+    pub(crate) fn arcs(&self) -> impl Iterator<Item = (EdgeIndex, Edge)> + '_ {
+        let forward = self.edges.iter().enumerate().map(|(edge_idx, edge)| {
+            let arc = Edge {
+                from: edge.from,
+                to: edge.to,
+            };
+            (edge_idx, arc)
+        });
+        let backward = self
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edge)| self.kind == GraphKind::Undirected && edge.from != edge.to)
+            .map(|(edge_idx, edge)| {
+                let arc = Edge {
+                    from: edge.to,
+                    to: edge.from,
+                };
+                (edge_idx, arc)
+            });
+        forward.chain(backward)
+    }
+
+    // `arcs` leaving `node_idx`, as (edge index, node at the other end), from
+    // the per-node edge lists. Empty if the node doesn't exist.
+    pub(crate) fn arcs_from(
+        &self,
+        node_idx: NodeIndex,
+    ) -> impl Iterator<Item = (EdgeIndex, NodeIndex)> + '_ {
+        self.out_edges
+            .get(node_idx)
+            .map(|edge_list| edge_list.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(move |edge_idx| {
+                let edge = &self.edges[*edge_idx];
+                let other = if edge.from == node_idx {
+                    edge.to
+                } else {
+                    edge.from
+                };
+                (*edge_idx, other)
+            })
+    }
+
+    pub(crate) fn push_edge(&mut self, new_edge: Edge, weight: E) -> EdgeIndex {
+        assert!(
+            new_edge.from < self.nodes.len() && new_edge.to < self.nodes.len(),
+            "edge {:?} points to a node that doesn't exist",
+            new_edge
+        );
+
+        self.link(new_edge.from, new_edge.to);
+        self.list_edge(self.edges.len(), &new_edge);
         self.edges.push(new_edge);
+        self.edge_weights.push(weight);
+        self.changed();
         self.edges.len() - 1
     }
 
-    pub fn find_node_idx(&self, node: Node) -> Option<NodeIndex> {
-        for (idx, current_node) in self.nodes.iter().enumerate() {
-            if current_node == &node {
-                return Some(idx);
-            }
+    pub fn edge_weight(&self, edge_idx: EdgeIndex) -> Option<&E> {
+        self.edge_weights.get(edge_idx)
+    }
+
+    pub fn edge_weight_mut(&mut self, edge_idx: EdgeIndex) -> Option<&mut E> {
+        self.edge_weights.get_mut(edge_idx)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    // Degrees read the adjacency lists, they're 0 for nodes that don't
+    // exist. In undirected graphs every edge counts both ways, so in, out
+    // and total degree are the same.
+    pub fn out_degree(&self, node_idx: NodeIndex) -> usize {
+        self.outgoing
+            .get(node_idx)
+            .map_or(0, |adjacency| adjacency.len())
+    }
+
+    pub fn in_degree(&self, node_idx: NodeIndex) -> usize {
+        self.incoming
+            .get(node_idx)
+            .map_or(0, |adjacency| adjacency.len())
+    }
+
+    pub fn degree(&self, node_idx: NodeIndex) -> usize {
+        match self.kind {
+            GraphKind::Directed => self.out_degree(node_idx) + self.in_degree(node_idx),
+            GraphKind::Undirected => self.out_degree(node_idx),
         }
+    }
+
+    pub fn node(&self, node_idx: NodeIndex) -> Option<&N> {
+        self.nodes.get(node_idx)
+    }
+
+    // Same as `node`.
+    pub fn get_node(&self, node_idx: NodeIndex) -> Option<&N> {
+        self.node(node_idx)
+    }
+
+    /*
+    Edit a payload in place, edges and indexes stay as they are. Unlike
+    `replace_node` this skips the node validator and doesn't notify a live
+    view. The dedup index forgets the node (only that one), since its
+    payload can change behind our back.
+    */
+    pub fn get_node_mut(&mut self, node_idx: NodeIndex) -> Option<&mut N> {
+        if node_idx >= self.nodes.len() {
+            return None;
+        }
+        self.node_lookup.invalidate(node_idx);
+        self.nodes.get_mut(node_idx)
+    }
+
+    pub fn edge(&self, edge_idx: EdgeIndex) -> Option<&Edge> {
+        self.edges.get(edge_idx)
+    }
+
+    // Every node with its index, in index order.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeIndex, &N)> {
+        self.nodes.iter().enumerate()
+    }
+
+    // Every edge with its index, in index order.
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeIndex, &Edge)> {
+        self.edges.iter().enumerate()
+    }
+
+    // Set a graph-level attribute, returning the previous value.
+    pub fn set_attr(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.attrs.insert(key.into(), value.into())
+    }
+
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs.get(key).map(|v| v.as_str())
+    }
+
+    pub fn remove_attr(&mut self, key: &str) -> Option<String> {
+        self.attrs.remove(key)
+    }
+
+    // All the graph-level attributes, sorted by key.
+    pub fn attrs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attrs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
 
-        None
+    // Whether a node with this payload exists, without giving it up like
+    // `find_node_idx` does.
+    pub fn contains_node(&self, node: &N) -> bool
+    where
+        N: Hash + PartialEq,
+    {
+        self.node_lookup.find(&self.nodes, node).is_some()
     }
 
-    pub fn remove_node(&mut self, node_idx: NodeIndex) -> Option<Node> {
+    pub fn find_node_idx(&self, node: N) -> Option<NodeIndex>
+    where
+        N: Hash + PartialEq,
+    {
+        self.node_lookup.find(&self.nodes, &node)
+    }
+
+    pub fn remove_node(&mut self, node_idx: NodeIndex) -> Option<N> {
         match self.nodes.get(node_idx) {
             None => None,
             Some(_) => {
                 // retrieve current last idx because we are doing a swap_remove
                 // and we will need to update the edges to the last node too
-                let last_node_idx = self.nodes.len();
+                let last_node_idx = self.nodes.len() - 1;
 
+                self.node_lookup.swap_remove(node_idx, self.nodes.len());
                 let removed_node = self.nodes.swap_remove(node_idx);
 
                 /*
@@ -140,8 +647,7 @@ impl Graph {
                 */
 
                 // remove all edges pointing to the removed node
-                self.edges
-                    .retain(|x| x.from != node_idx && x.to != node_idx);
+                self.take_edges_where(|edge| edge.from == node_idx || edge.to == node_idx);
 
                 // if we just removed the last node, we don't need to update
                 // more edges, otherwise, all the edges that were pointing to/from
@@ -158,36 +664,182 @@ impl Graph {
                     }
                 }
 
+                self.rebuild_adjacency();
+
                 Some(removed_node)
             }
         }
     }
 
-    pub fn reachable_nodes_from(&self, node_idx: NodeIndex) -> Vec<NodeIndex> {
-        self.edges
+    /*
+    Remove a single edge, returning it with its payload so it can be added
+    back. The last edge takes the index of the removed one (like nodes in
+    `remove_node`), every other edge keeps its index.
+    */
+    pub fn remove_edge(&mut self, edge_idx: EdgeIndex) -> Option<(Edge, E)> {
+        if edge_idx >= self.edges.len() {
+            return None;
+        }
+
+        let last_edge_idx = self.edges.len() - 1;
+        let mut edge_map: Vec<Option<EdgeIndex>> = (0..self.edges.len()).map(Some).collect();
+        edge_map[edge_idx] = None;
+        edge_map[last_edge_idx] = (edge_idx != last_edge_idx).then_some(edge_idx);
+
+        let edge = self.edges.swap_remove(edge_idx);
+        let weight = self.edge_weights.swap_remove(edge_idx);
+        self.remap_turn_restrictions(&edge_map);
+
+        match self.kind {
+            GraphKind::Directed => {
+                unlink(&mut self.outgoing[edge.from], edge.to);
+                unlink(&mut self.incoming[edge.to], edge.from);
+                unlink(&mut self.out_edges[edge.from], edge_idx);
+                if edge_idx != last_edge_idx {
+                    let moved = &mut self.out_edges[self.edges[edge_idx].from];
+                    if let Some(pos) = moved.iter().position(|idx| *idx == last_edge_idx) {
+                        moved[pos] = edge_idx;
+                    }
+                }
+                self.changed();
+            }
+            // the other edges between the same nodes may still link them
+            GraphKind::Undirected => self.rebuild_adjacency(),
+        }
+
+        Some((edge, weight))
+    }
+
+    // In undirected graphs `to -> from` is removed too (it's the same edge).
+    pub fn remove_edge_between(&mut self, from: NodeIndex, to: NodeIndex) -> Option<(Edge, E)> {
+        let edge_idx = self.find_edge(from, to)?;
+        self.remove_edge(edge_idx)
+    }
+
+    // Remove every edge going to or from `node_idx` but keep the node,
+    // returning the removed edges (in their original order) and payloads.
+    // The remaining edges keep their relative order.
+    pub fn disconnect(&mut self, node_idx: NodeIndex) -> Vec<(Edge, E)> {
+        let removed = self.take_edges_where(|edge| edge.from == node_idx || edge.to == node_idx);
+        self.rebuild_adjacency();
+        removed
+    }
+
+    // Remove the matching edges (and their payloads) keeping the order of
+    // the rest. Leaves the adjacency lists to the caller.
+    fn take_edges_where(&mut self, remove: impl Fn(&Edge) -> bool) -> Vec<(Edge, E)> {
+        let edges = std::mem::take(&mut self.edges);
+        let weights = std::mem::take(&mut self.edge_weights);
+        let mut edge_map: Vec<Option<EdgeIndex>> = Vec::with_capacity(edges.len());
+        let mut removed = Vec::new();
+        for (edge, weight) in edges.into_iter().zip(weights) {
+            if remove(&edge) {
+                edge_map.push(None);
+                removed.push((edge, weight));
+            } else {
+                edge_map.push(Some(self.edges.len()));
+                self.edges.push(edge);
+                self.edge_weights.push(weight);
+            }
+        }
+        self.remap_turn_restrictions(&edge_map);
+        removed
+    }
+
+    pub(crate) fn rebuild_adjacency(&mut self) {
+        self.outgoing.clear();
+        self.outgoing.resize_with(self.nodes.len(), Adjacency::new);
+        self.incoming.clear();
+        self.incoming.resize_with(self.nodes.len(), Adjacency::new);
+        self.out_edges.clear();
+        self.out_edges.resize_with(self.nodes.len(), EdgeList::new);
+
+        for edge_idx in 0..self.edges.len() {
+            let Edge { from, to } = self.edges[edge_idx];
+            self.link(from, to);
+            self.out_edges[from].push(edge_idx);
+            if self.kind == GraphKind::Undirected && from != to {
+                self.out_edges[to].push(edge_idx);
+            }
+        }
+        self.changed();
+    }
+
+    // Add `edge_idx` to the edge lists of the nodes it can be left from.
+    fn list_edge(&mut self, edge_idx: EdgeIndex, edge: &Edge) {
+        self.out_edges[edge.from].push(edge_idx);
+        if self.kind == GraphKind::Undirected && edge.from != edge.to {
+            self.out_edges[edge.to].push(edge_idx);
+        }
+    }
+
+    // Add an edge to the adjacency lists, both ways if the graph is undirected.
+    fn link(&mut self, from: NodeIndex, to: NodeIndex) {
+        if self.kind == GraphKind::Directed {
+            self.outgoing[from].push(to);
+            self.incoming[to].push(from);
+            return;
+        }
+
+        // `a -> b` and `b -> a` are the same undirected edge
+        if self.outgoing[from].contains(&to) {
+            return;
+        }
+        self.outgoing[from].push(to);
+        self.incoming[to].push(from);
+        if from != to {
+            self.outgoing[to].push(from);
+            self.incoming[from].push(to);
+        }
+    }
+
+    // Called after every mutation, redraws the live view if the graph is
+    // being served (see `serve`).
+    fn changed(&self) {
+        #[cfg(feature = "serve")]
+        if let Some(view) = &self.live_view {
+            view.publish(self);
+        }
+    }
+
+    // Successors of `node_idx` (both ways in undirected graphs), without
+    // allocating. Empty if the node doesn't exist.
+    pub fn neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.outgoing
+            .get(node_idx)
+            .map(|adjacency| adjacency.as_slice())
+            .unwrap_or_default()
             .iter()
-            .filter(|e| e.from == node_idx)
-            .map(|e| e.to)
-            .collect()
+            .copied()
+    }
+
+    pub fn reachable_nodes_from(&self, node_idx: NodeIndex) -> Vec<NodeIndex> {
+        match self.outgoing.get(node_idx) {
+            Some(adjacency) => adjacency.to_vec(),
+            None => Vec::new(),
+        }
     }
 
     pub fn nodes_that_can_reach(&self, node_idx: NodeIndex) -> Vec<NodeIndex> {
-        self.edges
-            .iter()
-            .filter(|e| e.to == node_idx)
-            .map(|e| e.from)
-            .collect()
+        match self.incoming.get(node_idx) {
+            Some(adjacency) => adjacency.to_vec(),
+            None => Vec::new(),
+        }
     }
 
     pub fn boundary(&self) -> Option<Vec<NodeIndex>> {
         // find all nodes that do NOT have a "from" edge, that is:
         // other nodes may reach it but it doesn't reach any, thus making it
         // a "boundary" node.
-
-        let froms: HashSet<usize> = self.edges.iter().map(|e| e.from).collect();
+        // In undirected graphs every edge goes both ways, so the boundary is
+        // the nodes with at most one neighbour (the leaves of a tree).
+        let max_neighbours = match self.kind {
+            GraphKind::Directed => 0,
+            GraphKind::Undirected => 1,
+        };
 
         let b: Vec<NodeIndex> = (0..self.nodes.len())
-            .filter(|node_idx| !froms.contains(node_idx))
+            .filter(|node_idx| self.outgoing[*node_idx].len() <= max_neighbours)
             .collect();
 
         if b.is_empty() {
@@ -213,9 +865,7 @@ impl Graph {
 
         queue.push_front(start);
 
-        while !queue.is_empty() {
-            let working_node = queue[0];
-
+        while let Some(working_node) = queue.front().copied() {
             for neighbour in self
                 .reachable_nodes_from(working_node)
                 .iter()
@@ -228,138 +878,131 @@ impl Graph {
                 }
             }
 
-            let finished = queue.pop_front().unwrap();
-            visited.insert(finished);
+            queue.pop_front();
+            visited.insert(working_node);
             distance += 1
         }
 
         distance
     }
 
-    /*
+    // Fewest-edges path from `start` to `end`, both included. See
+    // `shortest_path_traced` for a step by step record of the search.
+    pub fn shortest_path(&self, start: NodeIndex, end: NodeIndex) -> Option<Vec<NodeIndex>> {
+        // without a frontier cap the search can't fail
+        self.shortest_path_search(start, end, None, None)
+            .unwrap_or(None)
+    }
 
-    Note: the original graph (&self) node indexes will be wrapped as new nodes
-    in the path three.
-
-    Strategy:
-    1. Build a tree (path_tree), using the "start" as the root.
-    2. Append leaves as we explore the graph
-        Each leave is a node, the data contained in the node is the index of our
-        original graph (&self). `path_tree` will contain its own node indexes
-
-    3. If we find the destination node, we need to "backtrack" the tree we just
-        built (`path_tree`) back to the root node to find the path we went through.
-        Backtracking strategy:
-
-        - The path starts with the node we just found (the destination)
-        path: [just_found_idx]
-        - We have found the destination while looking for neighbours, in terms of our
-          `path_tree`, we are still in the previous node. We inspect the data
-          of the node we are iterating over and append that to the path.
-        path: [just_found_idx, current_exploration_node]
-        - Go back in the tree (`path_tree`), building the path. Notice that we go back
-          the path_tree by using its node/edge indexes, but at the same time we are
-          appending to our `path` the indexes of the original graph, which are contained
-          in the `path_tree` nodes.
-        path: [just_found_idx, current_exploration_node, ...
+    /*
+    Breadth-first search from `start`, remembering the node every node was
+    first reached from, then walking that back from `end` once it's found.
+    Bad indexes and unreachable nodes give `None`, nothing here panics.
     */
-    pub fn shortest_path(&self, start: NodeIndex, end: NodeIndex) -> Option<Vec<NodeIndex>> {
-        let mut visited_graph: HashSet<NodeIndex> = HashSet::new();
-        visited_graph.insert(start);
-
-        let mut visited_tree: HashSet<NodeIndex> = HashSet::new();
-
-        // set the starting node as the root of our path tree
-        let mut path_tree = Graph::new();
-        let first_node = path_tree.add_node(Node::from(start as isize));
-        visited_tree.insert(first_node);
-
-        // loop as long as we have paths to explore
-        // or we haven't found the destination
-        loop {
-            // working node is the index of the **path** tree,
-            // NOT our original graph
-            for path_tree_node_idx in path_tree.leaves().unwrap().iter() {
-                let path_node = &path_tree.nodes[*path_tree_node_idx];
-                let orig_graph_node_idx = path_node.extract_int();
-
-                // now we find all the neighbour nodes in our graph
-
-                'neighbours: for neighbour_idx in self
-                    .reachable_nodes_from(orig_graph_node_idx.try_into().unwrap())
-                    .iter()
-                {
-                    if visited_graph.contains(neighbour_idx) {
-                        continue 'neighbours;
-                    }
+    pub(crate) fn shortest_path_search(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+        max_frontier: Option<usize>,
+        mut trace: Option<&mut Trace>,
+    ) -> Result<Option<Vec<NodeIndex>>, LimitExceeded> {
+        let n = self.nodes.len();
+        if start >= n || end >= n {
+            return Ok(None);
+        }
+        if start == end {
+            return Ok(Some(vec![start]));
+        }
 
-                    if *neighbour_idx == end {
-                        /*
-                        Found end of path!
-
-                        Now we start backtracking from our current situation
-                        back to the root of the path_tree. We will keep track of the
-                        data contained in parent nodes as we backtrack. This will
-                        become the path used to reach our objective node index.
-                        */
-                        let mut path = vec![*neighbour_idx];
-
-                        let prev_node_data = path_tree.nodes[*path_tree_node_idx].extract_int();
-                        path.push(prev_node_data as usize);
-
-                        let mut current_path_tree_node_idx = *path_tree_node_idx;
-
-                        // start backtracking
-                        loop {
-                            let parent = path_tree
-                                .edges
-                                .iter()
-                                .find(|path_edge| path_edge.to == current_path_tree_node_idx);
-
-                            match parent {
-                                // We are still backtracking, add the data in the
-                                // current node to the path, and keep moving
-                                Some(edge) => {
-                                    let path_tree_parent = &path_tree.nodes[edge.from];
-                                    current_path_tree_node_idx = edge.from;
-                                    let orig_graph_idx = path_tree_parent.extract_int();
-                                    path.push(orig_graph_idx as usize);
-                                }
-                                // None = we reached the tree root, we can return the path
-                                // we need to reverse the path because we were pushing items starting
-                                // from the end of the path until we reach the root
-                                None => return Some(path.into_iter().rev().collect()),
-                            }
-                        }
-                    }
+        let mut reached_from: Vec<Option<NodeIndex>> = vec![None; n];
+        let mut visited = vec![false; n];
+        visited[start] = true;
+        let mut queue: VecDeque<NodeIndex> = VecDeque::from([start]);
+
+        while let Some(node_idx) = queue.pop_front() {
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.push(TraceEvent::Expand {
+                    node: node_idx,
+                    frontier: snapshot(queue.iter().copied()),
+                    visited: snapshot((0..n).filter(|idx| visited[*idx])),
+                });
+            }
 
-                    let idx = path_tree.add_node(Node::from(*neighbour_idx as isize));
-                    path_tree.add_edge(Edge {
-                        from: *path_tree_node_idx,
-                        to: idx,
+            for neighbour_idx in self.neighbors(node_idx) {
+                let chosen = !visited[neighbour_idx];
+                if let Some(trace) = trace.as_deref_mut() {
+                    trace.push(TraceEvent::Edge {
+                        from: node_idx,
+                        to: neighbour_idx,
+                        chosen,
                     });
-                    visited_graph.insert(*path_tree_node_idx);
+                }
+                if !chosen {
+                    continue;
+                }
+
+                visited[neighbour_idx] = true;
+                reached_from[neighbour_idx] = Some(node_idx);
 
-                    // if we have visited all the node but didn't find the objective
-                    if visited_graph.len() == self.nodes.len() {
-                        return None;
+                if neighbour_idx == end {
+                    let mut path = vec![end];
+                    let mut current = end;
+                    while let Some(previous) = reached_from[current] {
+                        path.push(previous);
+                        current = previous;
                     }
+                    path.reverse();
+                    return Ok(Some(path));
                 }
 
-                visited_tree.insert(*path_tree_node_idx);
-
-                visited_tree
-                    .difference(&HashSet::from_iter(
-                        path_tree.leaves().unwrap().iter().copied(),
-                    ))
-                    .next()?;
+                queue.push_back(neighbour_idx);
+                limits::check(Limit::Frontier, max_frontier, queue.len())?;
             }
         }
+
+        Ok(None)
     }
 }
 
-impl Default for Graph {
+// Remove one occurrence of `node_idx` from an adjacency list.
+fn unlink<const A: usize>(adjacency: &mut Adjacency<A>, node_idx: NodeIndex) {
+    if let Some(pos) = adjacency.iter().position(|n| *n == node_idx) {
+        adjacency.remove(pos);
+    }
+}
+
+impl<N, E, const A: usize> Default for Graph<N, E, A> {
     fn default() -> Self {
-        Self::new()
+        Self::with_inline_adjacency()
+    }
+}
+
+// `g[idx]` is the payload of node `idx`, panics if there's no such node.
+impl<N, E, const A: usize> Index<NodeIndex> for Graph<N, E, A> {
+    type Output = N;
+
+    fn index(&self, node_idx: NodeIndex) -> &N {
+        match self.nodes.get(node_idx) {
+            Some(node) => node,
+            None => panic!(
+                "node {} doesn't exist (the graph has {} nodes)",
+                node_idx,
+                self.nodes.len()
+            ),
+        }
+    }
+}
+
+// See `get_node_mut`.
+impl<N, E, const A: usize> IndexMut<NodeIndex> for Graph<N, E, A> {
+    fn index_mut(&mut self, node_idx: NodeIndex) -> &mut N {
+        let node_count = self.nodes.len();
+        match self.get_node_mut(node_idx) {
+            Some(node) => node,
+            None => panic!(
+                "node {} doesn't exist (the graph has {} nodes)",
+                node_idx, node_count
+            ),
+        }
     }
 }
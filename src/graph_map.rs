@@ -0,0 +1,185 @@
+use std::hash::Hash;
+
+use crate::graph::{Edge, EdgeIndex, Graph, GraphKind, NodeIndex, DEFAULT_INLINE_ADJACENCY};
+use crate::traits::{GraphBase, IntoNodeIdentifiers, Neighbors};
+
+/*
+A `Graph` whose nodes are addressed by their payload, any `K: Hash + Eq`
+(string IDs, `(x, y)` coordinates...), instead of a `NodeIndex`:
+
+    let mut follows: GraphMap<&str> = GraphMap::new();
+    follows.add_edge("alice", "bob");
+    follows.add_edge("bob", "carol");
+    assert!(follows.contains_edge(&"alice", &"bob"));
+
+Nodes are created the first time an edge mentions them. Lookups go through
+the hash index `Graph` already keeps to deduplicate nodes, so there's no
+second map and keys aren't cloned. Algorithms run on `graph()` like for
+`StableGraph`, `index` and `key` translate between keys and the
+`NodeIndex`es it uses. Those change when a node is removed (the last node
+takes its place).
+*/
+#[derive(Debug)]
+pub struct GraphMap<K, E = (), const A: usize = DEFAULT_INLINE_ADJACENCY> {
+    graph: Graph<K, E, A>,
+}
+
+impl<K, E, const A: usize> GraphMap<K, E, A>
+where
+    K: Hash + Eq,
+{
+    pub fn new() -> Self {
+        Self::with_kind(GraphKind::Directed)
+    }
+
+    pub fn with_kind(kind: GraphKind) -> Self {
+        GraphMap {
+            graph: Graph::with_kind(kind),
+        }
+    }
+
+    // The index-based graph, for running algorithms on.
+    pub fn graph(&self) -> &Graph<K, E, A> {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Graph<K, E, A> {
+        self.graph
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    // Adding a key that's already there returns its index.
+    pub fn add_node(&mut self, key: K) -> NodeIndex {
+        self.graph.add_node(key)
+    }
+
+    // Adds the missing nodes. Adding an edge that already exists leaves its
+    // weight untouched, like `Graph::add_edge`.
+    pub fn add_edge(&mut self, from: K, to: K) -> EdgeIndex
+    where
+        E: Default,
+    {
+        let edge = self.edge(from, to);
+        self.graph.add_edge(edge)
+    }
+
+    // Adds the missing nodes. Adding an edge that already exists replaces
+    // its weight.
+    pub fn add_weighted_edge(&mut self, from: K, to: K, weight: E) -> EdgeIndex {
+        let edge = self.edge(from, to);
+        self.graph.add_weighted_edge(edge, weight)
+    }
+
+    fn edge(&mut self, from: K, to: K) -> Edge {
+        Edge {
+            from: self.graph.add_node(from),
+            to: self.graph.add_node(to),
+        }
+    }
+
+    // Current index of `key` in `graph()`.
+    pub fn index(&self, key: &K) -> Option<NodeIndex> {
+        self.graph.node_lookup.find(&self.graph.nodes, key)
+    }
+
+    // Key of the node at `node_idx` in `graph()`.
+    pub fn key(&self, node_idx: NodeIndex) -> Option<&K> {
+        self.graph.nodes.get(node_idx)
+    }
+
+    pub fn contains_node(&self, key: &K) -> bool {
+        self.index(key).is_some()
+    }
+
+    pub fn contains_edge(&self, from: &K, to: &K) -> bool {
+        self.edge_index(from, to).is_some()
+    }
+
+    pub fn edge_weight(&self, from: &K, to: &K) -> Option<&E> {
+        let edge_idx = self.edge_index(from, to)?;
+        self.graph.edge_weights.get(edge_idx)
+    }
+
+    pub fn edge_weight_mut(&mut self, from: &K, to: &K) -> Option<&mut E> {
+        let edge_idx = self.edge_index(from, to)?;
+        self.graph.edge_weights.get_mut(edge_idx)
+    }
+
+    fn edge_index(&self, from: &K, to: &K) -> Option<EdgeIndex> {
+        self.graph.find_edge(self.index(from)?, self.index(to)?)
+    }
+
+    // All the keys, in index order.
+    pub fn nodes(&self) -> impl Iterator<Item = &K> {
+        self.graph.nodes.iter()
+    }
+
+    // Every edge as a `(from, to, weight)` triple, in edge index order.
+    pub fn all_edges(&self) -> impl Iterator<Item = (&K, &K, &E)> {
+        self.graph
+            .edges
+            .iter()
+            .zip(self.graph.edge_weights.iter())
+            .map(|(edge, weight)| {
+                (
+                    &self.graph.nodes[edge.from],
+                    &self.graph.nodes[edge.to],
+                    weight,
+                )
+            })
+    }
+
+    // Where the edges of `key` lead, nothing if it isn't in the graph.
+    pub fn neighbors(&self, key: &K) -> impl Iterator<Item = &K> {
+        let node_idx = self.index(key);
+        node_idx
+            .into_iter()
+            .flat_map(|node_idx| self.graph.neighbors(node_idx))
+            .map(|succ| &self.graph.nodes[succ])
+    }
+
+    // Removes the node with all of its edges, `None` if it isn't there.
+    pub fn remove_node(&mut self, key: &K) -> Option<K> {
+        let node_idx = self.index(key)?;
+        self.graph.remove_node(node_idx)
+    }
+
+    pub fn remove_edge(&mut self, from: &K, to: &K) -> Option<E> {
+        let from = self.index(from)?;
+        let to = self.index(to)?;
+        self.graph
+            .remove_edge_between(from, to)
+            .map(|(_, weight)| weight)
+    }
+}
+
+impl<K, E, const A: usize> Default for GraphMap<K, E, A>
+where
+    K: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Indexes are those of `graph()`, see `Algorithms`.
+impl<K, E, const A: usize> GraphBase for GraphMap<K, E, A> {
+    fn node_bound(&self) -> usize {
+        self.graph.nodes.len()
+    }
+}
+
+impl<K, E, const A: usize> IntoNodeIdentifiers for GraphMap<K, E, A> {}
+
+impl<K, E, const A: usize> Neighbors for GraphMap<K, E, A> {
+    fn neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.graph.neighbors(node_idx)
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::graph::{DataTypes, Edge, Graph, GraphKind, Node, NodeIndex};
+use crate::xml::{self, Element};
+
+/*
+GraphML, what yEd, Gephi and friends read and write.
+
+Payloads are stored as GraphML data, one value per node or edge, with a key
+for each `DataTypes` variant: `label` (string) for text, `int` (long) for
+integers and `blob` (string, hex) for blobs. Graph attributes (`set_attr`)
+are graph-level data.
+*/
+
+// Node and edge payloads that can go through GraphML.
+pub trait GraphmlData: Sized {
+    // `None` writes no data for the element.
+    fn to_data(&self) -> Option<&DataTypes>;
+    // `data` is `None` when the element had none, `id` is its GraphML id.
+    fn from_data(data: Option<DataTypes>, id: &str) -> Self;
+}
+
+impl GraphmlData for () {
+    fn to_data(&self) -> Option<&DataTypes> {
+        None
+    }
+
+    fn from_data(_: Option<DataTypes>, _: &str) {}
+}
+
+// Nodes without data are labeled with their id.
+impl GraphmlData for Node {
+    fn to_data(&self) -> Option<&DataTypes> {
+        Some(&self.data)
+    }
+
+    fn from_data(data: Option<DataTypes>, id: &str) -> Node {
+        match data {
+            Some(data) => Node { data },
+            None => Node::from(id),
+        }
+    }
+}
+
+// A GraphML document `read_graphml` couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct GraphmlError {
+    pub message: String,
+}
+
+impl fmt::Display for GraphmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid GraphML: {}", self.message)
+    }
+}
+
+impl std::error::Error for GraphmlError {}
+
+// `for`, id, `attr.name` and `attr.type` of the keys written
+const KEYS: [(&str, &str, &str, &str); 6] = [
+    ("node", "label", "label", "string"),
+    ("node", "int", "int", "long"),
+    ("node", "blob", "blob", "string"),
+    ("edge", "edge_label", "label", "string"),
+    ("edge", "edge_int", "int", "long"),
+    ("edge", "edge_blob", "blob", "string"),
+];
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: GraphmlData,
+    E: GraphmlData,
+{
+    // Node `i` is written as `n<i>` and edge `i` as `e<i>`.
+    pub fn write_graphml(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+        )?;
+        for (attr_idx, key) in self.attrs.keys().enumerate() {
+            writeln!(
+                out,
+                r#"  <key id="g{}" for="graph" attr.name="{}" attr.type="string"/>"#,
+                attr_idx,
+                xml::escape(key)
+            )?;
+        }
+        for (domain, id, name, kind) in KEYS {
+            writeln!(
+                out,
+                r#"  <key id="{}" for="{}" attr.name="{}" attr.type="{}"/>"#,
+                id, domain, name, kind
+            )?;
+        }
+
+        let edgedefault = match self.kind {
+            GraphKind::Directed => "directed",
+            GraphKind::Undirected => "undirected",
+        };
+        writeln!(out, r#"  <graph id="G" edgedefault="{}">"#, edgedefault)?;
+        for (attr_idx, value) in self.attrs.values().enumerate() {
+            writeln!(
+                out,
+                r#"    <data key="g{}">{}</data>"#,
+                attr_idx,
+                xml::escape(value)
+            )?;
+        }
+
+        for (node_idx, node) in self.nodes.iter().enumerate() {
+            match node.to_data() {
+                Some(data) => writeln!(
+                    out,
+                    r#"    <node id="n{}">{}</node>"#,
+                    node_idx,
+                    data_element("", data)
+                )?,
+                None => writeln!(out, r#"    <node id="n{}"/>"#, node_idx)?,
+            }
+        }
+        for (edge_idx, (edge, weight)) in
+            self.edges.iter().zip(self.edge_weights.iter()).enumerate()
+        {
+            let start = format!(
+                r#"    <edge id="e{}" source="n{}" target="n{}""#,
+                edge_idx, edge.from, edge.to
+            );
+            match weight.to_data() {
+                Some(data) => writeln!(out, "{}>{}</edge>", start, data_element("edge_", data))?,
+                None => writeln!(out, "{}/>", start)?,
+            }
+        }
+
+        writeln!(out, "  </graph>")?;
+        writeln!(out, "</graphml>")
+    }
+
+    /*
+    Read the first graph of a GraphML document. Nodes keep their document
+    order and are matched by id, edges keep theirs (parallel edges
+    included), `edgedefault="undirected"` makes an undirected graph.
+
+    A node or edge with several data values gets its `label` one if it has
+    it, the first one otherwise: `int`/`long` data becomes an integer, data
+    named `blob` a blob (hex) and anything else text. Data made only of
+    nested elements (yEd graphics...) is skipped. Nested graphs and
+    hyperedges are errors.
+    */
+    pub fn read_graphml(graphml: &str) -> Result<Self, GraphmlError> {
+        let error = |message: String| GraphmlError { message };
+        let root = xml::parse(graphml).map_err(error)?;
+        if local_name(&root.name) != "graphml" {
+            return Err(error(format!("the root element is <{}>", root.name)));
+        }
+
+        let mut keys: HashMap<&str, Key> = HashMap::new();
+        for key in root
+            .children
+            .iter()
+            .filter(|c| local_name(&c.name) == "key")
+        {
+            let id = key
+                .attr("id")
+                .ok_or_else(|| error("<key> without an id".to_string()))?;
+            keys.insert(
+                id,
+                Key {
+                    domain: key.attr("for").unwrap_or("all"),
+                    name: key.attr("attr.name").unwrap_or(id),
+                    kind: key.attr("attr.type").unwrap_or("string"),
+                },
+            );
+        }
+
+        let graph_element = root
+            .children
+            .iter()
+            .find(|c| local_name(&c.name) == "graph")
+            .ok_or_else(|| error("no <graph>".to_string()))?;
+        let kind = match graph_element.attr("edgedefault") {
+            Some("undirected") => GraphKind::Undirected,
+            _ => GraphKind::Directed,
+        };
+        let mut graph = Graph::with_kind(kind);
+
+        let mut ids: HashMap<&str, NodeIndex> = HashMap::new();
+        for child in graph_element.children.iter() {
+            match local_name(&child.name) {
+                "data" => {
+                    let key = child.attr("key").unwrap_or_default();
+                    let name = keys.get(key).map_or(key, |key| key.name);
+                    graph
+                        .attrs
+                        .insert(name.to_string(), child.text.trim().to_string());
+                }
+                "node" => {
+                    let id = child
+                        .attr("id")
+                        .ok_or_else(|| error("<node> without an id".to_string()))?;
+                    if ids.contains_key(id) {
+                        return Err(error(format!("duplicate node id {:?}", id)));
+                    }
+                    if child
+                        .children
+                        .iter()
+                        .any(|c| local_name(&c.name) == "graph")
+                    {
+                        return Err(error(format!("node {:?} has a nested graph", id)));
+                    }
+                    let data = element_data(child, &keys, "node").map_err(error)?;
+                    graph.nodes.push(N::from_data(data, id));
+                    ids.insert(id, graph.nodes.len() - 1);
+                }
+                "hyperedge" => return Err(error("hyperedges aren't supported".to_string())),
+                _ => {}
+            }
+        }
+
+        for (edge_idx, child) in graph_element
+            .children
+            .iter()
+            .filter(|c| local_name(&c.name) == "edge")
+            .enumerate()
+        {
+            let endpoint = |field: &str| {
+                let id = child
+                    .attr(field)
+                    .ok_or_else(|| error(format!("<edge> without a {}", field)))?;
+                ids.get(id)
+                    .copied()
+                    .ok_or_else(|| error(format!("edge to an unknown node {:?}", id)))
+            };
+            let edge = Edge {
+                from: endpoint("source")?,
+                to: endpoint("target")?,
+            };
+            let data = element_data(child, &keys, "edge").map_err(error)?;
+            let id = child
+                .attr("id")
+                .map_or_else(|| format!("e{}", edge_idx), str::to_string);
+            graph.edges.push(edge);
+            graph.edge_weights.push(E::from_data(data, &id));
+        }
+
+        graph.rebuild_adjacency();
+        Ok(graph)
+    }
+}
+
+struct Key<'x> {
+    domain: &'x str,
+    name: &'x str,
+    kind: &'x str,
+}
+
+fn data_element(prefix: &str, data: &DataTypes) -> String {
+    match data {
+        DataTypes::Text(text) => format!(
+            r#"<data key="{}label">{}</data>"#,
+            prefix,
+            xml::escape(text)
+        ),
+        DataTypes::Integer(v) => format!(r#"<data key="{}int">{}</data>"#, prefix, v),
+        DataTypes::Blob(bytes) => {
+            let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            format!(r#"<data key="{}blob">{}</data>"#, prefix, hex)
+        }
+    }
+}
+
+// The payload of a node or edge, see `read_graphml`.
+fn element_data(
+    element: &Element,
+    keys: &HashMap<&str, Key>,
+    domain: &str,
+) -> Result<Option<DataTypes>, String> {
+    let values: Vec<(&str, &str, &str)> = element
+        .children
+        .iter()
+        .filter(|c| local_name(&c.name) == "data")
+        .filter(|c| c.children.is_empty() || !c.text.trim().is_empty())
+        .filter_map(|c| {
+            let key = c.attr("key")?;
+            match keys.get(key) {
+                Some(def) if def.domain == domain || def.domain == "all" => {
+                    Some((def.name, def.kind, c.text.as_str()))
+                }
+                Some(_) => None,
+                None => Some((key, "string", c.text.as_str())),
+            }
+        })
+        .collect();
+
+    let chosen = values
+        .iter()
+        .find(|(name, _, _)| *name == "label")
+        .or(values.first());
+    let Some((name, kind, text)) = chosen else {
+        return Ok(None);
+    };
+
+    let data = if *name == "blob" {
+        DataTypes::Blob(decode_hex(text.trim()).ok_or_else(|| format!("invalid blob {:?}", text))?)
+    } else if matches!(*kind, "int" | "long") {
+        DataTypes::Integer(
+            text.trim()
+                .parse()
+                .map_err(|_| format!("invalid {} {:?}", kind, text))?,
+        )
+    } else {
+        DataTypes::Text(text.to_string())
+    };
+    Ok(Some(data))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// Element names without their namespace prefix.
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
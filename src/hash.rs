@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::graph::{Graph, NodeIndex};
+
+/*
+Merkle-style hashing of DAGs, meant to be used as content-addressed cache keys
+(think build systems: a target needs rebuilding when its own payload or any
+of its transitive dependencies change).
+
+hash(node) = fnv1a(payload(node) ++ sorted(hash(child) for child in children))
+
+Children hashes are sorted so the result doesn't depend on edge insertion
+order. Payloads go through their `Hash` impl, but the hasher always writes
+integers as little-endian 64-bit values, so keys are the same on every
+platform (as long as the payload's `Hash` impl doesn't change). FNV-1a is NOT
+a cryptographic hash, don't use these keys where someone could craft
+collisions on purpose.
+*/
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(FNV_OFFSET)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, v: u16) {
+        self.write_u64(v as u64);
+    }
+
+    fn write_u32(&mut self, v: u32) {
+        self.write_u64(v as u64);
+    }
+
+    fn write_u64(&mut self, v: u64) {
+        self.write(&v.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, v: usize) {
+        self.write_u64(v as u64);
+    }
+
+    fn write_i16(&mut self, v: i16) {
+        self.write_i64(v as i64);
+    }
+
+    fn write_i32(&mut self, v: i32) {
+        self.write_i64(v as i64);
+    }
+
+    fn write_i64(&mut self, v: i64) {
+        self.write(&v.to_le_bytes());
+    }
+
+    fn write_isize(&mut self, v: isize) {
+        self.write_i64(v as i64);
+    }
+}
+
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hasher = Fnv1a::new();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // Returns `None` if `root` doesn't exist or if a cycle is reachable from it.
+    pub fn subgraph_hash(&self, root: NodeIndex) -> Option<u64>
+    where
+        N: Hash,
+    {
+        if root >= self.nodes.len() {
+            return None;
+        }
+
+        let mut hashes: HashMap<NodeIndex, u64> = HashMap::new();
+        let mut on_stack = vec![false; self.nodes.len()];
+
+        // iterative post-order DFS, (node, children_pushed)
+        let mut stack: Vec<(NodeIndex, bool)> = vec![(root, false)];
+
+        while let Some((node_idx, children_pushed)) = stack.pop() {
+            if hashes.contains_key(&node_idx) {
+                continue;
+            }
+
+            if !children_pushed {
+                on_stack[node_idx] = true;
+                stack.push((node_idx, true));
+
+                for child in self.outgoing[node_idx].iter() {
+                    if on_stack[*child] {
+                        return None;
+                    }
+                    if !hashes.contains_key(child) {
+                        stack.push((*child, false));
+                    }
+                }
+                continue;
+            }
+
+            let mut children: Vec<u64> = self.outgoing[node_idx]
+                .iter()
+                .map(|child| hashes[child])
+                .collect();
+            children.sort_unstable();
+
+            let mut hasher = Fnv1a::new();
+            self.nodes[node_idx].hash(&mut hasher);
+            for child in children {
+                hasher.write_u64(child);
+            }
+
+            hashes.insert(node_idx, hasher.finish());
+            on_stack[node_idx] = false;
+        }
+
+        hashes.get(&root).copied()
+    }
+}
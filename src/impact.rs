@@ -0,0 +1,105 @@
+use std::collections::VecDeque;
+
+use crate::graph::{Graph, NodeIndex};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Change impact, reading `a -> b` as "a depends on b": every node that
+    depends on one of `changed`, directly or not, with the fewest edges
+    between it and the closest changed node. Sorted by node index, the
+    changed nodes included with 0. Indexes out of bounds are ignored.
+
+    Multi-source BFS over the incoming edges, O(n + m).
+    */
+    pub fn impacted_by(&self, changed: &[NodeIndex]) -> Vec<(NodeIndex, usize)> {
+        let n = self.nodes.len();
+        let mut distances: Vec<Option<usize>> = vec![None; n];
+        let mut queue = VecDeque::new();
+        for node_idx in changed.iter().copied().filter(|v| *v < n) {
+            if distances[node_idx].is_none() {
+                distances[node_idx] = Some(0);
+                queue.push_back((node_idx, 0));
+            }
+        }
+
+        while let Some((node_idx, distance)) = queue.pop_front() {
+            for pred in self.incoming[node_idx].iter().copied() {
+                if distances[pred].is_none() {
+                    distances[pred] = Some(distance + 1);
+                    queue.push_back((pred, distance + 1));
+                }
+            }
+        }
+
+        distances
+            .into_iter()
+            .enumerate()
+            .filter_map(|(node_idx, distance)| Some((node_idx, distance?)))
+            .collect()
+    }
+
+    /*
+    Root causes: every node that can reach all of `nodes`, a node of the set
+    counting as reaching itself. Sorted by index, empty when `nodes` is
+    (or has a node that doesn't exist).
+    */
+    pub fn common_ancestors(&self, nodes: &[NodeIndex]) -> Vec<NodeIndex> {
+        let n = self.nodes.len();
+        if nodes.is_empty() || nodes.iter().any(|v| *v >= n) {
+            return Vec::new();
+        }
+
+        let mut reaching = vec![0; n];
+        let mut targets = nodes.to_vec();
+        targets.sort_unstable();
+        targets.dedup();
+        for target in targets.iter() {
+            for (node_idx, _) in self.impacted_by(&[*target]) {
+                reaching[node_idx] += 1;
+            }
+        }
+
+        (0..n).filter(|v| reaching[*v] == targets.len()).collect()
+    }
+
+    /*
+    The `common_ancestors` closest to `nodes`: the ones that can't reach
+    another common ancestor, unless that one reaches them back (on a cycle
+    they're all equally close). Sorted by index.
+    */
+    pub fn lowest_common_ancestors(&self, nodes: &[NodeIndex]) -> Vec<NodeIndex> {
+        let ancestors = self.common_ancestors(nodes);
+        let mut is_ancestor = vec![false; self.nodes.len()];
+        for node_idx in ancestors.iter() {
+            is_ancestor[*node_idx] = true;
+        }
+
+        ancestors
+            .iter()
+            .copied()
+            .filter(|ancestor| {
+                // ancestors reaching back to this one
+                let mut back = vec![false; self.nodes.len()];
+                for (node_idx, _) in self.impacted_by(&[*ancestor]) {
+                    back[node_idx] = true;
+                }
+                let mut seen = vec![false; self.nodes.len()];
+                seen[*ancestor] = true;
+                let mut queue = VecDeque::from([*ancestor]);
+                while let Some(node_idx) = queue.pop_front() {
+                    for succ in self.outgoing[node_idx].iter().copied() {
+                        if seen[succ] {
+                            continue;
+                        }
+                        if is_ancestor[succ] && !back[succ] {
+                            return false;
+                        }
+                        seen[succ] = true;
+                        queue.push_back(succ);
+                    }
+                }
+                true
+            })
+            .collect()
+    }
+}
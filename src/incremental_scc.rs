@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+use crate::graph::{Edge, Graph, NodeIndex};
+
+/*
+Strongly connected components kept up to date as edges are added, for graphs
+that only grow (a dependency tracker ingesting new edges) where re-running
+`strongly_connected_components` after every edge would be wasteful.
+
+Keeps the condensation (see `Graph::condensation`) next to a union-find of
+the nodes into components. A new edge between two components only merges
+anything if it closes a cycle in the condensation, i.e. if its target
+component can already reach its source one. When it does, every component
+on a path from the target back to the source collapses into one.
+
+Adding an edge costs a search over the part of the condensation reachable
+from the target component, edges inside a component or parallel to an
+existing one are free. Edges can't be removed, splitting components back
+needs a full recomputation.
+*/
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct IncrementalScc {
+    // union-find, components are identified by their root node
+    parent: Vec<NodeIndex>,
+    // sorted members of every root, empty for the other nodes
+    members: Vec<Vec<NodeIndex>>,
+    // condensation edges between roots
+    successors: Vec<HashSet<NodeIndex>>,
+    predecessors: Vec<HashSet<NodeIndex>>,
+    component_count: usize,
+}
+
+impl IncrementalScc {
+    pub fn new() -> Self {
+        IncrementalScc::default()
+    }
+
+    // Start from the nodes and edges already in `graph`. Node indexes are
+    // the same as in the graph.
+    pub fn build<N, E, const A: usize>(graph: &Graph<N, E, A>) -> Self {
+        let mut scc = IncrementalScc::new();
+        for _ in 0..graph.nodes.len() {
+            scc.add_node();
+        }
+        for edge in graph.edges.iter() {
+            scc.add_edge(Edge {
+                from: edge.from,
+                to: edge.to,
+            });
+        }
+        scc
+    }
+
+    // A new node, in a component of its own.
+    pub fn add_node(&mut self) -> NodeIndex {
+        let node_idx = self.parent.len();
+        self.parent.push(node_idx);
+        self.members.push(vec![node_idx]);
+        self.successors.push(HashSet::new());
+        self.predecessors.push(HashSet::new());
+        self.component_count += 1;
+        node_idx
+    }
+
+    // Returns `true` if the edge merged some components together.
+    pub fn add_edge(&mut self, edge: Edge) -> bool {
+        assert!(
+            edge.from < self.parent.len() && edge.to < self.parent.len(),
+            "edge {:?} points to a node that doesn't exist",
+            edge
+        );
+
+        let from = self.component(edge.from);
+        let to = self.component(edge.to);
+        if from == to || !self.successors[from].insert(to) {
+            return false;
+        }
+        self.predecessors[to].insert(from);
+
+        let forward = self.reachable_from(to);
+        if !forward.contains(&from) {
+            return false;
+        }
+
+        // the components on a cycle through the new edge: reachable from
+        // `to`, and reaching `from`
+        let mut cycle = HashSet::from([from]);
+        let mut stack = vec![from];
+        while let Some(component) = stack.pop() {
+            for pred in self.predecessors[component].iter().copied() {
+                if forward.contains(&pred) && cycle.insert(pred) {
+                    stack.push(pred);
+                }
+            }
+        }
+
+        self.merge(&cycle);
+        true
+    }
+
+    // The component of a node, identified by one of its members. Only stable
+    // until the next merge.
+    pub fn component(&self, node_idx: NodeIndex) -> NodeIndex {
+        let mut current = node_idx;
+        while self.parent[current] != current {
+            current = self.parent[current];
+        }
+        current
+    }
+
+    pub fn same_component(&self, a: NodeIndex, b: NodeIndex) -> bool {
+        self.component(a) == self.component(b)
+    }
+
+    // The other members of a node's component, itself included, sorted.
+    pub fn members(&self, node_idx: NodeIndex) -> &[NodeIndex] {
+        &self.members[self.component(node_idx)]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.component_count
+    }
+
+    // All the components, members sorted, ordered by their lowest member.
+    pub fn components(&self) -> Vec<Vec<NodeIndex>> {
+        let mut components: Vec<Vec<NodeIndex>> = self
+            .members
+            .iter()
+            .filter(|members| !members.is_empty())
+            .cloned()
+            .collect();
+        components.sort_unstable_by_key(|members| members[0]);
+        components
+    }
+
+    fn reachable_from(&self, start: NodeIndex) -> HashSet<NodeIndex> {
+        let mut seen = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(component) = stack.pop() {
+            for succ in self.successors[component].iter().copied() {
+                if seen.insert(succ) {
+                    stack.push(succ);
+                }
+            }
+        }
+        seen
+    }
+
+    // Collapse `cycle` into its biggest component, so the union-find trees
+    // stay shallow.
+    fn merge(&mut self, cycle: &HashSet<NodeIndex>) {
+        let root = cycle
+            .iter()
+            .copied()
+            .max_by_key(|c| (self.members[*c].len(), std::cmp::Reverse(*c)))
+            .unwrap();
+
+        for component in cycle.iter().copied().filter(|c| *c != root) {
+            self.parent[component] = root;
+            let members = std::mem::take(&mut self.members[component]);
+            self.members[root].extend(members);
+
+            for succ in std::mem::take(&mut self.successors[component]) {
+                self.predecessors[succ].remove(&component);
+                if !cycle.contains(&succ) {
+                    self.predecessors[succ].insert(root);
+                    self.successors[root].insert(succ);
+                }
+            }
+            for pred in std::mem::take(&mut self.predecessors[component]) {
+                self.successors[pred].remove(&component);
+                if !cycle.contains(&pred) {
+                    self.successors[pred].insert(root);
+                    self.predecessors[root].insert(pred);
+                }
+            }
+        }
+
+        self.successors[root].retain(|c| !cycle.contains(c));
+        self.predecessors[root].retain(|c| !cycle.contains(c));
+        self.members[root].sort_unstable();
+        self.component_count -= cycle.len() - 1;
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::graph::{Edge, Graph, GraphKind, Node, NodeIndex};
+
+/*
+JSON Graph Format (the `json` feature, https://jsongraphformat.info), version 2:
+
+    {
+        "graph": {
+            "directed": true,
+            "metadata": { <graph attributes> },
+            "nodes": { "0": { "label": "hello" }, ... },
+            "edges": [{ "source": "0", "target": "1", "metadata": { "weight": 3 } }, ...]
+        }
+    }
+
+Node IDs are node indexes, nodes are written in index order.
+*/
+
+// A document `from_json` couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct JgfError {
+    pub message: String,
+}
+
+impl fmt::Display for JgfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON Graph Format: {}", self.message)
+    }
+}
+
+impl std::error::Error for JgfError {}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Display,
+    E: Serialize,
+{
+    // Labels come from `Display`, edge payloads are written as the `weight`
+    // metadata unless they serialize to nothing (`()`).
+    pub fn to_json(&self) -> String {
+        let mut nodes = Map::new();
+        for (node_idx, node) in self.nodes.iter().enumerate() {
+            nodes.insert(node_idx.to_string(), json!({ "label": node.to_string() }));
+        }
+
+        let edges: Vec<Value> = self
+            .edges
+            .iter()
+            .zip(self.edge_weights.iter())
+            .map(|(edge, weight)| {
+                let mut edge_json = json!({
+                    "source": edge.from.to_string(),
+                    "target": edge.to.to_string(),
+                });
+                match serde_json::to_value(weight) {
+                    Ok(Value::Null) | Err(_) => {}
+                    Ok(weight) => edge_json["metadata"] = json!({ "weight": weight }),
+                }
+                edge_json
+            })
+            .collect();
+
+        let mut graph = json!({ "directed": self.kind == GraphKind::Directed });
+        if !self.attrs.is_empty() {
+            graph["metadata"] = json!(self.attrs);
+        }
+        graph["nodes"] = Value::Object(nodes);
+        graph["edges"] = Value::Array(edges);
+        json!({ "graph": graph }).to_string()
+    }
+}
+
+impl Graph {
+    /*
+    Read a JSON Graph Format document, the first graph of a `graphs` list.
+    Nodes can be an object keyed by ID (version 2) or an array of nodes with
+    an `id` (version 1), they get their `label` as `DataTypes::Text`, or
+    their ID when they have none, in document order. `"directed": false`
+    makes an undirected graph, string values of the graph `metadata` become
+    graph attributes. Edge metadata is skipped.
+    */
+    pub fn from_json(json: &str) -> Result<Graph, JgfError> {
+        let error = |message: String| JgfError { message };
+        let document: Value = serde_json::from_str(json).map_err(|err| error(err.to_string()))?;
+
+        let graph_json = match (document.get("graph"), document.get("graphs")) {
+            (Some(graph), _) => graph,
+            (None, Some(Value::Array(graphs))) => graphs
+                .first()
+                .ok_or_else(|| error("empty `graphs`".to_string()))?,
+            _ => return Err(error("no `graph`".to_string())),
+        };
+
+        let kind = match graph_json.get("directed") {
+            Some(Value::Bool(false)) => GraphKind::Undirected,
+            _ => GraphKind::Directed,
+        };
+        let mut graph = Graph::with_kind(kind);
+        if let Some(Value::Object(metadata)) = graph_json.get("metadata") {
+            for (key, value) in metadata.iter() {
+                if let Value::String(value) = value {
+                    graph.set_attr(key.clone(), value.clone());
+                }
+            }
+        }
+
+        let nodes: Vec<(&str, &Value)> = match graph_json.get("nodes") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Object(nodes)) => {
+                nodes.iter().map(|(id, node)| (id.as_str(), node)).collect()
+            }
+            Some(Value::Array(nodes)) => nodes
+                .iter()
+                .map(|node| {
+                    let id = node
+                        .get("id")
+                        .and_then(|id| id.as_str())
+                        .ok_or_else(|| error(format!("node without an id: {}", node)))?;
+                    Ok((id, node))
+                })
+                .collect::<Result<_, JgfError>>()?,
+            Some(_) => return Err(error("`nodes` should be an object or an array".to_string())),
+        };
+
+        let mut ids: HashMap<&str, NodeIndex> = HashMap::new();
+        for (id, node) in nodes {
+            if ids.contains_key(id) {
+                return Err(error(format!("duplicate node id {:?}", id)));
+            }
+            let label = node
+                .get("label")
+                .and_then(|label| label.as_str())
+                .unwrap_or(id);
+            ids.insert(id, graph.add_node_unchecked(Node::from(label)));
+        }
+
+        let edges = match graph_json.get("edges") {
+            None | Some(Value::Null) => &Vec::new(),
+            Some(Value::Array(edges)) => edges,
+            Some(_) => return Err(error("`edges` should be an array".to_string())),
+        };
+        for edge in edges {
+            let endpoint = |field: &str| {
+                let id = edge
+                    .get(field)
+                    .and_then(|id| id.as_str())
+                    .ok_or_else(|| error(format!("edge without a {}: {}", field, edge)))?;
+                ids.get(id)
+                    .copied()
+                    .ok_or_else(|| error(format!("edge to an unknown node {:?}", id)))
+            };
+            let from = endpoint("source")?;
+            let to = endpoint("target")?;
+            graph.add_edge(Edge { from, to });
+        }
+
+        Ok(graph)
+    }
+}
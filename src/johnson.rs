@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use crate::bellman_ford::{cycle_from, NegativeCycle};
+use crate::dijkstra::dijkstra_search;
+use crate::graph::{Cost, Graph, NodeIndex};
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Copy + Into<Cost>,
+{
+    /*
+    Johnson's algorithm, all-pairs shortest paths for sparse graphs with
+    possibly negative costs. O(V * E * log V) instead of Floyd-Warshall's
+    O(V^3), and no V x V matrix.
+
+    1. Bellman-Ford from a virtual node with a 0-cost edge to every node gives
+       a potential `h` for every node.
+    2. Every edge u -> v is reweighted to `cost + h[u] - h[v]`, which is never
+       negative, and shortest paths stay the same.
+    3. Dijkstra from every node, undoing the reweighting on the way out.
+
+    Step 1 runs right away and fails if there's a negative cycle anywhere in
+    the graph. Edges and paths whose (reweighted) cost overflows a `Cost` are
+    ignored. Step 3 runs lazily, one source at a time, as the iterator is
+    consumed. Every map only contains the nodes reachable from its source
+    (the source included).
+    */
+    pub fn all_pairs_shortest_paths_sparse(
+        &self,
+    ) -> Result<impl Iterator<Item = (NodeIndex, HashMap<NodeIndex, Cost>)> + '_, NegativeCycle>
+    {
+        let potentials = self.johnson_potentials()?;
+
+        let mut successors: Vec<Vec<(NodeIndex, Cost)>> = vec![Vec::new(); self.nodes.len()];
+        for (edge_idx, arc) in self.arcs() {
+            let cost: Cost = self.edge_weights[edge_idx].into();
+            let reweighted = cost
+                .checked_add(potentials[arc.from])
+                .and_then(|cost| cost.checked_sub(potentials[arc.to]));
+            if let Some(reweighted) = reweighted {
+                successors[arc.from].push((arc.to, reweighted));
+            }
+        }
+
+        Ok((0..self.nodes.len()).map(move |source| {
+            let paths = dijkstra_search(&successors, source, None, None);
+            let distances = paths
+                .distances
+                .iter()
+                .enumerate()
+                .filter_map(|(target, d)| {
+                    let d = (*d)?
+                        .checked_add(potentials[target])?
+                        .checked_sub(potentials[source])?;
+                    Some((target, d))
+                })
+                .collect();
+            (source, distances)
+        }))
+    }
+
+    // Bellman-Ford distances from the virtual node. Starting every node at 0
+    // is the same as relaxing the virtual node's edges first, and the extra
+    // node means n rounds are needed instead of n - 1.
+    fn johnson_potentials(&self) -> Result<Vec<Cost>, NegativeCycle> {
+        let n = self.nodes.len();
+        let mut distances: Vec<Option<Cost>> = vec![Some(0); n];
+        let mut predecessors: Vec<Option<NodeIndex>> = vec![None; n];
+
+        for _ in 0..n {
+            if self
+                .relax_edges(&mut distances, &mut predecessors, |weight| (*weight).into())
+                .is_none()
+            {
+                return Ok(distances.into_iter().map(|d| d.unwrap()).collect());
+            }
+        }
+
+        match self.relax_edges(&mut distances, &mut predecessors, |weight| (*weight).into()) {
+            None => Ok(distances.into_iter().map(|d| d.unwrap()).collect()),
+            Some(node_idx) => Err(NegativeCycle {
+                cycle: cycle_from(&predecessors, node_idx),
+            }),
+        }
+    }
+}
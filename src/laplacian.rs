@@ -0,0 +1,148 @@
+use crate::graph::Graph;
+use crate::rng::Rng;
+
+/*
+Result of `fiedler`: the algebraic connectivity (second smallest eigenvalue
+of the Laplacian) and its eigenvector. The value is 0 when the graph is
+disconnected and grows with how hard it is to cut in two, the signs of the
+vector give the classic spectral bisection.
+*/
+#[derive(Clone, PartialEq, Debug)]
+pub struct Fiedler {
+    pub value: f64,
+    pub vector: Vec<f64>,
+    // whether the estimate settled before running out of iterations
+    pub converged: bool,
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    The Laplacian `D - A`, edges read in both directions and without weights
+    (self-loops and edges in both directions count once), like
+    `spectral_embedding`. Rows and columns follow node indexes.
+    */
+    pub fn laplacian_matrix(&self) -> Vec<Vec<f64>> {
+        let neighbours = self.undirected_neighbours();
+        let n = self.nodes.len();
+        let mut laplacian = vec![vec![0.0; n]; n];
+        for (u, adjacent) in neighbours.iter().enumerate() {
+            laplacian[u][u] = adjacent.len() as f64;
+            for v in adjacent {
+                laplacian[u][*v] = -1.0;
+            }
+        }
+        laplacian
+    }
+
+    // The normalized Laplacian `I - D^-1/2 A D^-1/2`, isolated nodes get a
+    // row and column of zeros.
+    pub fn normalized_laplacian_matrix(&self) -> Vec<Vec<f64>> {
+        let neighbours = self.undirected_neighbours();
+        let n = self.nodes.len();
+        let scale: Vec<f64> = neighbours
+            .iter()
+            .map(|adjacent| match adjacent.len() {
+                0 => 0.0,
+                degree => 1.0 / (degree as f64).sqrt(),
+            })
+            .collect();
+
+        let mut laplacian = vec![vec![0.0; n]; n];
+        for (u, adjacent) in neighbours.iter().enumerate() {
+            if !adjacent.is_empty() {
+                laplacian[u][u] = 1.0;
+            }
+            for v in adjacent {
+                laplacian[u][*v] = -scale[u] * scale[*v];
+            }
+        }
+        laplacian
+    }
+
+    /*
+    Estimate the Fiedler value and vector of the Laplacian (see
+    `laplacian_matrix`) without a dense eigendecomposition: O(m) memory and
+    O(n + m) per iteration, so it works on graphs `spectral_embedding`
+    can't handle.
+
+    Power iteration on `c * I - L` (c = twice the max degree, which bounds
+    the largest eigenvalue), keeping the vector orthogonal to the all-ones
+    eigenvector of the eigenvalue 0. It converges to the eigenvector of the
+    second smallest eigenvalue, slowly when the third one is close to it:
+    `converged` is false if the Rayleigh quotient still moved more than
+    1e-10 (relative) on the last of `max_iterations`. The starting vector
+    comes from `seed`, same seed, same result.
+    */
+    pub fn fiedler(&self, max_iterations: usize, seed: u64) -> Fiedler {
+        let n = self.nodes.len();
+        let neighbours = self.undirected_neighbours();
+        let laplacian_times = |x: &[f64]| -> Vec<f64> {
+            neighbours
+                .iter()
+                .enumerate()
+                .map(|(u, adjacent)| {
+                    adjacent.len() as f64 * x[u] - adjacent.iter().map(|v| x[*v]).sum::<f64>()
+                })
+                .collect()
+        };
+        if n < 2 {
+            return Fiedler {
+                value: 0.0,
+                vector: vec![0.0; n],
+                converged: true,
+            };
+        }
+
+        let shift = 2.0 * neighbours.iter().map(Vec::len).max().unwrap_or(0) as f64;
+        let mut rng = Rng::new(seed);
+        let mut vector: Vec<f64> = (0..n).map(|_| rng.next_f64() - 0.5).collect();
+        normalize(&mut vector);
+
+        let mut product = laplacian_times(&vector);
+        let mut value = dot(&vector, &product);
+        for _ in 0..max_iterations {
+            vector = vector
+                .iter()
+                .zip(&product)
+                .map(|(x, y)| shift * x - y)
+                .collect();
+            normalize(&mut vector);
+            product = laplacian_times(&vector);
+
+            let quotient = dot(&vector, &product);
+            let settled = (quotient - value).abs() <= 1e-10 * quotient.abs().max(1.0);
+            value = quotient;
+            if settled {
+                return Fiedler {
+                    value,
+                    vector,
+                    converged: true,
+                };
+            }
+        }
+
+        Fiedler {
+            value,
+            vector,
+            converged: false,
+        }
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+// Remove the component along the all-ones vector, then scale to length 1.
+fn normalize(vector: &mut [f64]) {
+    let mean = vector.iter().sum::<f64>() / vector.len() as f64;
+    for x in vector.iter_mut() {
+        *x -= mean;
+    }
+    let norm = vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for x in vector.iter_mut() {
+            *x /= norm;
+        }
+    }
+}
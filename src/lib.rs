@@ -1,8 +1,156 @@
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+pub mod astar;
+pub mod bellman_ford;
+pub mod bfs;
+pub mod bidirectional;
+pub mod binary;
+pub mod bipartite;
+pub mod blobs;
+pub mod builder;
+pub mod bundle;
+#[cfg(feature = "json")]
+pub mod cargo_metadata;
+pub mod chains;
+pub mod coarsen;
+pub mod compare;
+pub mod convert;
+pub mod cycles;
+#[cfg(feature = "json")]
+pub mod cytoscape;
+pub mod delta;
+pub mod dijkstra;
+#[cfg(feature = "fs")]
+pub mod directory;
+pub mod dominators;
+pub mod dot;
+pub mod embedding;
+#[cfg(feature = "json")]
+pub mod entities;
+pub mod feedback;
+pub mod floyd_warshall;
+pub mod gexf;
+pub mod gml;
 pub mod graph;
+pub mod graph_map;
+pub mod graphml;
+pub mod hash;
+pub mod impact;
+pub mod incremental_scc;
+#[cfg(feature = "json")]
+pub mod jgf;
+pub mod johnson;
+pub mod laplacian;
+pub mod limits;
+mod lookup;
+pub mod loops;
+pub mod markov;
+pub mod matrix;
+pub mod matrix_market;
+pub mod measure;
+pub mod merge;
+pub mod mermaid;
+pub mod optimize;
+pub mod pajek;
+pub mod pareto;
+pub mod payloads;
+#[cfg(feature = "petgraph")]
+mod petgraph_interop;
+pub mod quotient;
+pub mod random;
+pub mod ranking;
+pub mod reach;
+mod rng;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod simrank;
+pub mod simulate;
+pub mod slice;
+pub mod spanning;
+pub mod spill;
+pub mod stable;
+pub mod static_graph;
+pub mod stats;
+pub mod time_dependent;
+pub mod topo;
+pub mod trace;
+pub mod traits;
+#[cfg(feature = "json")]
+pub mod tree_json;
+pub mod triangles;
+pub mod turns;
+pub mod visit;
+pub mod within;
+mod xml;
 
+#[cfg(feature = "bumpalo")]
+pub use crate::arena::ArenaGraph;
+pub use crate::bellman_ford::NegativeCycle;
+pub use crate::bfs::Bfs;
+pub use crate::binary::{BinaryData, BinaryError};
+pub use crate::bipartite::{BipartiteGraph, LeftNode, RightNode, Side};
+pub use crate::blobs::{BlobStore, SharedBlob};
+pub use crate::builder::GraphBuilder;
+pub use crate::bundle::Bundle;
+#[cfg(feature = "json")]
+pub use crate::cargo_metadata::{
+    CargoDependency, CargoMetadataError, CargoPackage, DependencyKind, DependencyUse,
+};
+pub use crate::chains::Chain;
+pub use crate::coarsen::CoarseLevel;
+pub use crate::compare::EditDistance;
+#[cfg(feature = "json")]
+pub use crate::cytoscape::CytoscapeError;
+pub use crate::delta::DotSnapshot;
+pub use crate::dijkstra::ShortestPaths;
+#[cfg(feature = "fs")]
+pub use crate::directory::FsEdge;
+pub use crate::dominators::Dominators;
+pub use crate::dot::{DotError, DotOptions};
+#[cfg(feature = "json")]
+pub use crate::entities::{EntitiesError, Relation};
+pub use crate::floyd_warshall::DistanceMatrix;
+pub use crate::gexf::{GexfOptions, Spell};
+pub use crate::gml::GmlError;
+pub use crate::graph::Cost;
+pub use crate::graph::DataRef;
 pub use crate::graph::Edge;
+pub use crate::graph::EdgeIndex;
 pub use crate::graph::Graph;
+pub use crate::graph::GraphKind;
+pub use crate::graph::InvalidNode;
 pub use crate::graph::Node;
+pub use crate::graph::NodeIndex;
+pub use crate::graph_map::GraphMap;
+pub use crate::graphml::{GraphmlData, GraphmlError};
+pub use crate::incremental_scc::IncrementalScc;
+#[cfg(feature = "json")]
+pub use crate::jgf::JgfError;
+pub use crate::laplacian::Fiedler;
+pub use crate::limits::{Limit, LimitExceeded, Limits};
+pub use crate::loops::NaturalLoop;
+pub use crate::markov::Absorption;
+pub use crate::matrix_market::MatrixMarketError;
+pub use crate::measure::Measure;
+pub use crate::merge::{DuplicateEdge, MergePolicy};
+pub use crate::mermaid::{MermaidDirection, MermaidOptions};
+pub use crate::optimize::OptimizeReport;
+pub use crate::pajek::PajekError;
+pub use crate::ranking::RankedNode;
+pub use crate::reach::ReachIndex;
+pub use crate::simulate::SpreadModel;
+pub use crate::spill::{SpillStore, SpillingBfs, TempFileStore};
+pub use crate::stable::{NodeKey, StableGraph, StaleKey};
+pub use crate::static_graph::StaticGraph;
+pub use crate::topo::CycleError;
+pub use crate::trace::{Trace, TraceEvent};
+pub use crate::traits::{Algorithms, GraphBase, IntoNodeIdentifiers, Neighbors, WeightedNeighbors};
+#[cfg(feature = "json")]
+pub use crate::tree_json::TreeJsonError;
+pub use crate::triangles::TriangleEstimate;
+pub use crate::visit::{Control, DfsEvent};
 
 /*
 https://asciiflow.com/#/share/eJyrVspLzE1VssorzcnRUcpJrEwtUrJSqo5RqohRsrK0MNOJUaoEsowsLICsktSKEiAnRkkBN3g0pefRlAYMNCEmJg%2BvriY4A7vKR1P2oKNpuyD6%2FIzw6yPFDjhrCjZfzMCpjwQJLD5BCiFsslgQmvkIl8P94WeAKYwvah5NwzACl2uxxAEiXPG7GKd5KHaDItUYyUD06MHGxeIOoCmGRGjDGdXE2gwVI%2Bx5AroxhBXQowW%2FaqJsn4ZTL6bzYGmzAYmBMzNDAtwEwwQYA3umQ7EXVxqDGG2K1xRskUiCO4g1ckaMUq1SLQD7%2FQPc)
@@ -37,44 +185,38 @@ mod tests {
     use super::*;
 
     fn generate_base_graph() -> Graph {
-        let mut g = Graph::new();
-
-        let idx0 = g.add_node(Node::from("hello"));
-        let idx1 = g.add_node(Node::from("world"));
-        let idx2 = g.add_node(Node::from("foo"));
-        let idx3 = g.add_node(Node::from("bar"));
-        let idx4 = g.add_node(Node::from("baz"));
-        let idx5 = g.add_node(Node::from("asd"));
-
-        println!("Reachable {:?}", g.reachable_nodes_from(idx2));
-
-        g.add_edge(Edge {
-            from: idx0,
-            to: idx1,
-        });
-        g.add_edge(Edge {
-            from: idx0,
-            to: idx2,
-        });
-        g.add_edge(Edge {
-            from: idx0,
-            to: idx3,
-        });
-        g.add_edge(Edge {
-            from: idx0,
-            to: idx4,
-        });
-
-        g.add_edge(Edge {
-            from: idx3,
-            to: idx5,
-        });
-        g.add_edge(Edge {
-            from: idx4,
-            to: idx5,
-        });
+        GraphBuilder::new()
+            .node("hello")
+            .node("world")
+            .node("foo")
+            .node("bar")
+            .node("baz")
+            .node("asd")
+            .edge("hello", "world")
+            .edge("hello", "foo")
+            .edge("hello", "bar")
+            .edge("hello", "baz")
+            .edge("bar", "asd")
+            .edge("baz", "asd")
+            .build()
+    }
 
-        g
+    // `generate_base_graph` with its nodes and edges added in reverse order
+    fn generate_reversed_base_graph() -> Graph {
+        GraphBuilder::new()
+            .node("asd")
+            .node("baz")
+            .node("bar")
+            .node("foo")
+            .node("world")
+            .node("hello")
+            .edge("baz", "asd")
+            .edge("bar", "asd")
+            .edge("hello", "baz")
+            .edge("hello", "bar")
+            .edge("hello", "foo")
+            .edge("hello", "world")
+            .build()
     }
 
     #[test]
@@ -85,6 +227,27 @@ mod tests {
         assert_eq!(vec![idx], g2.boundary().unwrap());
     }
 
+    #[test]
+    fn node_bytes_are_read_without_copying() {
+        let blob = Node::from(vec![1, 2, 3]);
+        let bytes = blob.to_bytes();
+        let Some(DataRef::Blob(read)) = DataRef::from_bytes(&bytes) else {
+            panic!("expected a blob");
+        };
+        assert_eq!(read, &[1, 2, 3]);
+        // points into `bytes`
+        assert!(bytes.as_ptr_range().contains(&read.as_ptr()));
+
+        for node in [Node::from("hello"), Node::from(-7), blob] {
+            let bytes = node.to_bytes();
+            let data = DataRef::from_bytes(&bytes).unwrap();
+            assert_eq!(data, DataRef::from(&node));
+            assert_eq!(data.to_node(), node);
+        }
+        assert_eq!(None, DataRef::from_bytes(&[9, 1]));
+        assert_eq!(None, DataRef::from_bytes(&[1, 0]));
+    }
+
     #[test]
     fn test_boundary() {
         // println!("{:#?}", g);
@@ -110,6 +273,3596 @@ mod tests {
 
         assert_eq!(None, g.shortest_path(2, 5));
         assert_eq!(None, g.shortest_path(1, 5));
+        assert_eq!(Some(vec![2]), g.shortest_path(2, 2));
+        assert_eq!(None, g.shortest_path(0, 99));
+
+        // used to spin forever once the reachable part was exhausted
+        let mut g: Graph<isize> = Graph::default();
+        for i in 0..3 {
+            g.add_node(i);
+        }
+        g.add_edge(Edge { from: 0, to: 1 });
+        assert_eq!(None, g.shortest_path(0, 2));
+    }
+
+    #[test]
+    fn adjacency_survives_node_removal() {
+        let mut g: Graph<Node, (), 1> = Graph::with_inline_adjacency();
+        let a = g.add_node(Node::from("a"));
+        let b = g.add_node(Node::from("b"));
+        let c = g.add_node(Node::from("c"));
+        let d = g.add_node(Node::from("d"));
+        g.add_edge(Edge { from: a, to: b });
+        g.add_edge(Edge { from: a, to: d });
+        g.add_edge(Edge { from: d, to: c });
+
+        assert_eq!(vec![b, d], g.reachable_nodes_from(a));
+
+        // "d" gets moved into the slot freed by "b"
+        g.remove_node(b);
+        assert_eq!(Some(1), g.find_node_idx(Node::from("d")));
+        assert_eq!(vec![1], g.reachable_nodes_from(a));
+        assert_eq!(vec![c], g.reachable_nodes_from(1));
+        assert_eq!(vec![1], g.nodes_that_can_reach(c));
+    }
+
+    #[test]
+    fn optimize_reorders_nodes() {
+        // a chain 0 -> 5 -> 1 -> 4 -> 2 -> 3, scattered all over the node array
+        let mut g = Graph::new();
+        for i in 0..6 {
+            g.add_node(Node::from(i as isize));
+        }
+        for (from, to) in [(0, 5), (5, 1), (1, 4), (4, 2), (2, 3)] {
+            g.add_edge(Edge { from, to });
+        }
+
+        let report = g.optimize();
+        assert_eq!(5, report.bandwidth_before);
+        assert_eq!(1, report.bandwidth_after);
+        assert_eq!(vec![0, 5, 1, 4, 2, 3], report.permutation);
+
+        let idx5 = g.find_node_idx(Node::from(5)).unwrap();
+        let idx1 = g.find_node_idx(Node::from(1)).unwrap();
+        assert_eq!(vec![idx1], g.reachable_nodes_from(idx5));
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], g.shortest_path(0, 5).unwrap());
+
+        // (4, 2) was added fourth, it's now (3, 4)
+        assert_eq!(Some(&Edge { from: 3, to: 4 }), g.edge(report.edge_map[3]));
+    }
+
+    #[test]
+    fn rcm_and_degree_orderings() {
+        let g = generate_base_graph();
+
+        // degrees: 0 -> 4, 1 -> 1, 2 -> 1, 3 -> 2, 4 -> 2, 5 -> 2
+        assert_eq!(vec![1, 2, 3, 4, 5, 0], g.degree_ordering());
+
+        let rcm = g.rcm_ordering();
+        let mut sorted = rcm.clone();
+        sorted.sort();
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], sorted);
+        assert_eq!(vec![5, 4, 3, 2, 0, 1], rcm);
+
+        let mut permuted = generate_base_graph();
+        let edge_map = permuted.permute(&rcm);
+        for (old_edge_idx, edge) in g.edges.iter().enumerate() {
+            let moved = permuted.edge(edge_map[old_edge_idx]).unwrap();
+            assert_eq!(g.nodes[edge.from], permuted.nodes[moved.from]);
+            assert_eq!(g.nodes[edge.to], permuted.nodes[moved.to]);
+        }
+        assert!(permuted.bandwidth() <= g.bandwidth());
+        let hello = permuted.find_node_idx(Node::from("hello")).unwrap();
+        assert_eq!(4, hello);
+        assert_eq!(4, permuted.reachable_nodes_from(hello).len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn permute_rejects_duplicates() {
+        let mut g = generate_base_graph();
+        g.permute(&[0, 0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn bfs_with_budget_spills() {
+        // binary tree with 200 nodes, node i -> 2i + 1, 2i + 2
+        let mut g = Graph::new();
+        for i in 0..200 {
+            g.add_node(Node::from(i as isize));
+        }
+        for i in 0..200 {
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < 200 {
+                    g.add_edge(Edge { from: i, to: child });
+                }
+            }
+        }
+
+        let store = std::io::Cursor::new(Vec::new());
+        let mut bfs = g.bfs_with_budget(0, 64, store);
+        let order: Vec<NodeIndex> = bfs.by_ref().map(|n| n.unwrap()).collect();
+
+        assert!(bfs.spilled());
+        assert_eq!((0..200).collect::<Vec<_>>(), order);
+
+        assert!(g.can_reach_with_budget(5, 199, 32).unwrap());
+        assert!(!g.can_reach_with_budget(3, 199, 32).unwrap());
+    }
+
+    #[test]
+    fn adjacency_map_round_trip() {
+        let mut map = std::collections::HashMap::new();
+        map.insert("a", vec!["b", "c"]);
+        map.insert("b", vec!["c"]);
+
+        let g: Graph = Graph::from_adjacency_map(map);
+        let a = g.find_node_idx(Node::from("a")).unwrap();
+        let c = g.find_node_idx(Node::from("c")).unwrap();
+        assert_eq!(2, g.reachable_nodes_from(a).len());
+        assert!(g.reachable_nodes_from(c).is_empty());
+
+        let back = g.to_adjacency_map();
+        assert_eq!(3, back.len());
+        assert_eq!(vec![&Node::from("c")], back[&Node::from("b")]);
+        assert!(back[&Node::from("c")].is_empty());
+    }
+
+    #[test]
+    fn canonical_edge_list_ignores_insertion_order() {
+        let g = generate_base_graph();
+
+        let reversed = generate_reversed_base_graph();
+
+        assert_eq!(g.canonical_edge_list(), reversed.canonical_edge_list());
+        assert_eq!(
+            (&Node::from("bar"), &Node::from("asd")),
+            g.canonical_edge_list()[0]
+        );
+    }
+
+    #[test]
+    fn same_topology_matches_by_payload() {
+        let g = generate_base_graph();
+
+        let mut other = generate_reversed_base_graph();
+        assert!(g.same_topology(&other));
+        assert!(other.same_topology(&g));
+
+        other.add_edge(Edge { from: 0, to: 5 });
+        assert!(!g.same_topology(&other));
+
+        let mut renamed = generate_base_graph();
+        renamed.remove_node(0);
+        renamed.add_node(Node::from("bye"));
+        assert!(!g.same_topology(&renamed));
+
+        // undirected edges match either way around, but never directed ones
+        let mut line: Graph<&str> = Graph::with_kind(GraphKind::Undirected);
+        let mut flipped: Graph<&str> = Graph::with_kind(GraphKind::Undirected);
+        for label in ["a", "b", "c"] {
+            line.add_node(label);
+            flipped.add_node(label);
+        }
+        line.add_edge(Edge { from: 0, to: 1 });
+        line.add_edge(Edge { from: 1, to: 2 });
+        flipped.add_edge(Edge { from: 2, to: 1 });
+        flipped.add_edge(Edge { from: 1, to: 0 });
+        assert!(line.same_topology(&flipped));
+        flipped.set_kind(GraphKind::Directed);
+        assert!(!line.same_topology(&flipped));
+        line.set_kind(GraphKind::Directed);
+        assert!(!line.same_topology(&flipped));
+    }
+
+    #[test]
+    fn subgraph_hash_tracks_dependencies() {
+        let g = generate_base_graph();
+        let root_hash = g.subgraph_hash(0).unwrap();
+
+        // same content, different insertion order
+        let other = generate_reversed_base_graph();
+        assert_eq!(Some(root_hash), other.subgraph_hash(5));
+        assert_eq!(g.subgraph_hash(3), other.subgraph_hash(2));
+
+        // changing a transitive dependency changes the root, but not siblings
+        let mut changed = generate_base_graph();
+        let extra = changed.add_node(Node::from("extra"));
+        changed.add_edge(Edge { from: 5, to: extra });
+        assert_ne!(Some(root_hash), changed.subgraph_hash(0));
+        assert_eq!(g.subgraph_hash(2), changed.subgraph_hash(2));
+
+        // cycles can't be hashed
+        changed.add_edge(Edge { from: 5, to: 0 });
+        assert_eq!(None, changed.subgraph_hash(0));
+        assert_eq!(None, changed.subgraph_hash(42));
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let mut g = generate_base_graph();
+        assert_eq!(6, g.strongly_connected_components().len());
+
+        g.add_edge(Edge { from: 5, to: 0 });
+        let mut components = g.strongly_connected_components();
+        components.iter_mut().for_each(|c| c.sort());
+        components.sort();
+        assert_eq!(vec![vec![0, 3, 4, 5], vec![1], vec![2]], components);
+    }
+
+    #[test]
+    fn condensation_is_a_dag() {
+        let mut g = generate_base_graph();
+        // 0 -> 3 -> 5 -> 0 and 0 -> 4 -> 5 -> 0 make {0, 3, 4, 5} one component
+        g.add_edge(Edge { from: 5, to: 0 });
+        let extra = g.add_node(Node::from("extra"));
+        g.add_edge(Edge { from: 4, to: extra });
+        g.add_edge(Edge { from: extra, to: 2 });
+
+        let condensed = g.condensation();
+        assert_eq!(
+            vec![vec![0, 3, 4, 5], vec![6], vec![2], vec![1]],
+            condensed.nodes
+        );
+        assert_eq!(4, condensed.edges.len());
+        assert!(condensed.edges.iter().all(|e| e.from < e.to));
+        assert_eq!(None, condensed.find_cycle());
+        assert_eq!(vec![3, 2, 1], condensed.reachable_nodes_from(0));
+
+        // acyclic graphs map one node to one component
+        assert_eq!(6, generate_base_graph().condensation().nodes.len());
+    }
+
+    #[test]
+    fn simple_cycles_enumerates_every_cycle() {
+        let mut g = generate_base_graph();
+        assert!(g.simple_cycles(None).is_empty());
+
+        g.add_edge(Edge { from: 5, to: 0 });
+        g.add_edge(Edge { from: 2, to: 2 });
+        let mut cycles = g.simple_cycles(None);
+        cycles.sort();
+        assert_eq!(vec![vec![0, 3, 5], vec![0, 4, 5], vec![2]], cycles);
+
+        assert_eq!(1, g.simple_cycles(Some(1)).len());
+
+        // complete digraph on 4 nodes: 6 2-cycles + 8 3-cycles + 6 4-cycles
+        let mut complete = Graph::new();
+        for i in 0..4 {
+            complete.add_node(Node::from(i as isize));
+        }
+        for from in 0..4 {
+            for to in (0..4).filter(|to| *to != from) {
+                complete.add_edge(Edge { from, to });
+            }
+        }
+        assert_eq!(20, complete.simple_cycles(None).len());
+
+        // deep enough to overflow the stack if the search recursed
+        let n = 200_000;
+        let mut ring: Graph<usize> = Graph::with_inline_adjacency();
+        for i in 0..n {
+            ring.add_node_unchecked(i);
+        }
+        for from in 0..n {
+            let to = (from + 1) % n;
+            ring.push_edge(Edge { from, to }, ());
+        }
+        let cycles = ring.simple_cycles(Some(1));
+        assert_eq!(1, cycles.len());
+        assert_eq!(n, cycles[0].len());
+    }
+
+    #[test]
+    fn topological_sort_kahn() {
+        let mut g = generate_base_graph();
+        assert_eq!(Ok(vec![0, 1, 2, 3, 4, 5]), g.topological_sort());
+
+        // 6 -> 0 puts the new node first
+        let late = g.add_node(Node::from("late"));
+        g.add_edge(Edge { from: late, to: 0 });
+        assert_eq!(Ok(vec![6, 0, 1, 2, 3, 4, 5]), g.topological_sort());
+
+        // 0 -> 3 -> 5 -> 6 -> 0, 4 and 5 are stuck behind it too
+        g.add_edge(Edge { from: 5, to: late });
+        let err = g.topological_sort().unwrap_err();
+        let mut cycle = err.cycle.clone();
+        cycle.sort();
+        assert!(cycle == vec![0, 3, 5, 6] || cycle == vec![0, 4, 5, 6]);
+        for (i, from) in err.cycle.iter().enumerate() {
+            let to = err.cycle[(i + 1) % err.cycle.len()];
+            assert!(g.reachable_nodes_from(*from).contains(&to));
+        }
+
+        let mut looped = Graph::new();
+        looped.add_node(Node::from("self"));
+        looped.add_edge(Edge { from: 0, to: 0 });
+        assert_eq!(
+            "cycle through nodes [0]",
+            looped.topological_sort().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn find_cycle_returns_a_witness() {
+        let mut g: Graph<&str> = Graph::new();
+        for task in ["app", "lib", "codegen", "schema", "docs"] {
+            g.add_node(task);
+        }
+        g.add_edge(Edge { from: 0, to: 1 });
+        g.add_edge(Edge { from: 1, to: 2 });
+        g.add_edge(Edge { from: 2, to: 3 });
+        g.add_edge(Edge { from: 4, to: 0 });
+        assert_eq!(None, g.find_cycle());
+
+        g.add_edge(Edge { from: 3, to: 1 });
+        let cycle = g.find_cycle().unwrap();
+        let mut names: Vec<&str> = cycle.iter().map(|idx| g.nodes[*idx]).collect();
+        names.push(names[0]);
+        assert_eq!("lib -> codegen -> schema -> lib", names.join(" -> "));
+    }
+
+    // 0 -> 1 -> {2, 3} -> 4, with a back edge 4 -> 1 and an exit 4 -> 5
+    fn generate_loop_cfg() -> Graph {
+        let mut g = Graph::new();
+        for i in 0..6 {
+            g.add_node(Node::from(i as isize));
+        }
+        for (from, to) in [(0, 1), (1, 2), (1, 3), (2, 4), (3, 4), (4, 1), (4, 5)] {
+            g.add_edge(Edge { from, to });
+        }
+        g
+    }
+
+    #[test]
+    fn dominator_tree() {
+        let g = generate_loop_cfg();
+        let dom = g.dominators(0);
+
+        assert_eq!(None, dom.immediate_dominator(0));
+        assert_eq!(Some(0), dom.immediate_dominator(1));
+        assert_eq!(Some(1), dom.immediate_dominator(2));
+        assert_eq!(Some(1), dom.immediate_dominator(4));
+        assert_eq!(Some(4), dom.immediate_dominator(5));
+        assert_eq!(
+            vec![5, 4, 1, 0],
+            dom.dominators(5).unwrap().collect::<Vec<_>>()
+        );
+        assert!(dom.dominates(1, 5));
+        assert!(!dom.dominates(2, 4));
+
+        // nothing reaches node 0 when starting from 1
+        assert_eq!(None, g.dominators(1).immediate_dominator(0));
+    }
+
+    #[test]
+    fn dominance_frontiers() {
+        let g = generate_loop_cfg();
+        let frontiers = g.dominance_frontiers(0);
+
+        let set = |v: Vec<NodeIndex>| v.into_iter().collect::<std::collections::HashSet<_>>();
+        assert_eq!(set(vec![]), frontiers[0]);
+        assert_eq!(set(vec![1]), frontiers[1]);
+        assert_eq!(set(vec![4]), frontiers[2]);
+        assert_eq!(set(vec![4]), frontiers[3]);
+        assert_eq!(set(vec![1]), frontiers[4]);
+        assert_eq!(set(vec![]), frontiers[5]);
+    }
+
+    #[test]
+    fn natural_loops_nesting() {
+        let mut g = generate_loop_cfg();
+        // inner loop 2 -> 6 -> 2, inside the 1 -> ... -> 4 -> 1 loop
+        let six = g.add_node(Node::from(6));
+        g.add_edge(Edge { from: 2, to: six });
+        g.add_edge(Edge { from: six, to: 2 });
+
+        let loops = g.natural_loops(0);
+        assert_eq!(2, loops.len());
+
+        assert_eq!(1, loops[0].header);
+        assert_eq!(vec![4], loops[0].latches);
+        assert_eq!(vec![1, 2, 3, 4, 6], loops[0].body);
+        assert_eq!(None, loops[0].parent);
+        assert_eq!(0, loops[0].depth);
+
+        assert_eq!(2, loops[1].header);
+        assert_eq!(vec![2, 6], loops[1].body);
+        assert_eq!(Some(0), loops[1].parent);
+        assert_eq!(1, loops[1].depth);
+
+        // a node the root can't reach jumping into the loop isn't part of it
+        let stray = g.add_node(Node::from(7));
+        g.add_edge(Edge { from: stray, to: 3 });
+        assert_eq!(vec![1, 2, 3, 4, 6], g.natural_loops(0)[0].body);
+
+        assert!(generate_base_graph().natural_loops(0).is_empty());
+    }
+
+    #[test]
+    fn reach_index_matches_traversal() {
+        let g = generate_base_graph();
+        let index = ReachIndex::build(&g).unwrap();
+
+        let reachable: [&[NodeIndex]; 6] =
+            [&[0, 1, 2, 3, 4, 5], &[1], &[2], &[3, 5], &[4, 5], &[5]];
+        for (from, reachable_from) in reachable.iter().enumerate() {
+            for to in 0..6 {
+                let expected = reachable_from.contains(&to);
+                assert_eq!(expected, index.can_reach(from, to), "{} -> {}", from, to);
+            }
+        }
+
+        let mut bytes = Vec::new();
+        index.write_to(&mut bytes).unwrap();
+        let loaded = ReachIndex::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(index, loaded);
+        assert!(ReachIndex::read_from(&mut &bytes[..10]).is_err());
+
+        let mut cyclic = generate_base_graph();
+        cyclic.add_edge(Edge { from: 5, to: 0 });
+        assert_eq!(None, ReachIndex::build(&cyclic));
+    }
+
+    #[test]
+    fn coarsen_contracts_heavy_edges() {
+        let g = generate_base_graph();
+        let levels = g.coarsen(10);
+
+        assert!(!levels.is_empty());
+        let first = &levels[0];
+        assert_eq!(6, first.mapping.len());
+        assert_eq!(6, first.node_weights.iter().sum::<usize>());
+        assert!(first.node_weights.len() < 6);
+
+        // every level accounts for all the original nodes, and edges only
+        // disappear when they end up inside a merged node
+        for pair in levels.windows(2) {
+            assert_eq!(pair[0].node_weights.len(), pair[1].mapping.len());
+            assert_eq!(6, pair[1].node_weights.iter().sum::<usize>());
+        }
+        let last = levels.last().unwrap();
+        let edge_count = last.graph.canonical_edge_list().len();
+        let merged: usize = (0..edge_count)
+            .map(|e| *last.graph.edge_weight(e).unwrap())
+            .sum();
+        assert!(merged <= 6);
+        assert_eq!(None, last.graph.edge_weight(edge_count));
+    }
+
+    #[derive(PartialEq, Hash, Debug)]
+    struct City {
+        name: &'static str,
+        population: u32,
+    }
+
+    #[test]
+    fn generic_node_and_edge_data() {
+        let mut g: Graph<City, u32> = Graph::default();
+        let bcn = g.add_node(City {
+            name: "Barcelona",
+            population: 1_600_000,
+        });
+        let mad = g.add_node(City {
+            name: "Madrid",
+            population: 3_300_000,
+        });
+        let vlc = g.add_node(City {
+            name: "Valencia",
+            population: 800_000,
+        });
+
+        let e = g.add_weighted_edge(Edge { from: bcn, to: mad }, 620);
+        g.add_weighted_edge(Edge { from: mad, to: vlc }, 360);
+        assert_eq!(Some(&620), g.edge_weight(e));
+
+        // re-adding an edge replaces its weight
+        assert_eq!(e, g.add_weighted_edge(Edge { from: bcn, to: mad }, 600));
+        assert_eq!(Some(&600), g.edge_weight(e));
+
+        assert_eq!(vec![bcn, mad, vlc], g.shortest_path(bcn, vlc).unwrap());
+        assert_eq!(2, g.bfs_distance(bcn, vlc));
+        assert_eq!(Some(vec![vlc]), g.boundary());
+
+        let removed = g.remove_node(bcn).unwrap();
+        assert_eq!("Barcelona", removed.name);
+        assert_eq!(Some(&360), g.edge_weight(0));
+    }
+
+    #[test]
+    fn graph_attributes() {
+        let mut g = generate_base_graph();
+        assert_eq!(None, g.attr("name"));
+
+        assert_eq!(None, g.set_attr("name", "base"));
+        g.set_attr("created", String::from("2024-01-01"));
+        assert_eq!(Some("base"), g.attr("name"));
+        assert_eq!(Some(String::from("base")), g.set_attr("name", "renamed"));
+
+        let attrs: Vec<(&str, &str)> = g.attrs().collect();
+        assert_eq!(vec![("created", "2024-01-01"), ("name", "renamed")], attrs);
+
+        // attributes survive structural changes
+        g.remove_node(0);
+        g.optimize();
+        assert_eq!(Some("renamed"), g.attr("name"));
+        assert_eq!(Some(String::from("2024-01-01")), g.remove_attr("created"));
+        assert_eq!(1, g.attrs().count());
+    }
+
+    #[test]
+    fn node_validator_rejects_payloads() {
+        let mut g: Graph<(&str, i32)> = Graph::default();
+        g.add_node(("", -1));
+        g.set_node_validator(|(label, level): &(&str, i32)| {
+            if label.is_empty() {
+                return Err(String::from("empty label"));
+            }
+            if !(0..10).contains(level) {
+                return Err(format!("level {} out of range", level));
+            }
+            Ok(())
+        });
+
+        assert_eq!(Ok(1), g.try_add_node(("db", 3)));
+        assert_eq!(
+            Err(InvalidNode {
+                reason: String::from("empty label")
+            }),
+            g.try_add_node(("", 3))
+        );
+        assert_eq!(
+            "invalid node: level 12 out of range",
+            g.replace_node(1, ("db", 12)).unwrap_err().to_string()
+        );
+        assert_eq!(Ok(("db", 3)), g.replace_node(1, ("cache", 4)));
+        assert_eq!(
+            "invalid node: node 7 doesn't exist",
+            g.replace_node(7, ("cache", 4)).unwrap_err().to_string()
+        );
+        assert_eq!(2, g.nodes.len());
+
+        g.clear_node_validator();
+        assert_eq!(Ok(2), g.try_add_node(("", 12)));
+    }
+
+    #[test]
+    #[should_panic(expected = "empty label")]
+    fn add_node_panics_on_invalid_payload() {
+        let mut g: Graph<&str> = Graph::default();
+        g.set_node_validator(|label: &&str| {
+            if label.is_empty() {
+                Err("empty label")
+            } else {
+                Ok(())
+            }
+        });
+        g.add_node("");
+    }
+
+    #[test]
+    fn quotient_groups_nodes() {
+        // services, owned by 2 teams
+        let mut g: Graph<(&str, &str), u32> = Graph::default();
+        let api = g.add_node(("api", "web"));
+        let front = g.add_node(("front", "web"));
+        let db = g.add_node(("db", "data"));
+        let etl = g.add_node(("etl", "data"));
+
+        g.add_weighted_edge(
+            Edge {
+                from: front,
+                to: api,
+            },
+            10,
+        );
+        g.add_weighted_edge(Edge { from: api, to: db }, 5);
+        g.add_weighted_edge(
+            Edge {
+                from: front,
+                to: db,
+            },
+            1,
+        );
+        g.add_weighted_edge(Edge { from: etl, to: db }, 7);
+
+        let by_team = g.quotient(|service| service.1);
+        let web = by_team.find_node_idx("web").unwrap();
+        let data = by_team.find_node_idx("data").unwrap();
+        assert_eq!(vec![web, data], by_team.reachable_nodes_from(web));
+        assert_eq!(vec![data], by_team.reachable_nodes_from(data));
+
+        let calls = g.quotient_with(|service| service.1, |calls| *calls);
+        assert_eq!(Some(&10), calls.edge_weight(0));
+        assert_eq!(Some(&6), calls.edge_weight(1));
+        assert_eq!(Some(&7), calls.edge_weight(2));
+
+        let counts = by_team.canonical_edge_list().len();
+        assert_eq!(3, counts);
+        assert_eq!(Some(&2), by_team.edge_weight(1));
+    }
+
+    #[test]
+    fn rewiring_keeps_degrees() {
+        let mut g = generate_loop_cfg();
+        let degrees = |g: &Graph| -> Vec<(usize, usize)> {
+            (0..6)
+                .map(|n| {
+                    (
+                        g.reachable_nodes_from(n).len(),
+                        g.nodes_that_can_reach(n).len(),
+                    )
+                })
+                .collect()
+        };
+        let before = degrees(&g);
+        let edges_before = g.canonical_edge_list().len();
+
+        let swaps = g.rewire_preserving_degrees(200, 1);
+        assert!(swaps > 0);
+        assert_eq!(before, degrees(&g));
+        assert_eq!(edges_before, g.canonical_edge_list().len());
+        assert!(g.edges.iter().all(|e| e.from != e.to));
+
+        // same seed, same result
+        let mut h = generate_loop_cfg();
+        h.rewire_preserving_degrees(200, 1);
+        assert_eq!(g.edges, h.edges);
+    }
+
+    #[test]
+    fn configuration_model() {
+        let in_degrees = [1, 2, 0, 3, 1, 1];
+        let out_degrees = [2, 1, 3, 0, 1, 1];
+        let g: Graph<NodeIndex> =
+            Graph::from_degree_sequence(&in_degrees, &out_degrees, 3).unwrap();
+
+        for node_idx in 0..6 {
+            assert_eq!(in_degrees[node_idx], g.nodes_that_can_reach(node_idx).len());
+            assert_eq!(
+                out_degrees[node_idx],
+                g.reachable_nodes_from(node_idx).len()
+            );
+        }
+        assert!(g.edges.iter().all(|e| e.from != e.to));
+        assert_eq!(8, g.canonical_edge_list().len());
+
+        let h: Graph<NodeIndex> =
+            Graph::from_degree_sequence(&in_degrees, &out_degrees, 3).unwrap();
+        assert_eq!(g.edges, h.edges);
+
+        let wrong: Option<Graph<NodeIndex>> = Graph::from_degree_sequence(&[1, 1], &[1, 0], 3);
+        assert!(wrong.is_none());
+    }
+
+    #[test]
+    fn spread_simulations() {
+        let g = generate_base_graph();
+
+        let certain = SpreadModel::IndependentCascade { probability: 1.0 };
+        assert_eq!(
+            vec![Some(0), Some(1), Some(1), Some(1), Some(1), Some(2)],
+            g.simulate(certain, &[0], 42)
+        );
+        assert_eq!(3, g.spread(certain, &[3, 1], 42));
+
+        let never = SpreadModel::IndependentCascade { probability: 0.0 };
+        assert_eq!(1, g.spread(never, &[0], 42));
+
+        let sir = SpreadModel::Sir {
+            infection: 0.5,
+            recovery: 0.3,
+            max_steps: 100,
+        };
+        let run = g.simulate(sir, &[0], 7);
+        assert_eq!(run, g.simulate(sir, &[0], 7));
+        assert_eq!(Some(0), run[0]);
+        // node 5 can only get infected after 3 or 4
+        if let Some(t) = run[5] {
+            assert!(t > run[3].unwrap_or(0).min(run[4].unwrap_or(0)));
+        }
+    }
+
+    #[test]
+    fn influence_maximization() {
+        // 2 stars, hub 0 reaches 4 nodes and hub 5 reaches 2
+        let mut g = Graph::new();
+        for i in 0..8 {
+            g.add_node(Node::from(i as isize));
+        }
+        for to in 1..5 {
+            g.add_edge(Edge { from: 0, to });
+        }
+        g.add_edge(Edge { from: 5, to: 6 });
+        g.add_edge(Edge { from: 5, to: 7 });
+
+        let certain = SpreadModel::IndependentCascade { probability: 1.0 };
+        assert_eq!(vec![0], g.greedy_influence_maximization(1, certain, 3));
+        assert_eq!(vec![0, 5], g.greedy_influence_maximization(2, certain, 3));
+        // asking for more seeds than nodes returns all of them
+        assert_eq!(8, g.greedy_influence_maximization(20, certain, 1).len());
+
+        let maybe = SpreadModel::IndependentCascade { probability: 0.5 };
+        let picked = g.greedy_influence_maximization(2, maybe, 200);
+        assert_eq!(picked, g.greedy_influence_maximization(2, maybe, 200));
+        assert_eq!(vec![0, 5], picked);
+
+        // used to underflow: spread estimated on different random outcomes
+        // for every seed set made some gains negative
+        let mut rng = crate::rng::Rng::new(3);
+        let mut g: Graph<usize> = Graph::with_inline_adjacency();
+        for i in 0..12 {
+            g.add_node(i);
+        }
+        for from in 0..12 {
+            for to in 0..12 {
+                if from != to && rng.chance(0.3) {
+                    g.add_edge(Edge { from, to });
+                }
+            }
+        }
+        for samples in 1..10 {
+            let picked = g.greedy_influence_maximization(4, maybe, samples);
+            assert_eq!(4, picked.len());
+        }
+        let sir = SpreadModel::Sir {
+            infection: 0.5,
+            recovery: 0.5,
+            max_steps: 10,
+        };
+        assert_eq!(4, g.greedy_influence_maximization(4, sir, 3).len());
+    }
+
+    #[test]
+    fn dijkstra_weighted_paths() {
+        let mut g: Graph<&str, u32> = Graph::default();
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let c = g.add_node("c");
+        let d = g.add_node("d");
+        let lonely = g.add_node("lonely");
+
+        // the direct edge is more expensive than going around
+        g.add_weighted_edge(Edge { from: a, to: d }, 10);
+        g.add_weighted_edge(Edge { from: a, to: b }, 2);
+        g.add_weighted_edge(Edge { from: b, to: c }, 3);
+        g.add_weighted_edge(Edge { from: c, to: d }, 1);
+        g.add_weighted_edge(Edge { from: a, to: c }, 7);
+
+        assert_eq!(Some((6, vec![a, b, c, d])), g.dijkstra(a, d));
+        assert_eq!(None, g.dijkstra(d, a));
+
+        let all = g.dijkstra_all(a);
+        assert_eq!(
+            vec![Some(0), Some(2), Some(5), Some(6), None],
+            all.distances
+        );
+        assert_eq!(Some(vec![a, b, c]), all.path_to(c));
+        assert_eq!(Some(vec![a]), all.path_to(a));
+        assert_eq!(None, all.path_to(lonely));
+    }
+
+    #[test]
+    fn generic_measures() {
+        use std::num::Saturating;
+        use std::time::Duration;
+
+        let mut g: Graph<&str, f64> = Graph::default();
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let c = g.add_node("c");
+        g.add_weighted_edge(Edge { from: a, to: c }, 1.5);
+        g.add_weighted_edge(Edge { from: a, to: b }, 0.25);
+        g.add_weighted_edge(Edge { from: b, to: c }, 0.5);
+        assert_eq!(Some((0.75, vec![a, b, c])), g.dijkstra(a, c));
+        assert_eq!(Some((0.75, vec![a, b, c])), g.bidirectional_dijkstra(a, c));
+
+        let mut timed: Graph<&str, Duration> = Graph::default();
+        timed.add_node("a");
+        timed.add_node("b");
+        timed.add_weighted_edge(Edge { from: 0, to: 1 }, Duration::from_secs(90));
+        assert_eq!(
+            Some(Duration::from_secs(90)),
+            timed.dijkstra_all(0).distances[1]
+        );
+
+        // (transfers, minutes): one long direct ride beats two quick hops
+        let mut transit: Graph<&str, (u32, u32)> = Graph::default();
+        for stop in ["home", "hub", "work"] {
+            transit.add_node(stop);
+        }
+        transit.add_weighted_edge(Edge { from: 0, to: 2 }, (1, 50));
+        transit.add_weighted_edge(Edge { from: 0, to: 1 }, (1, 10));
+        transit.add_weighted_edge(Edge { from: 1, to: 2 }, (1, 10));
+        assert_eq!(Some(((1, 50), vec![0, 2])), transit.dijkstra(0, 2));
+
+        let mut capped: Graph<&str, Saturating<u8>> = Graph::default();
+        capped.add_node("a");
+        capped.add_node("b");
+        capped.add_node("c");
+        capped.add_weighted_edge(Edge { from: 0, to: 1 }, Saturating(200));
+        capped.add_weighted_edge(Edge { from: 1, to: 2 }, Saturating(200));
+        assert_eq!(
+            Some(Saturating(255)),
+            capped.dijkstra(0, 2).map(|(cost, _)| cost)
+        );
+
+        // astar picks its cost type from the closures
+        let found = g.astar(a, c, |_, w| Duration::from_secs_f64(*w), |_| Duration::ZERO);
+        assert_eq!(Some((Duration::from_millis(750), vec![a, b, c])), found);
+    }
+
+    #[test]
+    fn pareto_front_of_time_and_money() {
+        // edge payloads are (minutes, euros)
+        let mut g: Graph<&str, (u32, u32)> = Graph::default();
+        for stop in ["home", "station", "airport", "port", "office"] {
+            g.add_node(stop);
+        }
+        g.add_weighted_edge(Edge { from: 0, to: 1 }, (10, 2));
+        g.add_weighted_edge(Edge { from: 1, to: 4 }, (50, 3));
+        g.add_weighted_edge(Edge { from: 0, to: 2 }, (20, 10));
+        g.add_weighted_edge(Edge { from: 2, to: 4 }, (10, 90));
+        g.add_weighted_edge(Edge { from: 0, to: 3 }, (30, 1));
+        g.add_weighted_edge(Edge { from: 3, to: 4 }, (200, 1));
+        // slower and pricier than going through the station
+        g.add_weighted_edge(Edge { from: 1, to: 3 }, (40, 4));
+        // a free loop never helps
+        g.add_weighted_edge(Edge { from: 4, to: 1 }, (0, 0));
+
+        let front = g.pareto_paths(0, 4, |_, (minutes, euros)| [*minutes, *euros]);
+        assert_eq!(
+            vec![
+                ([30, 100], vec![0, 2, 4]),
+                ([60, 5], vec![0, 1, 4]),
+                ([230, 2], vec![0, 3, 4]),
+            ],
+            front
+        );
+
+        // a single dimension is plain shortest paths
+        let fastest = g.pareto_paths(0, 4, |_, (minutes, _)| [*minutes]);
+        assert_eq!(vec![([30], vec![0, 2, 4])], fastest);
+        assert_eq!(vec![([0, 0], vec![2])], g.pareto_paths(2, 2, |_, _| [1, 1]));
+        assert!(g.pareto_paths(4, 0, |_, (m, e)| [*m, *e]).is_empty());
+    }
+
+    #[test]
+    fn turn_restrictions_reroute_around_the_block() {
+        // 0 -> 1 -> 2 is short, but turning from 0 -> 1 onto 1 -> 2 is
+        // forbidden, so the way to 2 loops around 1 -> 3 -> 4 -> 1
+        let mut g: Graph<&str, u32> = Graph::default();
+        for label in ["a", "b", "c", "d", "e"] {
+            g.add_node(label);
+        }
+        let into_b = g.add_weighted_edge(Edge { from: 0, to: 1 }, 1);
+        let b_to_c = g.add_weighted_edge(Edge { from: 1, to: 2 }, 1);
+        g.add_weighted_edge(Edge { from: 1, to: 3 }, 1);
+        g.add_weighted_edge(Edge { from: 3, to: 4 }, 1);
+        let back_to_b = g.add_weighted_edge(Edge { from: 4, to: 1 }, 1);
+        g.add_weighted_edge(Edge { from: 0, to: 2 }, 10);
+
+        assert!(g.add_turn_restriction(into_b, b_to_c));
+        assert!(!g.add_turn_restriction(into_b, b_to_c));
+        assert!(!g.is_turn_allowed(into_b, b_to_c));
+
+        // plain dijkstra doesn't know about restrictions
+        assert_eq!(Some((2, vec![0, 1, 2])), g.dijkstra(0, 2));
+        assert_eq!(
+            Some((5, vec![0, 1, 3, 4, 1, 2])),
+            g.dijkstra_with_turn_restrictions(0, 2)
+        );
+
+        g.add_turn_restriction(back_to_b, b_to_c);
+        assert_eq!(
+            Some((10, vec![0, 2])),
+            g.dijkstra_with_turn_restrictions(0, 2)
+        );
+        assert_eq!(Some((0, vec![3])), g.dijkstra_with_turn_restrictions(3, 3));
+        assert_eq!(None, g.dijkstra_with_turn_restrictions(2, 0));
+
+        // restrictions follow their edges and go away with them
+        g.remove_node(4);
+        assert!(g.is_turn_allowed(back_to_b, b_to_c));
+        assert!(!g.is_turn_allowed(into_b, b_to_c));
+        assert!(g.remove_turn_restriction(into_b, b_to_c));
+        assert_eq!(
+            Some((2, vec![0, 1, 2])),
+            g.dijkstra_with_turn_restrictions(0, 2)
+        );
+    }
+
+    #[test]
+    fn undirected_graphs_go_both_ways() {
+        // a tree 0 - 1 - 2, 1 - 3, every edge added once
+        let mut g: Graph<&str> = Graph::with_kind(GraphKind::Undirected);
+        for label in ["root", "mid", "left", "right"] {
+            g.add_node(label);
+        }
+        g.add_edge(Edge { from: 0, to: 1 });
+        g.add_edge(Edge { from: 1, to: 2 });
+        g.add_edge(Edge { from: 3, to: 1 });
+        // same edge, other way around
+        assert_eq!(1, g.add_edge(Edge { from: 2, to: 1 }));
+
+        assert_eq!(vec![0, 2, 3], g.reachable_nodes_from(1));
+        assert_eq!(vec![1], g.nodes_that_can_reach(2));
+        assert_eq!(2, g.bfs_distance(2, 3));
+        assert_eq!(Some(vec![2, 1, 0]), g.shortest_path(2, 0));
+        assert_eq!(Some(vec![0, 2, 3]), g.boundary());
+
+        g.set_kind(GraphKind::Directed);
+        assert_eq!(GraphKind::Directed, g.kind());
+        assert_eq!(None, g.shortest_path(2, 0));
+        assert_eq!(Some(vec![2]), g.boundary());
+
+        // reverse duplicates only count once once undirected
+        g.add_edge(Edge { from: 1, to: 0 });
+        g.set_kind(GraphKind::Undirected);
+        assert_eq!(vec![1], g.reachable_nodes_from(0));
+    }
+
+    #[test]
+    fn weighted_searches_on_undirected_graphs() {
+        // a - b - c - d with a shortcut a - c, every edge stored backwards
+        let mut g: Graph<&str, u32> = Graph::with_kind(GraphKind::Undirected);
+        for label in ["a", "b", "c", "d"] {
+            g.add_node(label);
+        }
+        g.add_weighted_edge(Edge { from: 1, to: 0 }, 2);
+        let c_to_b = g.add_weighted_edge(Edge { from: 2, to: 1 }, 3);
+        g.add_weighted_edge(Edge { from: 2, to: 0 }, 10);
+        g.add_weighted_edge(Edge { from: 3, to: 2 }, 1);
+
+        assert_eq!(Some((6, vec![0, 1, 2, 3])), g.dijkstra(0, 3));
+        assert_eq!(Some((6, vec![3, 2, 1, 0])), g.dijkstra(3, 0));
+        assert_eq!(
+            vec![Some(6), Some(4), Some(1), Some(0)],
+            g.dijkstra_all(3).distances
+        );
+        assert_eq!(Some(6), g.bidirectional_dijkstra(0, 3).map(|(c, _)| c));
+        assert_eq!(
+            Some((6, vec![0, 1, 2, 3])),
+            g.astar(0, 3, |_, w| *w as Cost, |_| 0)
+        );
+        assert_eq!(
+            vec![Some(0), Some(2), Some(5), Some(6)],
+            g.bellman_ford(0).unwrap().distances
+        );
+        assert_eq!(Some(vec![3, 2, 1, 0]), g.floyd_warshall().path(3, 0));
+        let (_, from_d) = g.all_pairs_shortest_paths_sparse().unwrap().last().unwrap();
+        assert_eq!(Some(&6), from_d.get(&0));
+        assert_eq!(
+            vec![([6], vec![3, 2, 1, 0])],
+            g.pareto_paths(3, 0, |_, w| [*w])
+        );
+        assert_eq!(
+            Some((6, vec![3, 2, 1, 0])),
+            g.time_dependent_dijkstra(3, 0, 0, |_, w, _| Some(*w))
+        );
+        g.add_turn_restriction(c_to_b, 0);
+        assert_eq!(
+            Some((11, vec![3, 2, 0])),
+            g.dijkstra_with_turn_restrictions(3, 0)
+        );
+
+        // the walk is proportional to the weighted degrees
+        let stationary = g.stationary_distribution(1e-12, 10_000);
+        for (p, degree) in stationary.iter().zip([12.0, 5.0, 14.0, 1.0]) {
+            assert!((p - degree / 32.0).abs() < 1e-6);
+        }
+        let chain = g.to_markov_chain();
+        assert_eq!(GraphKind::Directed, chain.kind());
+        assert_eq!(8, chain.edge_count());
+
+        assert_eq!(
+            vec![("a", "b"), ("a", "c"), ("b", "c"), ("c", "d")],
+            g.canonical_edge_list()
+                .into_iter()
+                .map(|(from, to)| (*from, *to))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn time_dependent_routing_waits_for_departures() {
+        // stops 0 -> 1 -> 2 by train, or 0 -> 2 directly by bus. Payloads are
+        // (first departure, every, ride), in minutes
+        let mut g: Graph<&str, (u32, u32, u32)> = Graph::default();
+        for stop in ["home", "station", "work"] {
+            g.add_node(stop);
+        }
+        g.add_weighted_edge(Edge { from: 0, to: 1 }, (0, 10, 5));
+        g.add_weighted_edge(Edge { from: 1, to: 2 }, (0, 30, 5));
+        g.add_weighted_edge(Edge { from: 0, to: 2 }, (0, 15, 20));
+
+        let schedule = |_: &Edge, (first, every, ride): &(u32, u32, u32), t: u32| {
+            let wait = if t <= *first {
+                first - t
+            } else {
+                (every - (t - first) % every) % every
+            };
+            // nothing leaves after 100
+            (t + wait <= 100).then_some(wait + ride)
+        };
+
+        // train at 0, at the station at 5, next train at 30: 35. The bus
+        // leaving at 0 gets there at 20
+        assert_eq!(
+            Some((20, vec![0, 2])),
+            g.time_dependent_dijkstra(0, 2, 0, schedule)
+        );
+        // leaving at 16: train at 20, station at 25, train at 30, work at 35;
+        // next bus at 30 arrives at 50
+        assert_eq!(
+            Some((35, vec![0, 1, 2])),
+            g.time_dependent_dijkstra(0, 2, 16, schedule)
+        );
+        assert_eq!(None, g.time_dependent_dijkstra(0, 2, 101, schedule));
+        assert_eq!(
+            Some((7, vec![1])),
+            g.time_dependent_dijkstra(1, 1, 7, schedule)
+        );
+    }
+
+    #[test]
+    fn reachable_within_hops_and_budget() {
+        // 0 -> 1 -> 2 -> 3, plus a slow shortcut 0 -> 3
+        let mut g: Graph<&str, u32> = Graph::default();
+        for label in ["a", "b", "c", "d", "e"] {
+            g.add_node(label);
+        }
+        g.add_weighted_edge(Edge { from: 0, to: 1 }, 10);
+        g.add_weighted_edge(Edge { from: 1, to: 2 }, 10);
+        g.add_weighted_edge(Edge { from: 2, to: 3 }, 10);
+        g.add_weighted_edge(Edge { from: 0, to: 3 }, 25);
+
+        assert_eq!(vec![(0, 0)], g.reachable_within(0, 0));
+        assert_eq!(vec![(0, 0), (1, 1), (3, 1)], g.reachable_within(0, 1));
+        assert_eq!(
+            vec![(0, 0), (1, 1), (2, 2), (3, 1)],
+            g.reachable_within(0, 10)
+        );
+
+        assert_eq!(vec![(0, 0), (1, 10)], g.reachable_within_cost(0, 15));
+        assert_eq!(
+            vec![(0, 0), (1, 10), (2, 20), (3, 25)],
+            g.reachable_within_cost(0, 30)
+        );
+        assert_eq!(vec![(4, 0)], g.reachable_within_cost(4, 100));
+        assert!(g.reachable_within_cost(9, 100).is_empty());
+
+        // the last edge takes the index of the removed one
+        g.remove_edge(0);
+        assert_eq!(vec![(0, 0), (3, 25)], g.reachable_within_cost(0, 30));
+
+        g.set_kind(GraphKind::Undirected);
+        assert_eq!(vec![(0, 1), (2, 1), (3, 0)], g.reachable_within(3, 1));
+        assert_eq!(
+            vec![(0, 25), (1, 20), (2, 10), (3, 0)],
+            g.reachable_within_cost(3, 100)
+        );
+    }
+
+    #[test]
+    fn incremental_scc_matches_tarjan() {
+        let mut g: Graph<isize> = Graph::new();
+        for i in 0..6 {
+            g.add_node(i);
+        }
+        g.add_edge(Edge { from: 0, to: 1 });
+        g.add_edge(Edge { from: 1, to: 2 });
+        g.add_edge(Edge { from: 3, to: 4 });
+
+        let mut scc = IncrementalScc::build(&g);
+        assert_eq!(6, scc.component_count());
+
+        // 2 -> 0 closes 0 -> 1 -> 2
+        assert!(scc.add_edge(Edge { from: 2, to: 0 }));
+        assert!(!scc.add_edge(Edge { from: 1, to: 0 }));
+        assert_eq!(&[0, 1, 2], scc.members(1));
+        // 2 -> 3 -> 4 -> 1 pulls in both 3 and 4, not 5
+        assert!(!scc.add_edge(Edge { from: 2, to: 3 }));
+        assert!(scc.add_edge(Edge { from: 4, to: 1 }));
+        assert!(scc.same_component(0, 4));
+        assert!(!scc.same_component(0, 5));
+        assert_eq!(vec![vec![0, 1, 2, 3, 4], vec![5]], scc.components());
+
+        let node_idx = scc.add_node();
+        assert_eq!(6, node_idx);
+        assert_eq!(3, scc.component_count());
+
+        for (from, to) in [(2, 0), (1, 0), (2, 3), (4, 1)] {
+            g.add_edge(Edge { from, to });
+        }
+        let mut expected = g.strongly_connected_components();
+        for component in expected.iter_mut() {
+            component.sort_unstable();
+        }
+        expected.sort_unstable();
+        assert_eq!(expected, IncrementalScc::build(&g).components());
+    }
+
+    #[test]
+    fn node_lookup_survives_removals() {
+        let mut g: Graph<isize> = Graph::new();
+        for i in 0..10_000 {
+            assert_eq!(i as usize, g.add_node(i));
+        }
+        assert_eq!(42, g.add_node(42));
+        assert_eq!(Some(9_999), g.find_node_idx(9_999));
+
+        // 9_999 is swapped into the hole
+        g.remove_node(3);
+        assert_eq!(None, g.find_node_idx(3));
+        assert_eq!(Some(3), g.find_node_idx(9_999));
+        assert_eq!(Some(9_998), g.find_node_idx(9_998));
+        assert_eq!(9_999, g.add_node(3));
+
+        g.replace_node(5, -5).unwrap();
+        assert_eq!(None, g.find_node_idx(5));
+        assert_eq!(Some(5), g.find_node_idx(-5));
+        assert_eq!(Some(6), g.find_node_idx(6));
+        assert_eq!(5, g.add_node(-5));
+
+        // nodes written without going through `add_node` are found too
+        g.add_edge(Edge { from: 0, to: 1 });
+        let condensed = g.condensation();
+        assert!(condensed.find_node_idx(vec![0]).is_some());
+    }
+
+    #[test]
+    fn duplicate_payloads_are_distinct_nodes() {
+        let mut g: Graph<&str> = Graph::new();
+        let alice = g.add_node("alice");
+        let bob = g.add_node_unchecked("bob");
+        let other_bob = g.add_node_unchecked("bob");
+        assert_ne!(bob, other_bob);
+        assert_eq!(3, g.nodes.len());
+
+        g.add_edge(Edge {
+            from: alice,
+            to: other_bob,
+        });
+        assert_eq!(vec![other_bob], g.reachable_nodes_from(alice));
+        assert!(g.reachable_nodes_from(bob).is_empty());
+
+        // deduplicating methods pick one of them
+        let found = g.find_node_idx("bob").unwrap();
+        assert!(found == bob || found == other_bob);
+        assert_eq!(found, g.add_node("bob"));
+        assert_eq!(3, g.nodes.len());
+
+        g.remove_node(bob);
+        assert_eq!(Some(1), g.find_node_idx("bob"));
+    }
+
+    #[test]
+    fn sampled_triangles_bracket_the_exact_count() {
+        // K5 has 10 triangles, every edge is in exactly 3 of them
+        let mut k5: Graph<isize> = Graph::new();
+        for i in 0..5 {
+            k5.add_node(i);
+        }
+        for from in 0..5 {
+            for to in 0..5 {
+                if from != to {
+                    k5.add_edge(Edge { from, to });
+                }
+            }
+        }
+        assert_eq!(10, k5.triangle_count());
+        let estimate = k5.approximate_triangle_count(10, 1);
+        assert_eq!(
+            (10.0, 10.0, 10.0),
+            (estimate.estimate, estimate.low, estimate.high)
+        );
+
+        // a ring of 30 "fans": every 3 consecutive nodes form a triangle
+        let mut g: Graph<isize> = Graph::new();
+        for i in 0..60 {
+            g.add_node(i);
+        }
+        for i in 0..60 {
+            g.add_edge(Edge {
+                from: i,
+                to: (i + 1) % 60,
+            });
+            if i % 2 == 0 {
+                g.add_edge(Edge {
+                    from: i,
+                    to: (i + 2) % 60,
+                });
+            }
+        }
+        let exact = g.triangle_count();
+        assert_eq!(30, exact);
+        let estimate = g.approximate_triangle_count(2_000, 7);
+        assert!(estimate.low <= exact as f64 && exact as f64 <= estimate.high);
+        assert!(estimate.high - estimate.low < 10.0);
+        assert_eq!(estimate, g.approximate_triangle_count(2_000, 7));
+    }
+
+    #[test]
+    fn terminal_components_generalize_boundary() {
+        // 0 -> {1, 2} cycle -> 3, and 0 -> {4, 5} cycle, 6 alone
+        let mut g: Graph<isize> = Graph::new();
+        for i in 0..7 {
+            g.add_node(i);
+        }
+        for (from, to) in [(0, 1), (1, 2), (2, 1), (2, 3), (0, 4), (4, 5), (5, 4)] {
+            g.add_edge(Edge { from, to });
+        }
+
+        assert_eq!(Some(vec![3, 6]), g.boundary());
+        assert_eq!(vec![vec![3], vec![4, 5], vec![6]], g.terminal_components());
+
+        // on a DAG they're the boundary
+        let mut dag: Graph<isize> = Graph::new();
+        for i in 0..4 {
+            dag.add_node(i);
+        }
+        for (from, to) in [(0, 1), (0, 2), (2, 3)] {
+            dag.add_edge(Edge { from, to });
+        }
+        let boundary: Vec<Vec<NodeIndex>> = dag
+            .boundary()
+            .unwrap()
+            .into_iter()
+            .map(|n| vec![n])
+            .collect();
+        assert_eq!(boundary, dag.terminal_components());
+    }
+
+    #[test]
+    fn removing_edges_can_be_undone() {
+        let mut g: Graph<&str, u32> = Graph::default();
+        for label in ["a", "b", "c", "d"] {
+            g.add_node(label);
+        }
+        g.add_weighted_edge(Edge { from: 0, to: 1 }, 1);
+        g.add_weighted_edge(Edge { from: 1, to: 2 }, 2);
+        let c_to_d = g.add_weighted_edge(Edge { from: 2, to: 3 }, 3);
+        g.add_weighted_edge(Edge { from: 3, to: 0 }, 4);
+        g.add_turn_restriction(c_to_d, 3);
+
+        // 3 -> 0 takes the index of 0 -> 1
+        assert_eq!(Some((Edge { from: 0, to: 1 }, 1)), g.remove_edge(0));
+        assert_eq!(Some(&4), g.edge_weight(0));
+        assert!(g.reachable_nodes_from(0).is_empty());
+        assert!(g.nodes_that_can_reach(1).is_empty());
+        assert!(!g.is_turn_allowed(c_to_d, 0));
+        assert_eq!(None, g.remove_edge(3));
+
+        assert_eq!(None, g.remove_edge_between(2, 1));
+        assert_eq!(
+            Some((Edge { from: 1, to: 2 }, 2)),
+            g.remove_edge_between(1, 2)
+        );
+        g.add_weighted_edge(Edge { from: 1, to: 2 }, 2);
+        assert_eq!(Some((2, vec![1, 2])), g.dijkstra(1, 2));
+
+        let removed = g.disconnect(3);
+        assert_eq!(
+            vec![(Edge { from: 3, to: 0 }, 4), (Edge { from: 2, to: 3 }, 3)],
+            removed
+        );
+        assert_eq!(4, g.nodes.len());
+        assert_eq!(vec![Edge { from: 1, to: 2 }], g.edges);
+        assert!(g.reachable_nodes_from(2).is_empty());
+        for (edge, weight) in removed {
+            g.add_weighted_edge(edge, weight);
+        }
+        assert_eq!(Some((7, vec![2, 3, 0])), g.dijkstra(2, 0));
+
+        // undirected: the edge is removed whichever way it's named
+        let mut u: Graph<&str> = Graph::with_kind(GraphKind::Undirected);
+        u.add_node("x");
+        u.add_node("y");
+        u.add_edge(Edge { from: 0, to: 1 });
+        assert!(u.remove_edge_between(1, 0).is_some());
+        assert!(u.reachable_nodes_from(1).is_empty());
+    }
+
+    #[test]
+    fn traversal_trees() {
+        // 0 -> 1 -> 3, 0 -> 2 -> 3, 3 -> 0, 4 unreachable
+        let mut g: Graph<&str> = Graph::new();
+        for label in ["a", "b", "c", "d", "e"] {
+            g.add_node(label);
+        }
+        for (from, to) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 0)] {
+            g.add_edge(Edge { from, to });
+        }
+
+        let bfs = g.bfs_tree(0);
+        assert_eq!(vec![0, 1, 2, 3], bfs.nodes);
+        assert_eq!(
+            vec![
+                Edge { from: 0, to: 1 },
+                Edge { from: 0, to: 2 },
+                Edge { from: 1, to: 3 }
+            ],
+            bfs.edges
+        );
+
+        let dfs = g.dfs_tree(0);
+        assert_eq!(vec![0, 1, 3, 2], dfs.nodes);
+        assert_eq!(
+            vec![
+                Edge { from: 0, to: 1 },
+                Edge { from: 1, to: 2 },
+                Edge { from: 0, to: 3 }
+            ],
+            dfs.edges
+        );
+        assert_eq!(Some(vec![2, 3]), dfs.leaves());
+        // tree node 2 is node 3 of the graph
+        assert_eq!(Some(vec![0, 1, 2]), dfs.shortest_path(0, 2));
+
+        assert_eq!(vec![4], g.bfs_tree(4).nodes);
+        assert!(g.dfs_tree(9).nodes.is_empty());
+    }
+
+    #[test]
+    fn stable_keys_survive_removals() {
+        let mut g: StableGraph<&str, u32> = StableGraph::new();
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let c = g.add_node("c");
+        let d = g.add_node("d");
+        assert_eq!(b, g.add_node("b"));
+        g.add_weighted_edge(a, d, 1).unwrap();
+        g.add_weighted_edge(d, c, 2).unwrap();
+
+        // "d" is swapped into the index of "b", its key doesn't care
+        assert_eq!(Ok("b"), g.remove_node(b));
+        assert_eq!(Ok(&"d"), g.node(d));
+        assert_eq!(Ok(vec![c]), g.reachable_nodes_from(d));
+        assert_eq!(Err(StaleKey { key: b }), g.node(b));
+        assert!(g.add_edge(b, a).is_err());
+
+        // the slot of "b" is reused, but the old key stays stale
+        let e = g.add_node("e");
+        assert_ne!(b, e);
+        assert!(!g.contains(b));
+        assert_eq!(Ok(&"e"), g.node(e));
+
+        // algorithms run on the dense graph
+        let (cost, path) = g
+            .graph()
+            .dijkstra(g.index(a).unwrap(), g.index(c).unwrap())
+            .unwrap();
+        let path: Vec<NodeKey> = path.into_iter().map(|idx| g.key(idx)).collect();
+        assert_eq!((3, vec![a, d, c]), (cost, path));
+
+        assert_eq!(
+            Ok(Some((
+                Edge {
+                    from: g.index(a).unwrap(),
+                    to: g.index(d).unwrap()
+                },
+                1
+            ))),
+            g.remove_edge_between(a, d)
+        );
+        g.remove_node(a).unwrap();
+        assert_eq!(Ok(&"c"), g.node(c));
+        assert_eq!(Ok(&"e"), g.node(e));
+        assert_eq!(3, g.graph().nodes.len());
+    }
+
+    #[test]
+    fn read_api_iterators() {
+        let mut g: Graph<&str, u32> = Graph::default();
+        for label in ["a", "b", "c"] {
+            g.add_node(label);
+        }
+        g.add_weighted_edge(Edge { from: 0, to: 1 }, 5);
+        g.add_weighted_edge(Edge { from: 0, to: 2 }, 7);
+
+        assert_eq!((3, 2), (g.node_count(), g.edge_count()));
+        assert_eq!(
+            vec![(0, &"a"), (1, &"b"), (2, &"c")],
+            g.nodes().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, &Edge { from: 0, to: 1 }), (1, &Edge { from: 0, to: 2 })],
+            g.edges().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![1, 2], g.neighbors(0).collect::<Vec<_>>());
+        assert_eq!(0, g.neighbors(1).count());
+        assert_eq!(0, g.neighbors(9).count());
+        assert_eq!(Some(&"b"), g.node(1));
+        assert_eq!(Some(&Edge { from: 0, to: 2 }), g.edge(1));
+        assert_eq!(None, g.edge(2));
+    }
+
+    #[test]
+    fn linear_chains_collapse_into_edges() {
+        // 0 -> 1 -> 2 -> 3 -> 4, 0 -> 4, plus a separate ring 5 -> 6 -> 7 -> 5
+        // and a dead end 4 -> 8
+        let mut g: Graph<isize, u32> = Graph::default();
+        for i in 0..9 {
+            g.add_node(i);
+        }
+        for (from, to, w) in [
+            (0, 1, 1),
+            (1, 2, 2),
+            (2, 3, 3),
+            (3, 4, 4),
+            (0, 4, 20),
+            (5, 6, 1),
+            (6, 7, 1),
+            (7, 5, 1),
+            (4, 8, 1),
+        ] {
+            g.add_weighted_edge(Edge { from, to }, w);
+        }
+
+        let collapsed = g.collapse_linear_chains();
+        assert_eq!(vec![0, 4, 5, 8], collapsed.nodes);
+        assert_eq!(4, collapsed.edges.len());
+
+        let chain = collapsed.edge_weight(0).unwrap();
+        assert_eq!(Edge { from: 0, to: 1 }, collapsed.edges[0]);
+        assert_eq!(vec![1, 2, 3], chain.inner);
+        assert_eq!(vec![1, 2, 3, 4], chain.weights);
+        assert_eq!(10, chain.cost());
+        assert!(collapsed.edge_weight(1).unwrap().inner.is_empty());
+
+        // the ring becomes a self-loop on 5
+        assert_eq!(Edge { from: 2, to: 2 }, collapsed.edges[3]);
+        assert_eq!(vec![6, 7], collapsed.edge_weight(3).unwrap().inner);
+        // 8 is now node 3
+        assert_eq!(Some(vec![3]), collapsed.boundary());
+    }
+
+    #[test]
+    fn markov_chain_of_a_user_journey() {
+        // home -> {search 3, cart 1}, search -> {home 1, cart 1}, cart -> home
+        let mut g: Graph<&str, u32> = Graph::default();
+        for page in ["home", "search", "cart", "checkout"] {
+            g.add_node(page);
+        }
+        g.add_weighted_edge(Edge { from: 0, to: 1 }, 3);
+        g.add_weighted_edge(Edge { from: 0, to: 2 }, 1);
+        g.add_weighted_edge(Edge { from: 1, to: 0 }, 1);
+        g.add_weighted_edge(Edge { from: 1, to: 2 }, 1);
+        g.add_weighted_edge(Edge { from: 2, to: 0 }, 5);
+
+        let chain = g.to_markov_chain();
+        assert_eq!(vec![0.75, 0.25, 0.5, 0.5, 1.0, 1.0], chain.edge_weights);
+        // checkout is a dead end, it absorbs the walk
+        assert_eq!(Edge { from: 3, to: 3 }, chain.edges[5]);
+
+        // without checkout: pi = (8, 6, 5) / 19
+        g.remove_node(3);
+        let pi = g.stationary_distribution(1e-12, 10_000);
+        for (p, expected) in pi.iter().zip([8.0 / 19.0, 6.0 / 19.0, 5.0 / 19.0]) {
+            assert!((p - expected).abs() < 1e-9, "{:?}", pi);
+        }
+        assert_eq!(
+            pi,
+            g.to_markov_chain().stationary_distribution(1e-12, 10_000)
+        );
+
+        // a plain 2-cycle is periodic, the lazy walk still converges
+        let mut cycle: Graph<&str, f64> = Graph::default();
+        cycle.add_node("a");
+        cycle.add_node("b");
+        cycle.add_weighted_edge(Edge { from: 0, to: 1 }, 1.0);
+        cycle.add_weighted_edge(Edge { from: 1, to: 0 }, 1.0);
+        assert_eq!(vec![0.5, 0.5], cycle.stationary_distribution(1e-12, 100));
+    }
+
+    #[test]
+    fn absorbing_funnel() {
+        // landing -> {product 1, bounce 1}, product -> {buy 1, landing 1},
+        // and a loop 5 <-> 6 nothing gets out of
+        let mut g: Graph<&str, u32> = Graph::default();
+        for page in ["landing", "product", "buy", "bounce", "entry", "a", "b"] {
+            g.add_node(page);
+        }
+        for (from, to) in [
+            (0, 1),
+            (0, 3),
+            (1, 2),
+            (1, 0),
+            (4, 0),
+            (4, 5),
+            (5, 6),
+            (6, 5),
+        ] {
+            g.add_weighted_edge(Edge { from, to }, 1);
+        }
+
+        let absorption = g.absorption();
+        assert_eq!(vec![2, 3], absorption.absorbing);
+
+        // from landing: buy with 1/3, bounce with 2/3, in 2 steps on average
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        let landing = &absorption.probabilities[0];
+        assert!(close(landing[0], 1.0 / 3.0) && close(landing[1], 2.0 / 3.0));
+        assert!(close(absorption.expected_steps[0].unwrap(), 2.0));
+        assert!(close(absorption.probabilities[1][0], 2.0 / 3.0));
+        assert_eq!(Some(0.0), absorption.expected_steps[2]);
+        assert_eq!(vec![1.0, 0.0], absorption.probabilities[2]);
+
+        // half the walks from entry get stuck in the loop
+        assert!(close(absorption.probabilities[4][0], 1.0 / 6.0));
+        assert_eq!(None, absorption.expected_steps[4]);
+        assert_eq!(None, absorption.expected_steps[5]);
+        assert_eq!(vec![0.0, 0.0], absorption.probabilities[5]);
+
+        // dead ends become self-loops in the chain, still absorbing there
+        let mut g: Graph<&str, f64> = Graph::default();
+        for page in ["start", "buy", "bounce"] {
+            g.add_node(page);
+        }
+        g.add_weighted_edge(Edge { from: 0, to: 1 }, 1.0);
+        g.add_weighted_edge(Edge { from: 0, to: 2 }, 3.0);
+        let direct = g.absorption();
+        assert_eq!(vec![1, 2], direct.absorbing);
+        assert_eq!(direct, g.to_markov_chain().absorption());
+        assert_eq!(vec![0.25, 0.75], direct.probabilities[0]);
+    }
+
+    #[test]
+    fn lazy_bfs_sweeps_without_revisiting() {
+        // 0 -> {1, 2}, 1 -> 3, and a separate 4 -> 5 -> 1
+        let mut g: Graph<isize> = Graph::new();
+        for i in 0..6 {
+            g.add_node(i);
+        }
+        for (from, to) in [(0, 1), (0, 2), (1, 3), (4, 5), (5, 1)] {
+            g.add_edge(Edge { from, to });
+        }
+
+        let mut bfs = g.bfs(0);
+        assert_eq!(Some(0), bfs.next());
+        assert!(bfs.is_visited(2));
+        assert!(!bfs.is_visited(3));
+        assert_eq!(vec![1, 2, 3], bfs.by_ref().collect::<Vec<_>>());
+
+        // 1 and 3 were already visited
+        bfs.move_to(4);
+        assert_eq!(vec![4, 5], bfs.by_ref().collect::<Vec<_>>());
+        bfs.move_to(3);
+        assert_eq!(None, bfs.next());
+
+        bfs.reset();
+        assert_eq!(None, bfs.next());
+        bfs.move_to(4);
+        assert_eq!(vec![4, 5, 1, 3], bfs.collect::<Vec<_>>());
+        assert_eq!(0, g.bfs(9).count());
+    }
+
+    #[test]
+    fn random_walk_corpus() {
+        // 0 -> 1 -> 2 -> 0, 3 -> 0
+        let mut g: Graph<isize> = Graph::new();
+        for i in 0..4 {
+            g.add_node(i);
+        }
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 0)] {
+            g.add_edge(Edge { from, to });
+        }
+
+        let mut corpus = Vec::new();
+        g.write_random_walks(&mut corpus, 2, 4, 1).unwrap();
+        let corpus = String::from_utf8(corpus).unwrap();
+        assert_eq!("0 1 2 0\n1 2 0 1\n2 0 1 2\n3 0 1 2\n".repeat(2), corpus);
+
+        // a dead end stops the walk
+        g.add_node(4);
+        g.add_edge(Edge { from: 4, to: 3 });
+        g.remove_edge_between(3, 0);
+        let mut corpus = Vec::new();
+        g.write_random_walks(&mut corpus, 1, 10, 1).unwrap();
+        assert!(String::from_utf8(corpus).unwrap().ends_with("\n3\n4 3\n"));
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn spectral_embedding_separates_clusters() {
+        // two triangles joined by a single edge 2 - 3
+        let mut g: Graph<isize> = Graph::new();
+        for i in 0..6 {
+            g.add_node(i);
+        }
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+            g.add_edge(Edge { from, to });
+        }
+
+        let embedding = g.spectral_embedding(2);
+        assert_eq!(6, embedding.len());
+        assert!(embedding.iter().all(|coordinates| coordinates.len() == 2));
+        // the first coordinate (Fiedler vector) splits the triangles
+        let side: Vec<bool> = embedding.iter().map(|c| c[0] > 0.0).collect();
+        assert!(side[0] == side[1] && side[1] == side[2]);
+        assert!(side[3] == side[4] && side[4] == side[5]);
+        assert_ne!(side[0], side[3]);
+        assert_eq!(embedding, g.spectral_embedding(2));
+        assert_eq!(5, g.spectral_embedding(10)[0].len());
+    }
+
+    #[test]
+    fn dfs_visitor_classifies_edges() {
+        // 0 -> 1 -> 2 -> 0 (back), 0 -> 2 (forward), 3 -> 1 (cross)
+        let mut g: Graph<isize> = Graph::new();
+        for i in 0..4 {
+            g.add_node(i);
+        }
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (0, 2), (3, 1)] {
+            g.add_edge(Edge { from, to });
+        }
+
+        let mut events = Vec::new();
+        let result: Option<()> = g.depth_first_search(0..4, |event| {
+            events.push(event);
+            Control::Continue
+        });
+        assert_eq!(None, result);
+        assert_eq!(
+            vec![
+                DfsEvent::Discover(0),
+                DfsEvent::TreeEdge(0, 1),
+                DfsEvent::Discover(1),
+                DfsEvent::TreeEdge(1, 2),
+                DfsEvent::Discover(2),
+                DfsEvent::BackEdge(2, 0),
+                DfsEvent::Finish(2),
+                DfsEvent::Finish(1),
+                DfsEvent::CrossForwardEdge(0, 2),
+                DfsEvent::Finish(0),
+                DfsEvent::Discover(3),
+                DfsEvent::CrossForwardEdge(3, 1),
+                DfsEvent::Finish(3),
+            ],
+            events
+        );
+
+        // stop at the first back edge
+        let back_edge = g.depth_first_search([0], |event| match event {
+            DfsEvent::BackEdge(from, to) => Control::Break((from, to)),
+            _ => Control::Continue,
+        });
+        assert_eq!(Some((2, 0)), back_edge);
+
+        // pruning 1 keeps 2 for the forward edge 0 -> 2
+        let mut discovered = Vec::new();
+        let result: Option<()> = g.depth_first_search([0], |event| match event {
+            DfsEvent::Discover(1) => Control::Prune,
+            DfsEvent::Discover(n) => {
+                discovered.push(n);
+                Control::Continue
+            }
+            _ => Control::Continue,
+        });
+        assert_eq!(None, result);
+        assert_eq!(vec![0, 2], discovered);
+    }
+
+    #[test]
+    fn parallel_edges_are_bundled() {
+        // two chains 0 -> {1, 2} -> 3 collapse into parallel 0 -> 3 edges
+        let mut g: Graph<isize, u32> = Graph::default();
+        for i in 0..5 {
+            g.add_node(i);
+        }
+        for (from, to, w) in [(0, 1, 1), (1, 3, 2), (0, 2, 3), (2, 3, 4), (3, 4, 5)] {
+            g.add_weighted_edge(Edge { from, to }, w);
+        }
+        let collapsed = g.collapse_linear_chains();
+        assert_eq!(3, collapsed.edges.len());
+
+        let bundled = collapsed.aggregate_parallel_edges();
+        assert_eq!(
+            vec![Edge { from: 0, to: 1 }, Edge { from: 1, to: 2 }],
+            bundled.edges
+        );
+        let bundle = bundled.edge_weight(0).unwrap();
+        assert_eq!(2, bundle.count());
+        assert_eq!(
+            vec![3, 7],
+            bundle.weights.iter().map(|c| c.cost()).collect::<Vec<_>>()
+        );
+        assert_eq!(1, bundled.edge_weight(1).unwrap().count());
+
+        // undirected: a -> b and b -> a are the same edge
+        let mut u: Graph<&str, u32> = Graph::default();
+        u.add_node("a");
+        u.add_node("b");
+        u.add_weighted_edge(Edge { from: 0, to: 1 }, 2);
+        u.add_weighted_edge(Edge { from: 1, to: 0 }, 3);
+        assert_eq!(2, u.aggregate_parallel_edges().edges.len());
+        u.set_kind(GraphKind::Undirected);
+        let bundled = u.aggregate_parallel_edges();
+        assert_eq!(GraphKind::Undirected, bundled.kind());
+        assert_eq!(1, bundled.edges.len());
+        assert_eq!(5, bundled.edge_weight(0).unwrap().total());
+    }
+
+    #[test]
+    fn nodes_can_be_indexed() {
+        let mut g: Graph<String> = Graph::default();
+        let a = g.add_node("a".to_string());
+        let b = g.add_node("b".to_string());
+        assert_eq!("b", g[b]);
+
+        g[a].push('!');
+        assert_eq!("a!", g[a]);
+        // the dedup index sees the new payload
+        assert_eq!(a, g.add_node("a!".to_string()));
+        assert_eq!(2, g.add_node("a".to_string()));
+
+        g.add_edge(Edge { from: a, to: b });
+        g.get_node_mut(b).unwrap().make_ascii_uppercase();
+        assert_eq!(Some(&"B".to_string()), g.get_node(b));
+        assert_eq!(vec![b], g.reachable_nodes_from(a));
+        assert_eq!(None, g.get_node_mut(9));
+
+        // only the edited node leaves the dedup index, and it follows the
+        // node around when removals move it
+        let mut g: Graph<usize> = Graph::default();
+        for i in 0..1000 {
+            g.add_node(i);
+        }
+        g[10] = 5000;
+        g[999] = 6000;
+        assert_eq!(10, g.add_node(5000));
+        assert_eq!(500, g.add_node(500));
+        g.remove_node(20);
+        assert_eq!(20, g.add_node(6000));
+        g[20] = 7000;
+        g.remove_node(20);
+        assert_eq!(998, g.add_node(7000));
+        // the last node took the place of the removed one
+        assert_eq!(20, g.add_node(998));
+        assert_eq!(999, g.node_count());
+        g[998] = 8000;
+        g.remove_node(0);
+        assert_eq!(0, g.add_node(8000));
+    }
+
+    #[test]
+    #[should_panic(expected = "node 7 doesn't exist")]
+    fn indexing_a_missing_node_panics() {
+        let mut g: Graph<isize> = Graph::default();
+        g.add_node(1);
+        let _ = g[7];
+    }
+
+    #[test]
+    fn degrees() {
+        let mut g = generate_base_graph();
+        assert_eq!(4, g.out_degree(0));
+        assert_eq!(0, g.in_degree(0));
+        assert_eq!(2, g.in_degree(5));
+        assert_eq!(2, g.degree(3));
+        assert_eq!(0, g.degree(99));
+
+        g.set_kind(GraphKind::Undirected);
+        assert_eq!(2, g.out_degree(5));
+        assert_eq!(2, g.in_degree(5));
+        assert_eq!(2, g.degree(5));
+    }
+
+    #[test]
+    fn limits_stop_untrusted_growth() {
+        let mut g: Graph<isize> = Graph::new();
+        g.set_limits(Limits {
+            max_nodes: Some(4),
+            max_edges: Some(3),
+            max_frontier: Some(1),
+        });
+        for i in 0..4 {
+            g.add_node(i);
+        }
+        assert_eq!(
+            "invalid node: limit exceeded: more than 4 nodes",
+            g.try_add_node(4).unwrap_err().to_string()
+        );
+        // existing payloads are still found
+        assert_eq!(Ok(1), g.try_add_node(1));
+
+        g.add_edge(Edge { from: 0, to: 1 });
+        g.add_edge(Edge { from: 0, to: 2 });
+        g.add_edge(Edge { from: 2, to: 3 });
+        assert_eq!(
+            Err(LimitExceeded {
+                limit: Limit::Edges,
+                max: 3,
+            }),
+            g.try_add_edge(Edge { from: 1, to: 3 })
+        );
+        assert_eq!(Ok(0), g.try_add_edge(Edge { from: 0, to: 1 }));
+
+        // 0 queues both 1 and 2
+        assert_eq!(
+            Limit::Frontier,
+            g.try_shortest_path(0, 3).unwrap_err().limit
+        );
+        assert_eq!(Ok(Some(vec![2, 3])), g.try_shortest_path(2, 3));
+        assert_eq!(Some(vec![0, 2, 3]), g.shortest_path(0, 3));
+    }
+
+    #[test]
+    fn blob_store_shares_identical_payloads() {
+        #[derive(PartialEq, Hash)]
+        struct Artifact {
+            step: &'static str,
+            output: SharedBlob,
+        }
+
+        let mut store = BlobStore::new();
+        let mut g: Graph<Artifact> = Graph::new();
+        let binary = vec![7u8; 1024];
+        for step in ["build", "test", "package"] {
+            g.add_node(Artifact {
+                step,
+                output: store.intern(&binary),
+            });
+        }
+        let log = store.intern(b"ok");
+
+        assert_eq!(2, store.len());
+        assert_eq!(1026, store.stored_bytes());
+        assert_eq!(3, store.references(&binary));
+        assert_eq!(&binary[..], &*g[1].output);
+
+        assert!(!store.release(g.remove_node(0).unwrap().output));
+        assert_eq!(2, store.references(&binary));
+        // same bytes, different store: doesn't free ours
+        let mut other = BlobStore::new();
+        assert!(!store.release(other.intern(b"ok")));
+        assert!(store.contains(b"ok"));
+        assert!(store.release(log));
+        assert!(!store.contains(b"ok"));
+
+        drop(g);
+        assert_eq!(1, store.purge());
+        assert!(store.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_keeps_indexes() {
+        let mut g = generate_base_graph();
+        g.add_node(Node::from(vec![0u8, 1, 2]));
+        g.add_node(Node::from(42));
+        g.set_attr("name", "base");
+        g.add_turn_restriction(2, 4);
+
+        let json = serde_json::to_string(&g).unwrap();
+        let loaded: Graph = serde_json::from_str(&json).unwrap();
+        assert_eq!(g.nodes, loaded.nodes);
+        assert_eq!(g.edges, loaded.edges);
+        assert_eq!(g.outgoing, loaded.outgoing);
+        assert_eq!(Some("base"), loaded.attr("name"));
+        assert!(!loaded.is_turn_allowed(2, 4));
+        assert_eq!(Some(7), loaded.find_node_idx(Node::from(42)));
+
+        let mut weighted: Graph<&str, u32> = Graph::with_kind(GraphKind::Undirected);
+        weighted.add_node("a");
+        weighted.add_node("b");
+        weighted.add_weighted_edge(Edge { from: 1, to: 0 }, 5);
+        let json = serde_json::to_string(&weighted).unwrap();
+        let loaded: Graph<String, u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(GraphKind::Undirected, loaded.kind());
+        assert_eq!(Some(&5), loaded.edge_weight(0));
+        assert_eq!(vec![1], loaded.reachable_nodes_from(0));
+
+        let broken = json.replace("\"to\":0", "\"to\":9");
+        let err = serde_json::from_str::<Graph<String, u32>>(&broken)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("doesn't exist"));
+    }
+
+    #[test]
+    fn feedback_arc_set_breaks_every_cycle() {
+        let g = generate_base_graph();
+        assert_eq!(Vec::<EdgeIndex>::new(), g.feedback_arc_set());
+        assert_eq!(0.0, g.dagness());
+
+        // 0 -> 1 -> 2 -> 0, 2 -> 3 and a self-loop on 3
+        let mut g: Graph<isize> = Graph::new();
+        for i in 0..4 {
+            g.add_node(i);
+        }
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)] {
+            g.add_edge(Edge { from, to });
+        }
+        let fas = g.feedback_arc_set();
+        assert_eq!(2, fas.len());
+        assert!(fas.contains(&4));
+        assert_eq!(0.4, g.dagness());
+
+        for edge_idx in fas.into_iter().rev() {
+            g.remove_edge(edge_idx);
+        }
+        assert!(g.topological_sort().is_ok());
+    }
+
+    #[test]
+    fn dot_export() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::from("say \"hi\""));
+        let b = g.add_node(Node::from(7));
+        let c = g.add_node(Node::from(vec![1u8, 2, 3]));
+        g.add_edge(Edge { from: a, to: b });
+        g.add_edge(Edge { from: b, to: c });
+        g.set_attr("name", "demo");
+        assert_eq!(
+            "digraph {\n    \"name\"=\"demo\";\n    0 [label=\"say \\\"hi\\\"\"];\n    1 [label=\"7\"];\n    2 [label=\"<3 bytes>\"];\n    0 -> 1;\n    1 -> 2;\n}\n",
+            g.to_dot()
+        );
+
+        // parallel edges, styled
+        let collapsed = generate_base_graph().collapse_linear_chains();
+        let mut out = Vec::new();
+        collapsed
+            .write_dot_with(
+                &mut out,
+                &DotOptions {
+                    node_attrs: Some(&|_, node| {
+                        if *node == 0 {
+                            "shape=box".to_string()
+                        } else {
+                            String::new()
+                        }
+                    }),
+                    edge_attrs: Some(&|_, chain| format!("penwidth={}", chain.weights.len())),
+                    bundle_parallel_edges: true,
+                },
+            )
+            .unwrap();
+        let dot = String::from_utf8(out).unwrap();
+        assert!(dot.contains("    0 [label=\"0\", shape=box];\n"));
+        assert!(dot.contains("    0 -> 3 [label=\"x2\", penwidth=2];\n"));
+        assert!(dot.contains("    0 -> 1 [penwidth=1];\n"));
+    }
+
+    #[test]
+    fn dot_import() {
+        let g = Graph::from_dot(
+            r#"
+            // build graph
+            strict digraph deps {
+                rankdir=LR;
+                node [shape=box];
+                app [label="the app"];
+                app -> lib -> core [color=red];
+                app -> core
+                /* same label,
+                   different node */
+                other [label="the app"]
+                "quoted \"id\"" -> core;
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(Some("LR"), g.attr("rankdir"));
+        assert_eq!(
+            vec!["the app", "lib", "core", "the app", "quoted \"id\""],
+            g.nodes.iter().map(|n| n.to_string()).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![1, 2], g.reachable_nodes_from(0));
+        assert_eq!(vec![2], g.reachable_nodes_from(4));
+
+        // what `to_dot` writes reads back the same
+        let mut base = generate_base_graph();
+        base.set_attr("name", "base");
+        let loaded = Graph::from_dot(&base.to_dot()).unwrap();
+        assert_eq!(base.to_dot(), loaded.to_dot());
+
+        let undirected = Graph::from_dot("graph { a -- b }").unwrap();
+        assert_eq!(GraphKind::Undirected, undirected.kind());
+        assert_eq!(vec![0], undirected.reachable_nodes_from(1));
+
+        assert_eq!(
+            "DOT line 2: edges in this graph are written `--`",
+            Graph::from_dot("graph {\n a -> b }")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "DOT line 3: subgraphs aren't supported",
+            Graph::from_dot("digraph {\n a\n subgraph x { b } }")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn cytoscape_round_trip() {
+        let mut g = generate_base_graph();
+        g.set_attr("name", "base");
+        let json = g.to_cytoscape_json();
+        let loaded = Graph::from_cytoscape_json(&json).unwrap();
+        assert_eq!(g.nodes, loaded.nodes);
+        assert_eq!(g.edges, loaded.edges);
+        assert_eq!(Some("base"), loaded.attr("name"));
+
+        let mut weighted: Graph<&str, u32> = Graph::default();
+        weighted.add_node("a");
+        weighted.add_node("b");
+        weighted.add_weighted_edge(Edge { from: 0, to: 1 }, 5);
+        assert!(weighted
+            .to_cytoscape_json()
+            .contains(r#"{"data":{"id":"e0","source":"0","target":"1","weight":5}}"#));
+
+        // a flat array of elements, with string ids
+        let flat = Graph::from_cytoscape_json(
+            r#"[
+                {"data": {"id": "a", "label": "Alice"}},
+                {"data": {"id": "b"}},
+                {"data": {"id": "ab", "source": "a", "target": "b"}}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(Node::from("Alice"), flat.nodes[0]);
+        assert_eq!(Node::from("b"), flat.nodes[1]);
+        assert_eq!(vec![1], flat.reachable_nodes_from(0));
+
+        assert_eq!(
+            "invalid Cytoscape.js JSON: edge to an unknown node \"c\"",
+            Graph::from_cytoscape_json(r#"[{"data": {"id": "e", "source": "c", "target": "c"}}]"#)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn slice_keeps_nodes_between_sources_and_sinks() {
+        let g = generate_base_graph();
+        let sliced = g.slice(&[0], &[5]);
+        assert_eq!(vec![0, 3, 4, 5], sliced.nodes);
+        assert_eq!(4, sliced.edges.len());
+        assert_eq!(vec![3], sliced.reachable_nodes_from(1));
+
+        let sliced = g.slice(&[3, 4], &[5, 1]);
+        assert_eq!(vec![3, 4, 5], sliced.nodes);
+        assert!(g.slice(&[5], &[0]).nodes.is_empty());
+        assert_eq!(vec![2], g.slice(&[2, 99], &[2]).nodes);
+    }
+
+    #[test]
+    fn graphml_round_trip() {
+        let mut g = generate_base_graph();
+        g.add_node(Node::from(-3));
+        g.add_node(Node::from(vec![0u8, 255]));
+        g.add_node(Node::from("<a & b>"));
+        g.set_attr("name", "base");
+
+        let mut out = Vec::new();
+        g.write_graphml(&mut out).unwrap();
+        let loaded: Graph = Graph::read_graphml(&String::from_utf8(out).unwrap()).unwrap();
+        assert_eq!(g.nodes, loaded.nodes);
+        assert_eq!(g.edges, loaded.edges);
+        assert_eq!(Some("base"), loaded.attr("name"));
+
+        // what other tools write: other keys, several values, comments
+        let loaded: Graph<Node, Node> = Graph::read_graphml(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- exported by some editor -->
+            <graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:y="http://www.yworks.com/xml/graphml">
+              <key id="d0" for="node" attr.name="label" attr.type="string"/>
+              <key id="d1" for="node" attr.name="size" attr.type="double"/>
+              <key id="d2" for="edge" attr.name="weight" attr.type="int"/>
+              <key id="d3" for="node" yfiles.type="nodegraphics"/>
+              <graph id="G" edgedefault="undirected">
+                <node id="a"><data key="d1">2.5</data><data key="d0">Alice &amp; co</data></node>
+                <node id="b"><data key="d3"><y:ShapeNode><y:NodeLabel>B</y:NodeLabel></y:ShapeNode></data></node>
+                <node id="c"><data key="d1">1.0</data></node>
+                <edge source="a" target="b"><data key="d2">7</data></edge>
+                <edge source="b" target="c"/>
+              </graph>
+            </graphml>"#,
+        )
+        .unwrap();
+        assert_eq!(GraphKind::Undirected, loaded.kind());
+        assert_eq!(
+            vec![Node::from("Alice & co"), Node::from("b"), Node::from("1.0")],
+            loaded.nodes
+        );
+        assert_eq!(Some(&Node::from(7)), loaded.edge_weight(0));
+        assert_eq!(Some(&Node::from("e1")), loaded.edge_weight(1));
+
+        assert_eq!(
+            "invalid GraphML: edge to an unknown node \"x\"",
+            Graph::<Node>::read_graphml(
+                r#"<graphml><graph><node id="a"/><edge source="a" target="x"/></graph></graphml>"#
+            )
+            .unwrap_err()
+            .to_string()
+        );
+        assert_eq!(
+            "invalid GraphML: line 2: `</graph>` closes `<node>`",
+            Graph::<Node>::read_graphml("<graphml><graph><node id=\"a\">\n</graph></graphml>")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "invalid GraphML: line 1: elements nested deeper than 128",
+            Graph::<Node>::read_graphml(&"<a>".repeat(100_000))
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn impact_of_a_change() {
+        // app -> lib -> core, app -> core, tool -> core, docs on its own
+        let mut g: Graph<&str> = Graph::new();
+        for name in ["app", "lib", "core", "tool", "docs"] {
+            g.add_node(name);
+        }
+        for (from, to) in [(0, 1), (1, 2), (0, 2), (3, 2)] {
+            g.add_edge(Edge { from, to });
+        }
+
+        assert_eq!(vec![(0, 1), (1, 1), (2, 0), (3, 1)], g.impacted_by(&[2]));
+        assert_eq!(vec![(0, 1), (1, 0), (4, 0)], g.impacted_by(&[1, 4, 99]));
+        assert!(g.impacted_by(&[]).is_empty());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_graph_format_round_trip() {
+        let mut g = generate_base_graph();
+        for i in 0..6 {
+            g.add_node(Node::from(i));
+        }
+        g.set_attr("name", "base");
+        let loaded = Graph::from_json(&g.to_json()).unwrap();
+        assert_eq!(
+            g.nodes.iter().map(|n| n.to_string()).collect::<Vec<_>>(),
+            loaded
+                .nodes
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(g.edges, loaded.edges);
+        assert_eq!(Some("base"), loaded.attr("name"));
+
+        let mut weighted: Graph<&str, u32> = Graph::with_kind(GraphKind::Undirected);
+        weighted.add_node("a");
+        weighted.add_node("b");
+        weighted.add_weighted_edge(Edge { from: 0, to: 1 }, 5);
+        assert_eq!(
+            r#"{"graph":{"directed":false,"nodes":{"0":{"label":"a"},"1":{"label":"b"}},"edges":[{"source":"0","target":"1","metadata":{"weight":5}}]}}"#,
+            weighted.to_json()
+        );
+
+        // version 1, nodes in an array
+        let v1 = Graph::from_json(
+            r#"{"graphs": [{"directed": false, "nodes": [{"id": "x", "label": "X"}, {"id": "y"}],
+                "edges": [{"source": "y", "target": "x", "relation": "uses"}]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(GraphKind::Undirected, v1.kind());
+        assert_eq!(vec![Node::from("X"), Node::from("y")], v1.nodes);
+        assert_eq!(vec![1], v1.reachable_nodes_from(0));
+
+        assert_eq!(
+            "invalid JSON Graph Format: no `graph`",
+            Graph::from_json("{}").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn common_ancestors_of_failures() {
+        // power -> rack -> {db, cache}, rack -> web -> cache, net -> web
+        let mut g: Graph<&str> = Graph::new();
+        for name in ["power", "rack", "db", "cache", "web", "net"] {
+            g.add_node(name);
+        }
+        for (from, to) in [(0, 1), (1, 2), (1, 3), (1, 4), (4, 3), (5, 4)] {
+            g.add_edge(Edge { from, to });
+        }
+
+        assert_eq!(vec![0, 1], g.common_ancestors(&[2, 3]));
+        assert_eq!(vec![1], g.lowest_common_ancestors(&[2, 3]));
+        assert_eq!(vec![0, 1, 4, 5], g.common_ancestors(&[3, 4]));
+        assert_eq!(vec![4], g.lowest_common_ancestors(&[3, 4]));
+        assert!(g.common_ancestors(&[2, 5]).is_empty());
+        assert!(g.common_ancestors(&[]).is_empty());
+
+        // on a cycle every member is as close as the others
+        g.add_edge(Edge { from: 2, to: 1 });
+        assert_eq!(vec![1, 2], g.lowest_common_ancestors(&[2, 3]));
+    }
+
+    #[test]
+    fn gexf_export() {
+        let mut g: Graph<&str, u32> = Graph::default();
+        g.add_node("a & b");
+        g.add_node("c");
+        g.add_weighted_edge(Edge { from: 0, to: 1 }, 3);
+        g.set_attr("creator", "rust-graph");
+
+        let mut out = Vec::new();
+        g.write_gexf(&mut out).unwrap();
+        let gexf = String::from_utf8(out).unwrap();
+        assert!(gexf.contains("<creator>rust-graph</creator>"));
+        assert!(gexf.contains(r#"<graph defaultedgetype="directed" mode="static">"#));
+        assert!(gexf.contains(r#"<node id="0" label="a &amp; b"/>"#));
+        assert!(gexf.contains(r#"<edge id="0" source="0" target="1"/>"#));
+
+        let mut out = Vec::new();
+        g.write_gexf_with(
+            &mut out,
+            &GexfOptions {
+                edge_weight: Some(&|_, w| *w as f64 / 2.0),
+                node_spell: Some(&|node_idx, _| Spell {
+                    start: Some(node_idx as f64),
+                    end: None,
+                }),
+                edge_spell: None,
+            },
+        )
+        .unwrap();
+        let gexf = String::from_utf8(out).unwrap();
+        assert!(gexf.contains(r#"mode="dynamic" timeformat="double""#));
+        assert!(gexf.contains(r#"<node id="1" label="c" start="1"/>"#));
+        assert!(gexf.contains(r#"<edge id="0" source="0" target="1" weight="1.5"/>"#));
+    }
+
+    #[test]
+    fn merge_policies_combine_duplicate_edges() {
+        let dot = "digraph { a -> b [weight=2]; a -> b [weight=5]; b -> a }";
+        let weight = |policy| {
+            let graph = Graph::from_dot_weighted(dot, policy).unwrap();
+            assert_eq!(graph.edge_count(), 2);
+            *graph.edge_weight(0).unwrap()
+        };
+        assert_eq!(weight(MergePolicy::KeepFirst), 2.0);
+        assert_eq!(weight(MergePolicy::KeepLast), 5.0);
+        assert_eq!(weight(MergePolicy::Sum), 7.0);
+        assert_eq!(weight(MergePolicy::Max), 5.0);
+        let err = Graph::from_dot_weighted(dot, MergePolicy::Error).unwrap_err();
+        assert_eq!(err.to_string(), "DOT line 1: duplicate edge `a -> b`");
+
+        // parallel edges already in the graph
+        let mut graph: Graph<Node, u32> = Graph::with_kind(GraphKind::Undirected);
+        let a = graph.add_node(Node::from("a"));
+        let b = graph.add_node(Node::from("b"));
+        graph.add_weighted_edge(Edge { from: a, to: b }, 3);
+        graph.edges.push(Edge { from: b, to: a });
+        graph.edge_weights.push(4);
+        graph.rebuild_adjacency();
+        assert_eq!(
+            graph.merge_parallel_edges(MergePolicy::Error),
+            Err(DuplicateEdge { from: b, to: a })
+        );
+        assert_eq!(graph.edge_count(), 2);
+        graph.merge_parallel_edges(MergePolicy::Sum).unwrap();
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.edge_weight(0), Some(&7));
+    }
+
+    #[test]
+    fn dot_delta_only_writes_changes() {
+        let mut graph = Graph::new();
+        let a = graph.add_node(Node::from("a"));
+        let b = graph.add_node(Node::from("b"));
+        let c = graph.add_node(Node::from("c"));
+        graph.add_edge(Edge { from: a, to: b });
+        graph.add_edge(Edge { from: b, to: c });
+
+        let options = DotOptions::default();
+        let snapshot = graph.dot_snapshot(&options);
+        let mut out = Vec::new();
+        let snapshot = graph
+            .write_dot_delta(&mut out, &snapshot, &options)
+            .unwrap();
+        assert!(out.is_empty());
+
+        graph[a] = Node::from("A");
+        graph.remove_node(c);
+        let d = graph.add_node(Node::from("d"));
+        graph.add_edge(Edge { from: a, to: d });
+        let mut out = Vec::new();
+        graph
+            .write_dot_delta(&mut out, &snapshot, &options)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "- 1 -> 2;\n~ 0 [label=\"A\"];\n~ 2 [label=\"d\"];\n+ 0 -> 2;\n"
+        );
+    }
+
+    #[test]
+    fn pajek_round_trip() {
+        let net = "% friends\n*Vertices 3\n1 \"Alice\" 0.1 0.2\n2 \"Bob\"\n*Edges\n1 2 2.5\n2 3\n";
+        let graph = Graph::read_pajek(net).unwrap();
+        assert_eq!(graph.kind(), GraphKind::Undirected);
+        assert_eq!(graph[2], Node::from("3"));
+        assert_eq!(graph.edge_weight(0), Some(&2.5));
+
+        let mut out = Vec::new();
+        graph.write_pajek_weighted(&mut out, &|_, w| *w).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "*Vertices 3\n1 \"Alice\"\n2 \"Bob\"\n3 \"3\"\n*Edges\n1 2 2.5\n2 3 1\n"
+        );
+
+        let mixed = Graph::read_pajek("*Vertices 2\n*Arcs\n1 2\n*Edges\n1 2\n").unwrap();
+        assert_eq!(mixed.kind(), GraphKind::Directed);
+        assert_eq!(mixed.edge_count(), 3);
+        assert_eq!(
+            Graph::read_pajek("*Vertices 2\n*Arcs\n1 3\n").unwrap_err(),
+            PajekError {
+                line: 3,
+                message: "no vertex 3".to_string()
+            }
+        );
+        assert_eq!(
+            Graph::read_pajek("*Vertices 4000000000\n").unwrap_err(),
+            PajekError {
+                line: 1,
+                message: "4000000000 vertices, the most supported is 268435456".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn matrix_market_round_trip() {
+        let mtx = "%%MatrixMarket matrix coordinate real symmetric\n% lower triangle\n3 3 3\n2 1 0.5\n3 2 2\n3 3 1\n";
+        let graph = Graph::from_matrix_market(mtx.as_bytes()).unwrap();
+        assert_eq!(graph.kind(), GraphKind::Undirected);
+        assert_eq!(graph[0], Node::from(1));
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.neighbors(0).collect::<Vec<_>>(), vec![1]);
+
+        let mut out = Vec::new();
+        graph
+            .write_matrix_market_weighted(&mut out, &|_, w| *w)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "%%MatrixMarket matrix coordinate real symmetric\n3 3 3\n2 1 0.5\n3 2 2\n3 3 1\n"
+        );
+
+        let err = Graph::from_matrix_market(
+            "%%MatrixMarket matrix coordinate pattern general\n2 3 0\n".as_bytes(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Matrix Market line 2: a 2x3 matrix isn't square"
+        );
+
+        // absurd sizes are errors, not allocations
+        let header = "%%MatrixMarket matrix coordinate pattern general\n";
+        let huge = format!("{}1 1 18446744073709551615\n1 1\n", header);
+        let graph = Graph::from_matrix_market(huge.as_bytes()).unwrap();
+        assert_eq!(graph.node_count(), 1);
+        let huge = format!("{}4000000000 4000000000 1\n1 1\n", header);
+        let err = Graph::from_matrix_market(huge.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Matrix Market line 2: 4000000000 rows, the most supported is 268435456"
+        );
+    }
+
+    #[test]
+    fn bipartite_matching_and_projection() {
+        let mut graph: BipartiteGraph<&str, &str> = BipartiteGraph::new();
+        let ann = graph.add_left("ann");
+        let bob = graph.add_left("bob");
+        let cat = graph.add_left("cat");
+        let x = graph.add_right("x");
+        let y = graph.add_right("y");
+        let z = graph.add_right("z");
+        // greedy would match ann-x and leave bob out
+        graph.add_edge(ann, x);
+        graph.add_edge(ann, y);
+        graph.add_edge(bob, x);
+        graph.add_edge(cat, y);
+        graph.add_edge(cat, z);
+        assert_eq!(graph.add_left("ann"), ann);
+
+        let matching = graph.maximum_matching();
+        assert_eq!(matching.len(), 3);
+        assert!(matching.contains(&(bob, x)));
+        assert!(matching.contains(&(ann, y)));
+        assert!(matching.contains(&(cat, z)));
+
+        let people = graph.project_left();
+        assert_eq!(people.node_count(), 3);
+        assert_eq!(people.edge_count(), 2);
+        assert_eq!(people[0], ann);
+        assert_eq!(*graph.left(people[2]), "cat");
+        assert_eq!(people.neighbors(0).count(), 2);
+        assert_eq!(graph.project_right().edge_count(), 2);
+    }
+
+    #[test]
+    fn gml_round_trip() {
+        let gml = r#"
+            Creator "someone"
+            graph [
+              # the club
+              name "karate"
+              node [ id 1 label "Mr. &quot;Hi&quot;" graphics [ x 1.0 y 2.0 ] ]
+              node [ id 2 ]
+              edge [ source 1 target 2 value 3 ]
+              edge [ source 2 target 1 ]
+            ]
+        "#;
+        let graph = Graph::read_gml(gml).unwrap();
+        assert_eq!(graph.kind(), GraphKind::Undirected);
+        assert_eq!(graph[0], Node::from("Mr. \"Hi\""));
+        assert_eq!(graph[1], Node::from(2));
+        assert_eq!(graph.attr("name"), Some("karate"));
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.edge_weight(0), Some(&3.0));
+
+        let mut out = Vec::new();
+        graph.write_gml_weighted(&mut out, &|_, w| *w).unwrap();
+        let written = String::from_utf8(out).unwrap();
+        assert!(written.contains("node [ id 0 label \"Mr. &quot;Hi&quot;\" ]"));
+        let again = Graph::read_gml(&written).unwrap();
+        assert_eq!(again[0], graph[0]);
+        assert_eq!(again.edge_weight(1), Some(&1.0));
+
+        let err = Graph::read_gml("graph [ edge [ source 1 target 2 ] ").unwrap_err();
+        assert_eq!(err.to_string(), "GML line 1: missing `]`");
+
+        let deep = "graph [ ".to_string() + &"x [ ".repeat(100_000);
+        let err = Graph::read_gml(&deep).unwrap_err();
+        assert_eq!(err.to_string(), "GML line 1: lists nested deeper than 128");
+    }
+
+    #[test]
+    fn k_truss_peels_weak_edges() {
+        let mut graph: Graph<usize> = Graph::with_kind(GraphKind::Undirected);
+        for i in 0..6 {
+            graph.add_node(i);
+        }
+        for (from, to) in [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 2),
+            (1, 3),
+            (2, 3),
+            (4, 0),
+            (4, 1),
+            (5, 4),
+        ] {
+            graph.add_edge(Edge { from, to });
+        }
+
+        let truss = graph.k_truss(4);
+        assert_eq!(
+            truss.nodes().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(truss.edge_count(), 6);
+        assert_eq!(graph.k_truss(3).edge_count(), 8);
+        assert_eq!(graph.k_truss(2).edge_count(), 9);
+        assert_eq!(graph.k_truss(5).node_count(), 0);
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let mut graph: Graph<Node, f64> = Graph::with_inline_adjacency();
+        let a = graph.add_node(Node::from("a"));
+        let b = graph.add_node(Node::from(7));
+        let c = graph.add_node(Node::from(vec![1, 2]));
+        let bc = graph.add_weighted_edge(Edge { from: b, to: c }, 0.5);
+        let ab = graph.add_weighted_edge(Edge { from: a, to: b }, 2.0);
+        graph.add_turn_restriction(ab, bc);
+        graph.set_attr("name", "tiny");
+
+        let bytes = graph.to_bytes();
+        let loaded: Graph<Node, f64> = Graph::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            loaded.nodes().collect::<Vec<_>>(),
+            graph.nodes().collect::<Vec<_>>()
+        );
+        // edges come back sorted
+        assert_eq!(loaded.edge(0), Some(&Edge { from: a, to: b }));
+        assert_eq!(loaded.edge_weight(1), Some(&0.5));
+        assert!(!loaded.is_turn_allowed(0, 1));
+        assert_eq!(loaded.attr("name"), Some("tiny"));
+
+        let mut corrupted = bytes.clone();
+        corrupted[8] ^= 1;
+        assert_eq!(
+            Graph::<Node, f64>::from_bytes(&corrupted)
+                .unwrap_err()
+                .to_string(),
+            "invalid binary graph: checksum mismatch, the data is corrupted"
+        );
+
+        // blobs and text point into `bytes`, nothing is copied
+        let borrowed: Graph<DataRef, f64> = Graph::from_bytes_borrowed(&bytes).unwrap();
+        let DataRef::Blob(blob) = borrowed.nodes[c] else {
+            panic!("expected a blob, got {:?}", borrowed.nodes[c]);
+        };
+        assert_eq!(blob, &[1, 2]);
+        assert!(bytes.as_ptr_range().contains(&blob.as_ptr()));
+        assert_eq!(borrowed.nodes[a], DataRef::Text("a"));
+        assert_eq!(borrowed.nodes[b].to_node(), Node::from(7));
+        assert_eq!(borrowed.edge_weight(1), Some(&0.5));
+
+        let path = std::env::temp_dir().join(format!("rust-graph-{}.bin", std::process::id()));
+        graph.save(&path).unwrap();
+        let loaded: Graph<Node, f64> = Graph::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.edge_count(), 2);
+
+        // b -> c isn't followed by a -> b
+        graph.turn_restrictions.insert((bc, ab));
+        assert_eq!(
+            Graph::<Node, f64>::from_bytes(&graph.to_bytes())
+                .unwrap_err()
+                .to_string(),
+            "invalid binary graph: turn restriction between edges that don't meet"
+        );
+    }
+
+    #[test]
+    fn entropy_tells_rings_from_stars() {
+        let mut ring: Graph<usize> = Graph::with_kind(GraphKind::Undirected);
+        let mut star: Graph<usize> = Graph::with_kind(GraphKind::Undirected);
+        for i in 0..8 {
+            ring.add_node(i);
+            star.add_node(i);
+        }
+        for i in 0..8 {
+            ring.add_edge(Edge {
+                from: i,
+                to: (i + 1) % 8,
+            });
+        }
+        for i in 1..8 {
+            star.add_edge(Edge { from: 0, to: i });
+        }
+
+        assert_eq!(ring.degree_entropy(), 0.0);
+        // 1/8 of the nodes with degree 7, 7/8 with degree 1
+        let expected = -(0.125f64 * 0.125f64.log2() + 0.875 * 0.875f64.log2());
+        assert!((star.degree_entropy() - expected).abs() < 1e-12);
+
+        // 1 - 1/8 - 8 edges * 1/4 / 64
+        assert!((ring.von_neumann_entropy() - (1.0 - 0.125 - 2.0 / 64.0)).abs() < 1e-12);
+        // 7 edges * 1/7
+        assert!((star.von_neumann_entropy() - (1.0 - 0.125 - 1.0 / 64.0)).abs() < 1e-12);
+        assert_eq!(Graph::<usize>::new().von_neumann_entropy(), 0.0);
+    }
+
+    #[test]
+    fn mermaid_export() {
+        let mut graph: Graph<Node, u32> = Graph::with_inline_adjacency();
+        let a = graph.add_node(Node::from("say \"hi\""));
+        let b = graph.add_node(Node::from("<b>#1</b>"));
+        graph.add_weighted_edge(Edge { from: a, to: b }, 5);
+        graph.edges.push(Edge { from: a, to: b });
+        graph.edge_weights.push(6);
+        graph.rebuild_adjacency();
+
+        assert_eq!(
+            graph.to_mermaid(),
+            "flowchart TD\n    n0[\"say #quot;hi#quot;\"]\n    n1[\"#lt;b#gt;#35;1#lt;/b#gt;\"]\n    n0 --> n1\n    n0 --> n1\n"
+        );
+
+        let label = |_, w: &u32| format!("w={}", w);
+        let options = MermaidOptions {
+            direction: MermaidDirection::LeftRight,
+            edge_label: Some(&label),
+            bundle_parallel_edges: true,
+        };
+        let mermaid = graph.to_mermaid_with(&options);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.ends_with("    n0 -->|\"w=5 x2\"| n1\n"));
+    }
+
+    #[test]
+    fn simrank_scores() {
+        let mut graph: Graph<usize> = Graph::new();
+        for i in 0..5 {
+            graph.add_node(i);
+        }
+        for (from, to) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 0), (3, 4)] {
+            graph.add_edge(Edge { from, to });
+        }
+
+        let scores = graph.simrank(6, 0.8);
+        // 1 and 2 are both pointed to by 0 only
+        assert!((scores[1][2] - 0.8).abs() < 1e-12);
+        assert_eq!(scores[1][2], scores[2][1]);
+        assert_eq!(scores[3][3], 1.0);
+
+        let top = graph.simrank_top_k(6, 0.8, 4);
+        for (a, row) in top.iter().enumerate() {
+            for (b, score) in row {
+                assert!((scores[a][*b] - score).abs() < 1e-12);
+            }
+        }
+        assert_eq!(top[1][0].0, 2);
+        assert!(graph
+            .simrank_top_k(6, 0.8, 1)
+            .iter()
+            .all(|row| row.len() <= 1));
+    }
+
+    #[test]
+    fn adjacency_matrices() {
+        let mut graph: Graph<usize, f64> = Graph::with_kind(GraphKind::Undirected);
+        for i in 0..3 {
+            graph.add_node(i);
+        }
+        graph.add_weighted_edge(Edge { from: 0, to: 1 }, 2.5);
+        graph.add_weighted_edge(Edge { from: 2, to: 2 }, 4.0);
+
+        assert_eq!(
+            graph.adjacency_matrix(),
+            vec![
+                vec![0.0, 1.0, 0.0],
+                vec![1.0, 0.0, 0.0],
+                vec![0.0, 0.0, 1.0]
+            ]
+        );
+        let weighted = graph.weighted_adjacency_matrix(&|_, w| *w);
+        assert_eq!(weighted[1][0], 2.5);
+        assert_eq!(weighted[2][2], 4.0);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn adjacency_arrays_match_the_nested_vecs() {
+        let mut graph: Graph<usize, f64> = Graph::with_inline_adjacency();
+        for i in 0..3 {
+            graph.add_node(i);
+        }
+        graph.add_weighted_edge(Edge { from: 0, to: 1 }, 2.5);
+        graph.add_weighted_edge(Edge { from: 1, to: 2 }, 0.5);
+
+        let array = graph.weighted_adjacency_array(&|_, w| *w);
+        let nested = graph.weighted_adjacency_matrix(&|_, w| *w);
+        for (from, row) in nested.iter().enumerate() {
+            for (to, value) in row.iter().enumerate() {
+                assert_eq!(array[[from, to]], *value);
+            }
+        }
+        assert_eq!(graph.adjacency_array()[[1, 0]], 0.0);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn nested_json_tree_round_trip() {
+        let json = r#"{"name": "src", "children": [
+            {"name": "lib.rs"},
+            {"name": "bin", "children": [{"name": "main.rs"}, {"name": 42}]}
+        ]}"#;
+        let tree = Graph::from_nested_json(json).unwrap();
+        assert_eq!(tree.node_count(), 5);
+        assert_eq!(tree[3], Node::from("main.rs"));
+        assert_eq!(tree.reachable_nodes_from(2), vec![3, 4]);
+
+        let written = tree.to_nested_json(0).unwrap();
+        assert_eq!(
+            written,
+            r#"{"name":"src","children":[{"name":"lib.rs"},{"name":"bin","children":[{"name":"main.rs"},{"name":"42"}]}]}"#
+        );
+        let again = Graph::from_nested_json(&written).unwrap();
+        assert!(again.same_topology(&tree));
+
+        let mut cyclic = tree;
+        cyclic.add_edge(Edge { from: 4, to: 0 });
+        assert!(cyclic.to_nested_json(0).is_err());
+
+        // as deep as serde_json reads back, and one level too deep
+        let mut path: Graph<usize> = Graph::with_inline_adjacency();
+        path.add_node(0);
+        for i in 1..=64 {
+            path.add_node(i);
+            path.add_edge(Edge { from: i - 1, to: i });
+        }
+        let written = path.to_nested_json(1).unwrap();
+        assert_eq!(Graph::from_nested_json(&written).unwrap().node_count(), 64);
+        assert_eq!(
+            path.to_nested_json(0).unwrap_err().to_string(),
+            "invalid nested JSON tree: node 64 is more than 63 levels below the root"
+        );
+    }
+
+    #[cfg(all(feature = "fs", unix))]
+    #[test]
+    fn directory_scan() {
+        let root = std::env::temp_dir().join(format!("rust-graph-scan-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+        std::os::unix::fs::symlink("src/lib.rs", root.join("link")).unwrap();
+
+        let graph = Graph::from_directory(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+        let graph = graph.unwrap();
+
+        let root = std::fs::canonicalize(std::env::temp_dir())
+            .unwrap()
+            .join(format!("rust-graph-scan-{}", std::process::id()));
+        assert_eq!(graph[0], root);
+        assert_eq!(graph.node_count(), 5);
+        let idx = |path: &str| {
+            graph
+                .nodes()
+                .position(|(_, p)| *p == root.join(path))
+                .unwrap()
+        };
+        let lib = idx("src/lib.rs");
+        let link = idx("link");
+        let mut parents = graph.nodes_that_can_reach(lib);
+        parents.sort();
+        assert_eq!(parents, vec![link, idx("src")]);
+        let (symlink, _) = graph
+            .edges()
+            .find(|(_, e)| e.from == link && e.to == lib)
+            .unwrap();
+        assert_eq!(graph.edge_weight(symlink), Some(&FsEdge::Symlink));
+    }
+
+    #[test]
+    fn laplacian_and_fiedler_value() {
+        // a path of 4 nodes, λ2 = 2 - 2cos(π/4)
+        let mut path: Graph<usize> = Graph::with_kind(GraphKind::Undirected);
+        for i in 0..4 {
+            path.add_node(i);
+        }
+        for i in 0..3 {
+            path.add_edge(Edge { from: i, to: i + 1 });
+        }
+
+        let laplacian = path.laplacian_matrix();
+        assert_eq!(laplacian[1], vec![-1.0, 2.0, -1.0, 0.0]);
+        let normalized = path.normalized_laplacian_matrix();
+        assert!((normalized[0][1] + 1.0 / 2f64.sqrt()).abs() < 1e-12);
+
+        let fiedler = path.fiedler(10_000, 7);
+        assert!(fiedler.converged);
+        let expected = 2.0 - 2.0 * (std::f64::consts::PI / 4.0).cos();
+        assert!((fiedler.value - expected).abs() < 1e-6);
+        // the two halves of the path end up on opposite sides
+        assert!(fiedler.vector[0] * fiedler.vector[3] < 0.0);
+        assert_eq!(fiedler.vector[0] > 0.0, fiedler.vector[1] > 0.0);
+
+        path.remove_edge_between(1, 2);
+        assert!(path.fiedler(10_000, 7).value.abs() < 1e-6);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn cargo_metadata_import() {
+        let metadata = r#"{
+            "packages": [
+                {"id": "app 0.1.0 (path+file:///ws/app)", "name": "app", "version": "0.1.0",
+                 "dependencies": [
+                    {"name": "serde", "kind": null, "target": null},
+                    {"name": "serde", "kind": "dev", "target": null},
+                    {"name": "winapi", "kind": null, "target": "cfg(windows)"}
+                 ]},
+                {"id": "serde 1.0.0 (registry+https://github.com/rust-lang/crates.io-index)",
+                 "name": "serde", "version": "1.0.0", "dependencies": []},
+                {"id": "winapi 0.3.9 (registry+https://github.com/rust-lang/crates.io-index)",
+                 "name": "winapi", "version": "0.3.9", "dependencies": []}
+            ],
+            "workspace_members": ["app 0.1.0 (path+file:///ws/app)"],
+            "resolve": {"nodes": [
+                {"id": "app 0.1.0 (path+file:///ws/app)", "deps": [
+                    {"name": "serde", "pkg": "serde 1.0.0 (registry+https://github.com/rust-lang/crates.io-index)",
+                     "dep_kinds": [{"kind": null, "target": null}, {"kind": "dev", "target": null}]},
+                    {"name": "winapi", "pkg": "winapi 0.3.9 (registry+https://github.com/rust-lang/crates.io-index)",
+                     "dep_kinds": [{"kind": null, "target": "cfg(windows)"}]}
+                ]},
+                {"id": "serde 1.0.0 (registry+https://github.com/rust-lang/crates.io-index)", "deps": []},
+                {"id": "winapi 0.3.9 (registry+https://github.com/rust-lang/crates.io-index)", "deps": []}
+            ]},
+            "workspace_root": "/ws"
+        }"#;
+        let graph = Graph::from_cargo_metadata(metadata).unwrap();
+        assert_eq!(graph.attr("workspace_root"), Some("/ws"));
+        assert!(graph.nodes[0].workspace_member && !graph.nodes[1].workspace_member);
+        assert_eq!(graph.nodes[2].to_string(), "winapi 0.3.9");
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(
+            graph.edge_weights[0].uses,
+            vec![
+                DependencyUse {
+                    kind: DependencyKind::Normal,
+                    target: None
+                },
+                DependencyUse {
+                    kind: DependencyKind::Dev,
+                    target: None
+                },
+            ]
+        );
+        assert_eq!(
+            graph.edge_weights[1].uses[0].target.as_deref(),
+            Some("cfg(windows)")
+        );
+
+        // `--no-deps` has no `resolve`, the declared dependencies give the
+        // same graph
+        let no_deps = metadata.replace("\"resolve\"", "\"ignored\"");
+        let declared = Graph::from_cargo_metadata(&no_deps).unwrap();
+        assert_eq!(declared.edges, graph.edges);
+        assert_eq!(declared.edge_weights, graph.edge_weights);
+
+        assert!(Graph::from_cargo_metadata("{}").is_err());
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn petgraph_round_trip() {
+        let mut g = Graph::new();
+        let text = g.add_node(Node::from("text"));
+        let number = g.add_node(Node::from(-3));
+        let blob = g.add_node(Node::from(vec![0u8, 255]));
+        g.add_edge(Edge {
+            from: text,
+            to: number,
+        });
+        g.add_edge(Edge {
+            from: number,
+            to: blob,
+        });
+
+        let converted: ::petgraph::Graph<Node, ()> = (&g).into();
+        assert_eq!(converted.node_count(), 3);
+        assert_eq!(
+            converted[::petgraph::graph::NodeIndex::new(1)],
+            Node::from(-3)
+        );
+        assert_eq!(
+            ::petgraph::algo::toposort(&converted, None).unwrap().len(),
+            3
+        );
+
+        let back: Graph = Graph::from(&converted);
+        assert_eq!(back.nodes, g.nodes);
+        assert_eq!(back.edges, g.edges);
+
+        // parallel edges and the kind survive too
+        let mut multi = ::petgraph::graph::UnGraph::<&str, u32>::new_undirected();
+        let a = multi.add_node("a");
+        let b = multi.add_node("b");
+        multi.add_edge(a, b, 1);
+        multi.add_edge(a, b, 2);
+        let from_petgraph: Graph<&str, u32> = Graph::from(&multi);
+        assert_eq!(from_petgraph.kind, GraphKind::Undirected);
+        assert_eq!(from_petgraph.edge_weights, vec![1, 2]);
+        assert_eq!(from_petgraph.edges.len(), 2);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn entities_json_import() {
+        let graph = Graph::from_entities_json(
+            r#"{
+                "attributes": {"tool": "pyan"},
+                "entities": [{"id": "app.main"}, {"id": "app.load", "kind": "function"}, {"id": 7}],
+                "relations": [
+                    {"from": "app.main", "to": "app.load", "kind": "calls", "weight": 3},
+                    {"from": "app.main", "to": "app.load", "kind": "imports"},
+                    {"from": "app.load", "to": "json"},
+                    {"from": 7, "to": "app.main"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(graph.attr("tool"), Some("pyan"));
+        assert_eq!(
+            graph.nodes,
+            vec![
+                Node::from("app.main"),
+                Node::from("app.load"),
+                Node::from(7),
+                Node::from("json"),
+            ]
+        );
+        assert_eq!(graph.edges.len(), 4);
+        assert_eq!(
+            graph.edge_weights[0],
+            Relation {
+                kind: "calls".to_string(),
+                weight: 3.0
+            }
+        );
+        assert_eq!(graph.edge_weights[2].kind, "");
+        assert_eq!(graph.edge_weights[2].weight, 1.0);
+
+        assert!(Graph::from_entities_json(r#"{"entities": [{"id": "a"}, {"id": "a"}]}"#).is_err());
+        assert!(Graph::from_entities_json(r#"{"relations": [{"from": "a"}]}"#).is_err());
+    }
+
+    #[test]
+    fn algorithms_run_on_any_backend() {
+        static DIAMOND: StaticGraph<&str> = StaticGraph::new(
+            &["a", "b", "c", "d"],
+            &[
+                Edge { from: 0, to: 1 },
+                Edge { from: 0, to: 2 },
+                Edge { from: 1, to: 3 },
+                Edge { from: 2, to: 3 },
+            ],
+        );
+        let graph = DIAMOND.to_graph();
+        let lists: Vec<Vec<NodeIndex>> = vec![vec![1, 2], vec![3], vec![3], vec![]];
+
+        let (mut on_graph, mut on_static, mut on_lists) = (Vec::new(), Vec::new(), Vec::new());
+        graph.depth_first_search(0..4, |event| {
+            on_graph.push(event);
+            Control::<()>::Continue
+        });
+        Algorithms::depth_first_search(&DIAMOND, 0..4, |event| {
+            on_static.push(event);
+            Control::<()>::Continue
+        });
+        lists.depth_first_search(0..4, |event| {
+            on_lists.push(event);
+            Control::<()>::Continue
+        });
+        assert_eq!(on_static, on_graph);
+        assert_eq!(on_lists, on_graph);
+
+        assert_eq!(
+            DIAMOND.bfs_distances(0),
+            vec![Some(0), Some(1), Some(1), Some(2)]
+        );
+        assert_eq!(lists.bfs_distances(3), vec![None, None, None, Some(0)]);
+        assert_eq!(DIAMOND.node_identifiers().count(), 4);
+
+        let mut weighted: Graph<&str, u32> = Graph::default();
+        for node in ["a", "b", "c"] {
+            weighted.add_node(node);
+        }
+        weighted.add_weighted_edge(Edge { from: 0, to: 1 }, 5);
+        weighted.add_weighted_edge(Edge { from: 0, to: 2 }, 1);
+        weighted.add_weighted_edge(Edge { from: 2, to: 1 }, 1);
+        let successors: Vec<Vec<(NodeIndex, u32)>> =
+            vec![vec![(1, 5), (2, 1)], vec![], vec![(1, 1)]];
+        assert_eq!(successors.shortest_paths(0), weighted.dijkstra_all(0));
+    }
+
+    #[test]
+    fn graph_edit_distance_upper_bound() {
+        let mut before = Graph::new();
+        for table in ["users", "orders", "items"] {
+            before.add_node(table);
+        }
+        before.add_edge(Edge { from: 1, to: 0 });
+        before.add_edge(Edge { from: 2, to: 1 });
+
+        // same schema, nodes added in another order
+        let mut shuffled = Graph::new();
+        for table in ["items", "users", "orders"] {
+            shuffled.add_node(table);
+        }
+        shuffled.add_edge(Edge { from: 2, to: 1 });
+        shuffled.add_edge(Edge { from: 0, to: 2 });
+        let same = before.ged_upper_bound(&shuffled);
+        assert_eq!(same.cost, 0);
+        assert_eq!(same.alignment, vec![Some(1), Some(2), Some(0)]);
+        assert!(same.inserted.is_empty());
+
+        // `items` renamed, a `payments` table pointing to `orders`
+        let mut after = Graph::new();
+        for table in ["users", "orders", "line_items", "payments"] {
+            after.add_node(table);
+        }
+        after.add_edge(Edge { from: 1, to: 0 });
+        after.add_edge(Edge { from: 2, to: 1 });
+        after.add_edge(Edge { from: 3, to: 1 });
+        let changed = before.ged_upper_bound(&after);
+        assert_eq!(changed.cost, 3);
+        assert_eq!(changed.alignment, vec![Some(0), Some(1), Some(2)]);
+        assert_eq!(changed.inserted, vec![3]);
+        assert_eq!(after.ged_upper_bound(&before).cost, 3);
+
+        assert_eq!(before.ged_upper_bound(&Graph::new()).cost, 5);
+    }
+
+    #[test]
+    fn graph_map_is_keyed_by_value() {
+        let mut follows: GraphMap<String, u32> = GraphMap::new();
+        follows.add_weighted_edge("alice".to_string(), "bob".to_string(), 3);
+        follows.add_weighted_edge("bob".to_string(), "carol".to_string(), 1);
+        follows.add_weighted_edge("alice".to_string(), "carol".to_string(), 2);
+        assert_eq!(follows.node_count(), 3);
+        assert_eq!(follows.edge_count(), 3);
+
+        let alice = "alice".to_string();
+        let carol = "carol".to_string();
+        assert!(follows.contains_edge(&alice, &carol));
+        assert!(!follows.contains_edge(&carol, &alice));
+        assert_eq!(follows.edge_weight(&alice, &carol), Some(&2));
+        *follows.edge_weight_mut(&alice, &carol).unwrap() += 1;
+        assert_eq!(
+            follows.neighbors(&alice).collect::<Vec<_>>(),
+            vec!["bob", "carol"]
+        );
+        assert_eq!(follows.neighbors(&"dave".to_string()).count(), 0);
+
+        // indexes move around, keys don't
+        assert_eq!(
+            follows.remove_node(&"bob".to_string()),
+            Some("bob".to_string())
+        );
+        assert_eq!(follows.index(&carol), Some(1));
+        assert_eq!(follows.edge_weight(&alice, &carol), Some(&3));
+        assert_eq!(follows.remove_edge(&alice, &carol), Some(3));
+        assert_eq!(follows.edge_count(), 0);
+
+        let mut undirected: GraphMap<(i32, i32)> = GraphMap::with_kind(GraphKind::Undirected);
+        undirected.add_edge((0, 0), (0, 1));
+        assert!(undirected.contains_edge(&(0, 1), &(0, 0)));
+        assert_eq!(undirected.bfs_distances(0), vec![Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn rank_nodes_is_stable() {
+        let mut g: Graph<&str> = Graph::new();
+        for node in ["c", "a", "b", "d", "e, f"] {
+            g.add_node(node);
+        }
+        let scores = [0.5, 0.25, 0.5, f64::NAN, 0.125];
+
+        let ranking = g.rank_nodes(&scores);
+        let order: Vec<NodeIndex> = ranking.iter().map(|ranked| ranked.node).collect();
+        // tied "c" and "b" are ordered by payload, NaN comes last
+        assert_eq!(order, vec![2, 0, 1, 4, 3]);
+        let ranks: Vec<usize> = ranking.iter().map(|ranked| ranked.rank).collect();
+        assert_eq!(ranks, vec![1, 1, 3, 4, 5]);
+        let percentiles: Vec<f64> = ranking.iter().map(|ranked| ranked.percentile).collect();
+        assert_eq!(percentiles, vec![75.0, 75.0, 50.0, 25.0, 0.0]);
+
+        let mut csv = Vec::new();
+        g.write_ranking_csv(&mut csv, &scores).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "rank,node,score,percentile\n\
+             1,b,0.5,75\n\
+             1,c,0.5,75\n\
+             3,a,0.25,50\n\
+             4,\"e, f\",0.125,25\n\
+             5,d,NaN,0\n"
+        );
+    }
+
+    #[test]
+    fn from_edges_and_builder() {
+        let g: Graph<&str> = Graph::from_edges([("a", "b"), ("b", "c"), ("a", "b")]);
+        assert_eq!(g.nodes, vec!["a", "b", "c"]);
+        assert_eq!(
+            g.edges,
+            vec![Edge { from: 0, to: 1 }, Edge { from: 1, to: 2 }]
+        );
+
+        let built: Graph<&str, u32> = GraphBuilder::new()
+            .edge("b", "a")
+            .weighted_edge("a", "b", 4)
+            .node("lonely")
+            .directed(false)
+            .build();
+        assert_eq!(built.kind, GraphKind::Undirected);
+        assert_eq!(built.nodes, vec!["b", "a", "lonely"]);
+        // `a -> b` is `b -> a` in an undirected graph
+        assert_eq!(built.edge_weights, vec![4]);
+    }
+
+    #[test]
+    fn traversals_with_payloads() {
+        let g = generate_base_graph();
+        let path = g.shortest_path_nodes(0, 5).unwrap();
+        assert_eq!(
+            path,
+            vec![
+                (&Node::from("hello"), 0),
+                (&Node::from("bar"), 3),
+                (&Node::from("asd"), 5),
+            ]
+        );
+        assert_eq!(g.shortest_path_nodes(5, 0), None);
+
+        let order = g.topological_sort_nodes().unwrap();
+        assert_eq!(
+            order.iter().map(|(_, idx)| *idx).collect::<Vec<_>>(),
+            g.topological_sort().unwrap()
+        );
+        assert_eq!(order[0].0, &Node::from("hello"));
+
+        let bfs: Vec<NodeIndex> = g.bfs_nodes(3).map(|(_, idx)| idx).collect();
+        assert_eq!(bfs, vec![3, 5]);
+        assert_eq!(
+            g.reachable_within_nodes(4, 1),
+            vec![(&Node::from("baz"), 4, 0), (&Node::from("asd"), 5, 1)]
+        );
+
+        let weighted: Graph<&str, u32> = GraphBuilder::new()
+            .weighted_edge("a", "b", 2)
+            .weighted_edge("b", "c", 2)
+            .weighted_edge("a", "c", 5)
+            .build();
+        assert_eq!(
+            weighted.dijkstra_nodes(0, 2),
+            Some((4, vec![(&"a", 0), (&"b", 1), (&"c", 2)]))
+        );
+    }
+
+    #[test]
+    fn bidirectional_search_matches_dijkstra() {
+        // a ladder: two parallel chains 0..6 and 6..12 with rungs between
+        // them, the bottom chain is cheaper
+        let mut g: Graph<isize, u32> = Graph::default();
+        for i in 0..13 {
+            g.add_node(i);
+        }
+        for i in 0..5 {
+            g.add_weighted_edge(Edge { from: i, to: i + 1 }, 3);
+            g.add_weighted_edge(
+                Edge {
+                    from: i + 6,
+                    to: i + 7,
+                },
+                1,
+            );
+            g.add_weighted_edge(Edge { from: i, to: i + 6 }, 2);
+        }
+        g.add_weighted_edge(Edge { from: 5, to: 11 }, 2);
+
+        for start in 0..13 {
+            for end in 0..13 {
+                let expected = g.dijkstra(start, end).map(|(cost, _)| cost);
+                let found = g.bidirectional_dijkstra(start, end);
+                assert_eq!(expected, found.as_ref().map(|(cost, _)| *cost));
+
+                if let Some((cost, path)) = found {
+                    assert_eq!((start, end), (path[0], *path.last().unwrap()));
+                    let walked: u32 = path
+                        .windows(2)
+                        .map(|hop| {
+                            let edge = Edge {
+                                from: hop[0],
+                                to: hop[1],
+                            };
+                            let idx = g.edges.iter().position(|e| e == &edge).unwrap();
+                            g.edge_weights[idx]
+                        })
+                        .sum();
+                    assert_eq!(cost, walked);
+                }
+            }
+        }
+        assert_eq!(
+            Some((7, vec![0, 6, 7, 8, 9, 10, 11])),
+            g.bidirectional_dijkstra(0, 11)
+        );
+        // the lonely node 12
+        assert_eq!(None, g.bidirectional_dijkstra(0, 12));
+
+        // fewest hops ignores the weights: 0 -> 1 -> ... -> 5 -> 11
+        assert_eq!(6, g.bidirectional_bfs(0, 11).unwrap().len() - 1);
+        assert_eq!(Some(vec![6, 7, 8]), g.bidirectional_bfs(6, 8));
+        assert_eq!(Some(vec![3]), g.bidirectional_bfs(3, 3));
+        assert_eq!(None, g.bidirectional_bfs(11, 0));
+    }
+
+    #[test]
+    fn edge_and_node_lookups() {
+        let g = generate_base_graph();
+        assert!(g.has_edge(0, 3));
+        assert!(!g.has_edge(3, 0));
+        assert!(!g.has_edge(0, 42));
+        assert_eq!(g.find_edge(3, 5), Some(4));
+        assert_eq!(g.find_edge(5, 3), None);
+        assert!(g.contains_node(&Node::from("baz")));
+        assert!(!g.contains_node(&Node::from("qux")));
+
+        let mut undirected: Graph<&str> = Graph::with_kind(GraphKind::Undirected);
+        let a = undirected.add_node("a");
+        let b = undirected.add_node("b");
+        undirected.add_edge(Edge { from: a, to: b });
+        assert!(undirected.has_edge(b, a));
+        assert_eq!(undirected.find_edge(b, a), Some(0));
+    }
+
+    #[test]
+    fn traced_searches_record_every_step() {
+        let g = generate_base_graph();
+        let (path, trace) = g.shortest_path_traced(0, 5);
+        assert_eq!(g.shortest_path(0, 5), path);
+
+        let first = trace.replay().next().unwrap();
+        assert_eq!(
+            &TraceEvent::Expand {
+                node: 0,
+                frontier: vec![],
+                visited: vec![0],
+            },
+            first
+        );
+        assert_eq!(
+            vec![(0, 1), (0, 2), (0, 3), (0, 4)],
+            trace.chosen_edges()[..4]
+        );
+        assert_eq!(
+            &TraceEvent::Done { path: path.clone() },
+            trace.events.last().unwrap()
+        );
+        assert_eq!(
+            "done, path [0, 3, 5]",
+            trace.events.last().unwrap().to_string()
+        );
+
+        // a -> c costs more than a -> b -> c, so a -> c gets replaced
+        let mut weighted: Graph<&str, u32> = Graph::default();
+        let a = weighted.add_node("a");
+        let b = weighted.add_node("b");
+        let c = weighted.add_node("c");
+        weighted.add_weighted_edge(Edge { from: a, to: c }, 5);
+        weighted.add_weighted_edge(Edge { from: a, to: b }, 1);
+        weighted.add_weighted_edge(Edge { from: b, to: c }, 1);
+
+        let (found, trace) = weighted.dijkstra_traced(a, c);
+        assert_eq!(weighted.dijkstra(a, c), found);
+        assert_eq!(vec![(a, c), (a, b), (b, c)], trace.chosen_edges());
+
+        let mut exported = Vec::new();
+        trace.write_json_lines(&mut exported).unwrap();
+        let exported = String::from_utf8(exported).unwrap();
+        let lines: Vec<&str> = exported.lines().collect();
+        assert_eq!(trace.events.len(), lines.len());
+        assert_eq!(
+            r#"{"event":"expand","node":1,"frontier":[2],"visited":[0, 1]}"#,
+            lines[3]
+        );
+        assert_eq!(
+            r#"{"event":"done","path":[0, 1, 2]}"#,
+            lines[lines.len() - 1]
+        );
+    }
+
+    #[test]
+    fn astar_on_a_grid() {
+        // 4x4 grid, moving right or down costs 1, the heuristic is the
+        // manhattan distance to the goal
+        let mut g: Graph<(isize, isize)> = Graph::new();
+        for y in 0..4 {
+            for x in 0..4 {
+                g.add_node((x, y));
+            }
+        }
+        for y in 0..4 {
+            for x in 0..4 {
+                let from = g.find_node_idx((x, y)).unwrap();
+                if let Some(to) = g.find_node_idx((x + 1, y)) {
+                    g.add_edge(Edge { from, to });
+                }
+                if let Some(to) = g.find_node_idx((x, y + 1)) {
+                    g.add_edge(Edge { from, to });
+                }
+            }
+        }
+
+        let start = g.find_node_idx((0, 0)).unwrap();
+        let goal = g.find_node_idx((3, 3)).unwrap();
+        let manhattan = |node_idx: NodeIndex| {
+            let (x, y) = g.nodes[node_idx];
+            ((3 - x) + (3 - y)) as Cost
+        };
+
+        let (cost, path) = g.astar(start, goal, |_, _| 1, manhattan).unwrap();
+        assert_eq!(6, cost);
+        assert_eq!(7, path.len());
+        assert_eq!((start, goal), (path[0], path[6]));
+
+        // can't go back up
+        assert_eq!(None, g.astar(goal, start, |_, _| 1, |_| 0));
+
+        // make going through the top row expensive
+        let expensive = |e: &Edge, _: &()| if g.nodes[e.to].1 == 0 { 10 } else { 1 };
+        let (cost, path) = g.astar(start, goal, expensive, manhattan).unwrap();
+        assert_eq!(6, cost);
+        assert_eq!(g.find_node_idx((0, 1)), Some(path[1]));
+
+        // only the edges of expanded nodes are looked at
+        let costed = std::cell::Cell::new(0);
+        let counting = |_: &Edge, _: &()| {
+            costed.set(costed.get() + 1);
+            1
+        };
+        let next_door = g.find_node_idx((1, 0)).unwrap();
+        assert_eq!(
+            Some((1, vec![start, next_door])),
+            g.astar(start, next_door, counting, |_| 0)
+        );
+        assert_eq!(2, costed.get());
+    }
+
+    #[test]
+    fn floyd_warshall_all_pairs() {
+        let mut g: Graph<&str, i32> = Graph::default();
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let c = g.add_node("c");
+        let d = g.add_node("d");
+
+        g.add_weighted_edge(Edge { from: a, to: b }, 3);
+        g.add_weighted_edge(Edge { from: b, to: c }, -1);
+        g.add_weighted_edge(Edge { from: a, to: c }, 5);
+        g.add_weighted_edge(Edge { from: c, to: a }, 1);
+
+        let m = g.floyd_warshall();
+        assert_eq!(Some(2), m.distance(a, c));
+        assert_eq!(Some(vec![a, b, c]), m.path(a, c));
+        assert_eq!(Some(vec![b, c, a]), m.path(b, a));
+        assert_eq!(Some(vec![d]), m.path(d, d));
+        assert_eq!(None, m.distance(a, d));
+        assert_eq!(None, m.distance(a, 10));
+        assert!(!m.has_negative_cycle());
+
+        // hop counts, with any payload
+        let hops = generate_loop_cfg().floyd_warshall_by(|_, _| 1);
+        assert_eq!(Some(3), hops.distance(2, 3));
+        assert_eq!(Some(vec![0, 1, 2, 4, 5]), hops.path(0, 5));
+        assert_eq!(None, hops.distance(5, 0));
+
+        g.add_weighted_edge(Edge { from: c, to: b }, -1);
+        assert!(g.floyd_warshall().has_negative_cycle());
+    }
+
+    #[test]
+    fn johnson_matches_floyd_warshall() {
+        let mut g: Graph<isize, i32> = Graph::default();
+        for i in 0..6 {
+            g.add_node(i);
+        }
+        let edges = [
+            (0, 1, 4),
+            (0, 2, 1),
+            (2, 1, -2),
+            (1, 3, 3),
+            (3, 4, -1),
+            (4, 1, 2),
+            (2, 4, 6),
+        ];
+        for (from, to, cost) in edges {
+            g.add_weighted_edge(Edge { from, to }, cost);
+        }
+
+        let matrix = g.floyd_warshall_by(|_, cost| *cost as Cost);
+        let mut sources = 0;
+        for (source, distances) in g.all_pairs_shortest_paths_sparse().unwrap() {
+            sources += 1;
+            for target in 0..6 {
+                assert_eq!(
+                    matrix.distance(source, target),
+                    distances.get(&target).copied()
+                );
+            }
+        }
+        assert_eq!(6, sources);
+
+        // 1 -> 3 -> 4 -> 1 now costs -1
+        g.add_weighted_edge(Edge { from: 4, to: 1 }, -3);
+        let err = g.all_pairs_shortest_paths_sparse().err().unwrap();
+        let mut cycle = err.cycle;
+        cycle.sort();
+        assert_eq!(vec![1, 3, 4], cycle);
+    }
+
+    #[test]
+    fn bellman_ford_negative_weights() {
+        let mut g: Graph<&str, i32> = Graph::default();
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let c = g.add_node("c");
+        let d = g.add_node("d");
+
+        g.add_weighted_edge(Edge { from: a, to: b }, 4);
+        g.add_weighted_edge(Edge { from: a, to: c }, 5);
+        g.add_weighted_edge(Edge { from: c, to: b }, -3);
+        g.add_weighted_edge(Edge { from: b, to: d }, 2);
+
+        let paths = g.bellman_ford(a).unwrap();
+        assert_eq!(vec![Some(0), Some(2), Some(5), Some(4)], paths.distances);
+        assert_eq!(Some(vec![a, c, b, d]), paths.path_to(d));
+
+        // d -> c closes the cycle c -> b -> d -> c, with cost -3 + 2 - 1
+        g.add_weighted_edge(Edge { from: d, to: c }, -1);
+        let err = g.bellman_ford(a).unwrap_err();
+        assert_eq!(3, err.cycle.len());
+        let start = err.cycle.iter().position(|n| *n == c).unwrap();
+        assert_eq!(b, err.cycle[(start + 1) % 3]);
+        assert_eq!(d, err.cycle[(start + 2) % 3]);
+
+        // negative cycles that can't be reached from the source don't matter
+        let mut h: Graph<&str, i32> = Graph::default();
+        let x = h.add_node("x");
+        let y = h.add_node("y");
+        h.add_weighted_edge(Edge { from: y, to: y }, -1);
+        assert_eq!(vec![Some(0), None], h.bellman_ford(x).unwrap().distances);
+
+        // any `Measure` works, here floats
+        let mut f: Graph<&str, f64> = Graph::default();
+        let x = f.add_node("x");
+        let y = f.add_node("y");
+        f.add_weighted_edge(Edge { from: x, to: y }, 1.5);
+        f.add_weighted_edge(Edge { from: y, to: x }, -1.0);
+        assert_eq!(
+            vec![Some(0.0), Some(1.5)],
+            f.bellman_ford(x).unwrap().distances
+        );
+        assert_eq!(Some(-1.0), f.floyd_warshall().distance(y, x));
+        assert!(!f.floyd_warshall().has_negative_cycle());
+        f.add_weighted_edge(Edge { from: y, to: x }, -2.0);
+        assert!(f.bellman_ford(x).is_err());
+        assert!(f.floyd_warshall().has_negative_cycle());
+    }
+
+    #[test]
+    fn overflowing_costs_are_ignored() {
+        let mut g: Graph<&str, i64> = Graph::default();
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let c = g.add_node("c");
+        g.add_weighted_edge(Edge { from: a, to: b }, i64::MAX);
+        g.add_weighted_edge(Edge { from: b, to: c }, i64::MAX);
+
+        let paths = g.bellman_ford(a).unwrap();
+        assert_eq!(vec![Some(0), Some(i64::MAX), None], paths.distances);
+        let m = g.floyd_warshall();
+        assert_eq!(Some(i64::MAX), m.distance(a, b));
+        assert_eq!(None, m.distance(a, c));
+
+        // c gets a potential of -1, so a -> c can't be reweighted
+        let mut h: Graph<&str, i64> = Graph::default();
+        let a = h.add_node("a");
+        let b = h.add_node("b");
+        let c = h.add_node("c");
+        h.add_weighted_edge(Edge { from: b, to: c }, -1);
+        h.add_weighted_edge(Edge { from: a, to: c }, i64::MAX);
+        let (_, from_a) = h.all_pairs_shortest_paths_sparse().unwrap().next().unwrap();
+        assert_eq!(Some(&0), from_a.get(&a));
+        assert_eq!(None, from_a.get(&c));
+    }
+
+    #[cfg(feature = "bumpalo")]
+    #[test]
+    #[should_panic(expected = "points to a node that doesn't exist")]
+    fn arena_graph_rejects_dangling_edges() {
+        let bump = bumpalo::Bump::new();
+        let mut ag = ArenaGraph::new_in(&bump);
+        let a = ag.add_integer(1);
+        ag.add_edge(Edge { from: a, to: 3 });
+    }
+
+    #[cfg(feature = "bumpalo")]
+    #[test]
+    fn arena_graph_matches_graph() {
+        let bump = bumpalo::Bump::new();
+        let mut ag = ArenaGraph::new_in(&bump);
+
+        let a = ag.add_text("hello");
+        let b = ag.add_node(&Node::from("world"));
+        let c = ag.add_integer(3);
+        assert_eq!(a, ag.add_text("hello"));
+
+        ag.add_edge(Edge { from: a, to: b });
+        ag.add_edge(Edge { from: a, to: c });
+
+        assert_eq!(vec![b, c], ag.reachable_nodes_from(a));
+        assert_eq!(Some(vec![b, c]), ag.boundary());
+
+        let g = ag.to_graph();
+        assert_eq!(Some(b), g.find_node_idx(Node::from("world")));
+        assert_eq!(vec![b, c], g.reachable_nodes_from(a));
+
+        // deduplication goes through hash indexes, building stays linear
+        let mut big = ArenaGraph::new_in(&bump);
+        for i in 0..100_000 {
+            let from = big.add_text(&i.to_string());
+            let to = big.add_integer(i);
+            big.add_edge(Edge { from, to });
+            assert_eq!(from, big.add_text(&i.to_string()));
+            assert_eq!(i as usize, big.add_edge(Edge { from, to }));
+        }
+        assert_eq!(200_000, big.node_count());
+        assert_eq!(100_000, big.edge_count());
+        assert_eq!(Some(1), big.find_node_idx(DataRef::Integer(0)));
+    }
+
+    #[cfg(feature = "serve")]
+    #[test]
+    fn live_view_follows_mutations() {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpStream;
+
+        let get = |addr, path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {} HTTP/1.1\r\nHost: test\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let mut g: Graph<&str> = Graph::new();
+        g.add_node("<start>");
+        let addr = g.watch_and_serve("127.0.0.1:0").unwrap();
+
+        assert!(get(addr, "/").contains("EventSource"));
+        assert!(get(addr, "/graph.svg").contains("&lt;start&gt;"));
+        assert!(get(addr, "/nope").starts_with("HTTP/1.1 404"));
+
+        let mut events = TcpStream::connect(addr).unwrap();
+        write!(events, "GET /events HTTP/1.1\r\nHost: test\r\n\r\n").unwrap();
+        let mut events = BufReader::new(events);
+        let mut next_drawing = || loop {
+            let mut line = String::new();
+            events.read_line(&mut line).unwrap();
+            if let Some(svg) = line.strip_prefix("data: ") {
+                return svg.to_owned();
+            }
+        };
+
+        assert!(!next_drawing().contains("finish"));
+        let finish = g.add_node("finish");
+        assert!(next_drawing().contains("finish"));
+        g.add_edge(Edge {
+            from: 0,
+            to: finish,
+        });
+        assert!(next_drawing().contains("<line"));
+
+        // the event stream ends with the server
+        g.stop_serving();
+        let mut rest = String::new();
+        events.read_to_string(&mut rest).unwrap();
+        assert!(!rest.contains("data: "));
+    }
+
+    static PIPELINE: StaticGraph<&str> = StaticGraph::new(
+        &["fetch", "decode", "execute", "trap"],
+        &[
+            Edge { from: 0, to: 1 },
+            Edge { from: 1, to: 2 },
+            Edge { from: 1, to: 3 },
+            Edge { from: 2, to: 0 },
+        ],
+    );
+
+    #[test]
+    fn static_graph_queries() {
+        assert_eq!(4, PIPELINE.node_count());
+        assert_eq!(4, PIPELINE.edge_count());
+        assert_eq!(Some(&"trap"), PIPELINE.node(3));
+        assert_eq!(Some(1), PIPELINE.find_node_idx(&"decode"));
+
+        assert_eq!(
+            vec![2, 3],
+            PIPELINE.reachable_nodes_from(1).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![2],
+            PIPELINE.nodes_that_can_reach(0).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![3], PIPELINE.boundary().collect::<Vec<_>>());
+
+        let g = PIPELINE.to_graph();
+        assert_eq!(Some(2), g.find_node_idx("execute"));
+        assert_eq!(vec![2, 3], g.reachable_nodes_from(1));
+        assert_eq!(Some(vec![0, 1, 2]), g.bidirectional_bfs(0, 2));
     }
 
     // println!("==============");
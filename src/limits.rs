@@ -0,0 +1,109 @@
+use std::fmt;
+
+use crate::graph::{Edge, EdgeIndex, Graph, NodeIndex};
+
+/*
+Caps on how big a graph (and a search over it) can get, for graphs built from
+input you don't trust. `None` means unbounded, which is the default.
+
+- `max_nodes`: `try_add_node` returns an `InvalidNode` once the graph is
+  full, `add_node` and `add_node_unchecked` panic
+- `max_edges`: `try_add_edge` and `try_add_weighted_edge` return a
+  `LimitExceeded`, `add_edge` and `add_weighted_edge` panic
+- `max_frontier`: `try_shortest_path` gives up with a `LimitExceeded` when
+  more nodes than this are waiting to be expanded
+
+Re-adding an existing node or edge never counts against the limits. Graphs
+derived from this one (condensation, `bfs_tree`...) start without limits.
+*/
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Limits {
+    pub max_nodes: Option<usize>,
+    pub max_edges: Option<usize>,
+    pub max_frontier: Option<usize>,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Limit {
+    Nodes,
+    Edges,
+    Frontier,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    pub max: usize,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.limit {
+            Limit::Nodes => "nodes",
+            Limit::Edges => "edges",
+            Limit::Frontier => "nodes in the search frontier",
+        };
+        write!(f, "limit exceeded: more than {} {}", self.max, what)
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+// `Err` if `count` is over `max`.
+pub(crate) fn check(limit: Limit, max: Option<usize>, count: usize) -> Result<(), LimitExceeded> {
+    match max {
+        Some(max) if count > max => Err(LimitExceeded { limit, max }),
+        _ => Ok(()),
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // Applies to what's added from now on, a graph that's already over a
+    // new limit is left as it is.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    pub fn try_add_edge(&mut self, new_edge: Edge) -> Result<EdgeIndex, LimitExceeded>
+    where
+        E: Default,
+    {
+        match self.find_edge(new_edge.from, new_edge.to) {
+            Some(idx) => Ok(idx),
+            None => {
+                check(Limit::Edges, self.limits.max_edges, self.edges.len() + 1)?;
+                Ok(self.push_edge(new_edge, E::default()))
+            }
+        }
+    }
+
+    pub fn try_add_weighted_edge(
+        &mut self,
+        new_edge: Edge,
+        weight: E,
+    ) -> Result<EdgeIndex, LimitExceeded> {
+        match self.find_edge(new_edge.from, new_edge.to) {
+            Some(idx) => {
+                self.edge_weights[idx] = weight;
+                Ok(idx)
+            }
+            None => {
+                check(Limit::Edges, self.limits.max_edges, self.edges.len() + 1)?;
+                Ok(self.push_edge(new_edge, weight))
+            }
+        }
+    }
+
+    // `shortest_path`, giving up once the frontier outgrows `max_frontier`.
+    pub fn try_shortest_path(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+    ) -> Result<Option<Vec<NodeIndex>>, LimitExceeded> {
+        self.shortest_path_search(start, end, self.limits.max_frontier, None)
+    }
+}
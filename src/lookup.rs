@@ -0,0 +1,131 @@
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use smallvec::SmallVec;
+
+use crate::graph::NodeIndex;
+
+/*
+Hash index over the node payloads, so `add_node` and `find_node_idx` don't
+have to compare against every node to deduplicate.
+
+Nodes are indexed in order: `hashes[i]` is the hash of node `i` for every
+node before `hashes.len()`, the ones after (appended by code that pushes to
+`Graph::nodes` directly, like `condensation`) are only scanned until the next
+`catch_up`. So are the `stale` nodes, whose payload was handed out mutably
+(`get_node_mut`, `replace_node`) and may not match their hash anymore.
+Different payloads can share a hash, bucket entries are always checked
+against the payload itself.
+*/
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NodeLookup {
+    hashes: Vec<u64>,
+    buckets: HashMap<u64, SmallVec<[NodeIndex; 1]>>,
+    stale: BTreeSet<NodeIndex>,
+}
+
+fn hash_of<N: Hash>(node: &N) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    node.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl NodeLookup {
+    pub(crate) fn find<N: Hash + PartialEq>(&self, nodes: &[N], node: &N) -> Option<NodeIndex> {
+        let hash = hash_of(node);
+        let indexed = self.buckets.get(&hash).and_then(|bucket| {
+            bucket
+                .iter()
+                .copied()
+                .find(|idx| *idx < nodes.len() && nodes[*idx] == *node)
+        });
+
+        let stale = || {
+            self.stale
+                .iter()
+                .copied()
+                .find(|idx| *idx < nodes.len() && nodes[*idx] == *node)
+        };
+
+        indexed.or_else(stale).or_else(|| {
+            let start = self.hashes.len().min(nodes.len());
+            nodes[start..]
+                .iter()
+                .position(|current| current == node)
+                .map(|offset| start + offset)
+        })
+    }
+
+    // Index every node that isn't yet.
+    pub(crate) fn catch_up<N: Hash>(&mut self, nodes: &[N]) {
+        for node_idx in std::mem::take(&mut self.stale) {
+            let Some(node) = nodes.get(node_idx) else {
+                continue;
+            };
+            let hash = hash_of(node);
+            self.hashes[node_idx] = hash;
+            self.buckets.entry(hash).or_default().push(node_idx);
+        }
+
+        let start = self.hashes.len();
+        for (node_idx, node) in nodes.iter().enumerate().skip(start) {
+            let hash = hash_of(node);
+            self.hashes.push(hash);
+            self.buckets.entry(hash).or_default().push(node_idx);
+        }
+    }
+
+    // Mirror `Vec::swap_remove` on the nodes, `node_count` being the amount
+    // of nodes before the removal.
+    pub(crate) fn swap_remove(&mut self, node_idx: NodeIndex, node_count: usize) {
+        if self.hashes.len() < node_count {
+            // the node moved into `node_idx` isn't indexed
+            self.truncate(node_idx);
+            return;
+        }
+
+        let last_idx = node_count - 1;
+        self.unlink(node_idx);
+        self.stale.remove(&node_idx);
+        if self.stale.remove(&last_idx) {
+            self.stale.insert(node_idx);
+        }
+        if node_idx != last_idx {
+            let moved = self.hashes[last_idx];
+            if let Some(bucket) = self.buckets.get_mut(&moved) {
+                for idx in bucket.iter_mut().filter(|idx| **idx == last_idx) {
+                    *idx = node_idx;
+                }
+            }
+        }
+        self.hashes.swap_remove(node_idx);
+    }
+
+    // Forget the nodes from `len` on, they're indexed again on the next
+    // `catch_up`.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        for node_idx in len..self.hashes.len() {
+            self.unlink(node_idx);
+        }
+        self.hashes.truncate(len);
+        self.stale.retain(|idx| *idx < len);
+    }
+
+    // Forget the hash of a single node, its payload is about to change. It's
+    // indexed again on the next `catch_up`.
+    pub(crate) fn invalidate(&mut self, node_idx: NodeIndex) {
+        if node_idx < self.hashes.len() && self.stale.insert(node_idx) {
+            self.unlink(node_idx);
+        }
+    }
+
+    fn unlink(&mut self, node_idx: NodeIndex) {
+        let hash = self.hashes[node_idx];
+        if let Some(bucket) = self.buckets.get_mut(&hash) {
+            bucket.retain(|idx| *idx != node_idx);
+            if bucket.is_empty() {
+                self.buckets.remove(&hash);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::graph::{Graph, NodeIndex};
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct NaturalLoop {
+    pub header: NodeIndex,
+    // sources of the back edges pointing to the header
+    pub latches: Vec<NodeIndex>,
+    // sorted, header included
+    pub body: Vec<NodeIndex>,
+    // index (in the `Vec` returned by `natural_loops`) of the innermost loop
+    // containing this one, `None` for outermost loops
+    pub parent: Option<usize>,
+    // 0 for outermost loops
+    pub depth: usize,
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Natural loops of a control-flow graph whose entry is `root`.
+
+    1. A back edge is an edge `latch -> header` where `header` dominates `latch`.
+    2. The body of the loop is the header plus every node that can reach the
+       latch without going through the header (reverse DFS from the latch,
+       stopping at the header and skipping nodes `root` can't reach). Back
+       edges sharing a header are merged into a single loop.
+    3. Loop A is nested inside loop B if B's body contains A's header, the
+       parent of a loop is the smallest loop containing it.
+
+    Loops are sorted by header. Parts of the graph that can't be reached from
+    `root` are ignored.
+    */
+    pub fn natural_loops(&self, root: NodeIndex) -> Vec<NaturalLoop> {
+        let dominators = self.dominators(root);
+        let mut loops: Vec<NaturalLoop> = Vec::new();
+
+        for edge in self.edges.iter() {
+            if !dominators.dominates(edge.to, edge.from) {
+                continue;
+            }
+
+            match loops.iter_mut().find(|l| l.header == edge.to) {
+                Some(existing) => existing.latches.push(edge.from),
+                None => loops.push(NaturalLoop {
+                    header: edge.to,
+                    latches: vec![edge.from],
+                    body: Vec::new(),
+                    parent: None,
+                    depth: 0,
+                }),
+            }
+        }
+
+        loops.sort_by_key(|l| l.header);
+
+        for l in loops.iter_mut() {
+            l.latches.sort_unstable();
+
+            let mut in_body = vec![false; self.nodes.len()];
+            in_body[l.header] = true;
+            let mut stack: Vec<NodeIndex> = Vec::new();
+
+            for latch in l.latches.iter().copied() {
+                if !in_body[latch] {
+                    in_body[latch] = true;
+                    stack.push(latch);
+                }
+            }
+
+            while let Some(node_idx) = stack.pop() {
+                for pred in self.incoming[node_idx].iter().copied() {
+                    if !in_body[pred] && dominators.is_reachable(pred) {
+                        in_body[pred] = true;
+                        stack.push(pred);
+                    }
+                }
+            }
+
+            l.body = (0..self.nodes.len()).filter(|n| in_body[*n]).collect();
+        }
+
+        for i in 0..loops.len() {
+            loops[i].parent = (0..loops.len())
+                .filter(|j| *j != i)
+                .filter(|j| loops[*j].body.binary_search(&loops[i].header).is_ok())
+                .filter(|j| loops[*j].body.len() > loops[i].body.len())
+                .min_by_key(|j| loops[*j].body.len());
+        }
+
+        for i in 0..loops.len() {
+            let mut depth = 0;
+            let mut current = loops[i].parent;
+            while let Some(parent) = current {
+                depth += 1;
+                current = loops[parent].parent;
+            }
+            loops[i].depth = depth;
+        }
+
+        loops
+    }
+}
@@ -0,0 +1,244 @@
+use crate::graph::{Edge, Graph, NodeIndex};
+
+/*
+Where random walks end up, see `Graph::absorption`. `probabilities[node][i]`
+is the probability that a walk starting at `node` ends in `absorbing[i]`.
+`expected_steps[node]` is the expected number of steps before that happens,
+`None` if there's a chance the walk never gets absorbed (it can get stuck in
+a cycle with no way out).
+*/
+#[derive(Clone, PartialEq, Debug)]
+pub struct Absorption {
+    pub absorbing: Vec<NodeIndex>,
+    pub expected_steps: Vec<Option<f64>>,
+    pub probabilities: Vec<Vec<f64>>,
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Copy + Into<f64>,
+{
+    /*
+    The graph as a Markov chain: every edge weight becomes the probability of
+    taking that edge from its source, i.e. the weight divided by the total
+    weight of the source's outgoing edges. Weights must not be negative.
+
+    Nodes whose outgoing weights add up to 0 (dead ends, like the last page
+    of a user journey) get a self-loop with probability 1, so they absorb
+    the walk and every node's probabilities add up to 1. Node and edge
+    indexes are preserved, self-loops are added at the end. The chain is
+    always directed: an undirected edge is walked both ways with different
+    probabilities, its reverse comes after all the original edges.
+    */
+    pub fn to_markov_chain(&self) -> Graph<N, f64, A>
+    where
+        N: Clone,
+    {
+        let totals = self.out_weight_totals();
+        let mut chain = Graph::with_inline_adjacency();
+        chain.nodes = self.nodes.clone();
+        for (edge_idx, arc) in self.arcs() {
+            let total = totals[arc.from];
+            chain.edge_weights.push(if total > 0.0 {
+                self.edge_weights[edge_idx].into() / total
+            } else {
+                0.0
+            });
+            chain.edges.push(arc);
+        }
+        for node_idx in (0..self.nodes.len()).filter(|n| totals[*n] == 0.0) {
+            chain.edges.push(Edge {
+                from: node_idx,
+                to: node_idx,
+            });
+            chain.edge_weights.push(1.0);
+        }
+        chain.rebuild_adjacency();
+        chain
+    }
+
+    /*
+    Long run fraction of the time a random walk (see `to_markov_chain`)
+    spends on every node, by power iteration starting from the uniform
+    distribution. Stops once an iteration changes the distribution by less
+    than `tolerance` (sum of the absolute changes) or after `max_iterations`.
+
+    Every step is "lazy" (stay put with probability 1/2), which has the same
+    stationary distribution but also converges on periodic chains (e.g. a
+    plain cycle). If the chain has several closed classes the result depends
+    on the starting point, here uniform.
+    */
+    pub fn stationary_distribution(&self, tolerance: f64, max_iterations: usize) -> Vec<f64> {
+        let n = self.nodes.len();
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let totals = self.out_weight_totals();
+        let transitions: Vec<(NodeIndex, NodeIndex, f64)> = self
+            .arcs()
+            .filter(|(_, arc)| totals[arc.from] > 0.0)
+            .map(|(edge_idx, arc)| {
+                let probability = self.edge_weights[edge_idx].into() / totals[arc.from];
+                (arc.from, arc.to, probability)
+            })
+            .collect();
+
+        let mut distribution = vec![1.0 / n as f64; n];
+        for _ in 0..max_iterations {
+            let mut next: Vec<f64> = distribution.iter().map(|p| p / 2.0).collect();
+            for node_idx in (0..n).filter(|n| totals[*n] == 0.0) {
+                next[node_idx] += distribution[node_idx] / 2.0;
+            }
+            for (from, to, probability) in transitions.iter() {
+                next[*to] += distribution[*from] * probability / 2.0;
+            }
+
+            let change: f64 = next
+                .iter()
+                .zip(distribution.iter())
+                .map(|(a, b)| (a - b).abs())
+                .sum();
+            distribution = next;
+            if change < tolerance {
+                break;
+            }
+        }
+        distribution
+    }
+
+    /*
+    Absorbing chain analysis of the random walk (see `to_markov_chain`): the
+    absorbing nodes are the dead ends (the `boundary`, plus nodes whose
+    outgoing weights add up to 0) and the nodes whose only way out is a
+    self-loop, like "purchased" or "bounced" at the end of a funnel. The
+    result is the same on the graph and on its `to_markov_chain`.
+
+    Solves `(I - Q) x = b` over the nodes that can reach an absorbing node,
+    `Q` being the transitions between them, once for the expected steps
+    (`b = 1`) and once per absorbing node (`b` = probability of stepping
+    straight into it). Dense Gaussian elimination, so cubic in the number of
+    those nodes.
+    */
+    pub fn absorption(&self) -> Absorption {
+        let n = self.nodes.len();
+        let totals = self.out_weight_totals();
+        let mut self_loops = vec![0.0; n];
+        for (edge, weight) in self.edges.iter().zip(self.edge_weights.iter()) {
+            if edge.from == edge.to {
+                self_loops[edge.from] += (*weight).into();
+            }
+        }
+        // summed in the same order, so exactly equal when there's nothing else
+        let is_absorbing: Vec<bool> = (0..n).map(|n| totals[n] == self_loops[n]).collect();
+        let absorbing: Vec<NodeIndex> = (0..n).filter(|n| is_absorbing[*n]).collect();
+
+        // transient nodes that can get absorbed at all
+        let mut leaks = vec![false; n];
+        let mut stack = absorbing.clone();
+        while let Some(node_idx) = stack.pop() {
+            for pred in self.incoming[node_idx].iter().copied() {
+                if !is_absorbing[pred] && !leaks[pred] {
+                    leaks[pred] = true;
+                    stack.push(pred);
+                }
+            }
+        }
+        let transient: Vec<NodeIndex> = (0..n).filter(|n| leaks[*n]).collect();
+        let mut row_of = vec![usize::MAX; n];
+        for (row, node_idx) in transient.iter().enumerate() {
+            row_of[*node_idx] = row;
+        }
+        let mut column_of = vec![usize::MAX; n];
+        for (column, node_idx) in absorbing.iter().enumerate() {
+            column_of[*node_idx] = column;
+        }
+
+        // [I - Q | 1 | R]
+        let t = transient.len();
+        let width = t + 1 + absorbing.len();
+        let mut system = vec![vec![0.0; width]; t];
+        for (row, equation) in system.iter_mut().enumerate() {
+            equation[row] = 1.0;
+            equation[t] = 1.0;
+        }
+        for (edge_idx, arc) in self.arcs() {
+            let row = row_of[arc.from];
+            if row == usize::MAX {
+                continue;
+            }
+            let probability = self.edge_weights[edge_idx].into() / totals[arc.from];
+            if row_of[arc.to] != usize::MAX {
+                system[row][row_of[arc.to]] -= probability;
+            } else if column_of[arc.to] != usize::MAX {
+                system[row][t + 1 + column_of[arc.to]] += probability;
+            }
+        }
+        gauss_jordan(&mut system, t);
+
+        let mut expected_steps = vec![None; n];
+        let mut probabilities = vec![vec![0.0; absorbing.len()]; n];
+        for (column, node_idx) in absorbing.iter().enumerate() {
+            expected_steps[*node_idx] = Some(0.0);
+            probabilities[*node_idx][column] = 1.0;
+        }
+        for (row, node_idx) in transient.iter().enumerate() {
+            probabilities[*node_idx] = system[row][t + 1..].to_vec();
+            let absorbed: f64 = probabilities[*node_idx].iter().sum();
+            if absorbed > 1.0 - 1e-9 {
+                expected_steps[*node_idx] = Some(system[row][t]);
+            }
+        }
+
+        Absorption {
+            absorbing,
+            expected_steps,
+            probabilities,
+        }
+    }
+
+    fn out_weight_totals(&self) -> Vec<f64> {
+        let mut totals = vec![0.0; self.nodes.len()];
+        for (edge_idx, arc) in self.arcs() {
+            let weight: f64 = self.edge_weights[edge_idx].into();
+            assert!(
+                weight >= 0.0,
+                "negative weight on edge {:?}",
+                self.edges[edge_idx]
+            );
+            totals[arc.from] += weight;
+        }
+        totals
+    }
+}
+
+// Reduce the first `n` columns of `system` to the identity (partial
+// pivoting), leaving the solutions in the remaining columns. The square part
+// has to be invertible.
+fn gauss_jordan(system: &mut [Vec<f64>], n: usize) {
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|a, b| {
+                system[*a][column]
+                    .abs()
+                    .total_cmp(&system[*b][column].abs())
+            })
+            .unwrap();
+        system.swap(column, pivot);
+
+        let divisor = system[column][column];
+        for value in system[column].iter_mut() {
+            *value /= divisor;
+        }
+        let pivot_row = system[column].clone();
+        for (row, equation) in system.iter_mut().enumerate() {
+            let factor = equation[column];
+            if row == column || factor == 0.0 {
+                continue;
+            }
+            for (value, pivot_value) in equation.iter_mut().zip(pivot_row.iter()) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+}
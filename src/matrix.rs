@@ -0,0 +1,68 @@
+use crate::graph::{EdgeWeight, Graph, GraphKind};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    The adjacency matrix, `matrix[from][to]` is 1 when there's an edge and 0
+    otherwise. Undirected graphs give a symmetric matrix. Rows and columns
+    follow node indexes. Takes n^2 floats, meant for feeding numeric code,
+    not for big graphs.
+    */
+    pub fn adjacency_matrix(&self) -> Vec<Vec<f64>> {
+        let n = self.nodes.len();
+        let mut matrix = vec![vec![0.0; n]; n];
+        for edge in self.edges.iter() {
+            matrix[edge.from][edge.to] = 1.0;
+            if self.kind == GraphKind::Undirected {
+                matrix[edge.to][edge.from] = 1.0;
+            }
+        }
+        matrix
+    }
+
+    // `adjacency_matrix` with the weight of every edge instead of 1, e.g.
+    // `&|_, w| *w as f64`. Parallel edges add up.
+    pub fn weighted_adjacency_matrix(&self, edge_weight: EdgeWeight<'_, E>) -> Vec<Vec<f64>> {
+        let n = self.nodes.len();
+        let mut matrix = vec![vec![0.0; n]; n];
+        self.for_each_weighted_entry(edge_weight, |from, to, weight| matrix[from][to] += weight);
+        matrix
+    }
+
+    // `adjacency_matrix` as an `ndarray` array.
+    #[cfg(feature = "ndarray")]
+    pub fn adjacency_array(&self) -> ndarray::Array2<f64> {
+        let n = self.nodes.len();
+        let mut matrix = ndarray::Array2::zeros((n, n));
+        for edge in self.edges.iter() {
+            matrix[[edge.from, edge.to]] = 1.0;
+            if self.kind == GraphKind::Undirected {
+                matrix[[edge.to, edge.from]] = 1.0;
+            }
+        }
+        matrix
+    }
+
+    // `weighted_adjacency_matrix` as an `ndarray` array.
+    #[cfg(feature = "ndarray")]
+    pub fn weighted_adjacency_array(&self, edge_weight: EdgeWeight<'_, E>) -> ndarray::Array2<f64> {
+        let n = self.nodes.len();
+        let mut matrix = ndarray::Array2::zeros((n, n));
+        self.for_each_weighted_entry(edge_weight, |from, to, weight| matrix[[from, to]] += weight);
+        matrix
+    }
+
+    // Undirected edges go both ways, self-loops once.
+    fn for_each_weighted_entry(
+        &self,
+        edge_weight: EdgeWeight<'_, E>,
+        mut entry: impl FnMut(usize, usize, f64),
+    ) {
+        for (edge_idx, edge) in self.edges.iter().enumerate() {
+            let weight = edge_weight(edge_idx, &self.edge_weights[edge_idx]);
+            entry(edge.from, edge.to, weight);
+            if self.kind == GraphKind::Undirected && edge.from != edge.to {
+                entry(edge.to, edge.from, weight);
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::graph::{Edge, EdgeWeight, Graph, GraphKind, Node};
+
+/*
+Matrix Market coordinate files (`.mtx`), the format of the SuiteSparse
+collection, read as adjacency matrices: entry (i, j) is an edge from node i to
+node j, its value the weight.
+
+    %%MatrixMarket matrix coordinate real general
+    % comments
+    3 3 2
+    1 2 0.5
+    3 1 2
+
+Rows and columns are numbered from 1.
+*/
+
+// Larger sizes are an error, rather than allocating whatever a corrupted size
+// line asks for. The biggest SuiteSparse matrices have about 2^28 rows.
+const MAX_ROWS: usize = 1 << 28;
+
+// A Matrix Market file `from_matrix_market` couldn't read. I/O errors end up
+// here too, on the line they happened.
+#[derive(PartialEq, Eq, Debug)]
+pub struct MatrixMarketError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for MatrixMarketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Matrix Market line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for MatrixMarketError {}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // A `pattern` matrix: where the edges are, no weights. Undirected graphs
+    // are written as `symmetric`, every edge in the lower triangle.
+    pub fn write_matrix_market(&self, out: &mut impl Write) -> io::Result<()> {
+        self.write_matrix_market_entries(out, None)
+    }
+
+    // A `real` matrix with the weight of every edge, e.g. `&|_, w| *w as f64`.
+    pub fn write_matrix_market_weighted(
+        &self,
+        out: &mut impl Write,
+        edge_weight: EdgeWeight<'_, E>,
+    ) -> io::Result<()> {
+        self.write_matrix_market_entries(out, Some(edge_weight))
+    }
+
+    fn write_matrix_market_entries(
+        &self,
+        out: &mut impl Write,
+        edge_weight: Option<EdgeWeight<'_, E>>,
+    ) -> io::Result<()> {
+        let field = if edge_weight.is_some() {
+            "real"
+        } else {
+            "pattern"
+        };
+        let symmetry = match self.kind {
+            GraphKind::Directed => "general",
+            GraphKind::Undirected => "symmetric",
+        };
+        writeln!(
+            out,
+            "%%MatrixMarket matrix coordinate {} {}",
+            field, symmetry
+        )?;
+        writeln!(
+            out,
+            "{} {} {}",
+            self.nodes.len(),
+            self.nodes.len(),
+            self.edges.len()
+        )?;
+
+        for (edge_idx, edge) in self.edges.iter().enumerate() {
+            let (row, col) = match self.kind {
+                GraphKind::Directed => (edge.from, edge.to),
+                GraphKind::Undirected => (edge.from.max(edge.to), edge.from.min(edge.to)),
+            };
+            match edge_weight {
+                Some(weight) => writeln!(
+                    out,
+                    "{} {} {}",
+                    row + 1,
+                    col + 1,
+                    weight(edge_idx, &self.edge_weights[edge_idx])
+                )?,
+                None => writeln!(out, "{} {}", row + 1, col + 1)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Graph<Node, f64> {
+    /*
+    Read a square coordinate matrix. Node i is `DataTypes::Integer(i)`,
+    numbered from 1 like in the file, and every entry is an edge weighted
+    with its value (1 for `pattern` matrices). Entries are kept in file order,
+    duplicates included (`merge_parallel_edges` with `MergePolicy::Sum` adds
+    them up, like most readers do).
+
+    `symmetric` matrices make undirected graphs, `skew-symmetric` ones
+    directed graphs with the mirrored entries added (negated). `array`
+    (dense) files, `complex` and `hermitian` matrices and non-square
+    matrices are errors, so are matrices of more than 2^28 rows.
+    */
+    pub fn from_matrix_market(reader: impl BufRead) -> Result<Self, MatrixMarketError> {
+        let mut lines = reader.lines().enumerate();
+        let mut next_line = || -> Result<Option<(usize, String)>, MatrixMarketError> {
+            match lines.next() {
+                None => Ok(None),
+                Some((line_idx, Err(err))) => Err(MatrixMarketError {
+                    line: line_idx + 1,
+                    message: err.to_string(),
+                }),
+                Some((line_idx, Ok(line))) => Ok(Some((line_idx + 1, line))),
+            }
+        };
+        let error = |line: usize, message: String| MatrixMarketError { line, message };
+
+        let header = match next_line()? {
+            Some((_, header)) => header.to_ascii_lowercase(),
+            None => return Err(error(1, "empty file".to_string())),
+        };
+        let header: Vec<&str> = header.split_whitespace().collect();
+        if header.len() != 5 || header[0] != "%%matrixmarket" || header[1] != "matrix" {
+            return Err(error(
+                1,
+                "expected a `%%MatrixMarket matrix` header".to_string(),
+            ));
+        }
+        if header[2] != "coordinate" {
+            return Err(error(1, format!("unsupported format {}", header[2])));
+        }
+        let pattern = match header[3] {
+            "real" | "integer" | "double" => false,
+            "pattern" => true,
+            field => return Err(error(1, format!("unsupported field {}", field))),
+        };
+        let (kind, skew) = match header[4] {
+            "general" => (GraphKind::Directed, false),
+            "symmetric" => (GraphKind::Undirected, false),
+            "skew-symmetric" => (GraphKind::Directed, true),
+            symmetry => return Err(error(1, format!("unsupported symmetry {}", symmetry))),
+        };
+
+        let mut graph = Graph::with_kind(kind);
+        let mut size = None;
+        let mut read = 0;
+        while let Some((line, text)) = next_line()? {
+            let text = text.trim();
+            if text.is_empty() || text.starts_with('%') {
+                continue;
+            }
+            let fields: Vec<&str> = text.split_whitespace().collect();
+
+            let Some((nodes, entries)) = size else {
+                let numbers: Vec<usize> = fields.iter().filter_map(|f| f.parse().ok()).collect();
+                if fields.len() != 3 || numbers.len() != 3 {
+                    return Err(error(line, "expected `rows columns entries`".to_string()));
+                }
+                if numbers[0] != numbers[1] {
+                    return Err(error(
+                        line,
+                        format!("a {}x{} matrix isn't square", numbers[0], numbers[1]),
+                    ));
+                }
+                if numbers[0] > MAX_ROWS {
+                    return Err(error(
+                        line,
+                        format!("{} rows, the most supported is {}", numbers[0], MAX_ROWS),
+                    ));
+                }
+                size = Some((numbers[0], numbers[2]));
+                continue;
+            };
+
+            if fields.len() != if pattern { 2 } else { 3 } {
+                return Err(error(line, "wrong number of values".to_string()));
+            }
+            let node = |field: &str| {
+                field
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|number| (1..=nodes).contains(number))
+                    .map(|number| number - 1)
+                    .ok_or_else(|| error(line, format!("no row or column {}", field)))
+            };
+            let (from, to) = (node(fields[0])?, node(fields[1])?);
+            let weight: f64 = match fields.get(2) {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| error(line, format!("invalid value {}", value)))?,
+                None => 1.0,
+            };
+
+            graph.edges.push(Edge { from, to });
+            graph.edge_weights.push(weight);
+            if skew && from != to {
+                graph.edges.push(Edge { from: to, to: from });
+                graph.edge_weights.push(-weight);
+            }
+            read += 1;
+            if read > entries {
+                return Err(error(line, format!("more than {} entries", entries)));
+            }
+        }
+
+        let Some((nodes, _)) = size else {
+            return Err(error(1, "missing the size line".to_string()));
+        };
+        // only now that the entries agree with it
+        graph.nodes = (1..=nodes as isize).map(Node::from).collect();
+        graph.node_lookup.catch_up(&graph.nodes);
+        graph.rebuild_adjacency();
+        Ok(graph)
+    }
+}
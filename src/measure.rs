@@ -0,0 +1,162 @@
+use std::cmp::Ordering;
+use std::num::Saturating;
+use std::time::Duration;
+
+use crate::graph::NodeIndex;
+
+/*
+Anything that can be used as a path cost by the shortest path algorithms
+(`dijkstra`, `astar`, `bellman_ford`, `floyd_warshall`...): there's a zero,
+costs can be added up and compared. Negative costs only make sense for the
+ones that say so.
+
+Implemented for the integer and float types, `Duration`, `Saturating<_>`
+integers (costs that stop growing instead of overflowing) and tuples of
+measures, which add up component by component and compare lexicographically
+(e.g. `(transfers, minutes)` picks the fewest transfers first).
+
+Floats are only `PartialOrd`, NaN costs compare as equal to everything and
+make results meaningless.
+*/
+pub trait Measure: Copy + PartialOrd {
+    fn zero() -> Self;
+    fn add(self, other: Self) -> Self;
+
+    // `None` if the sum doesn't fit, for the algorithms that skip paths that
+    // overflow (`bellman_ford`, `floyd_warshall`). Types that can't overflow
+    // keep the default.
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(self.add(other))
+    }
+}
+
+macro_rules! impl_measure {
+    ($($t:ty),*) => {
+        $(
+            impl Measure for $t {
+                fn zero() -> Self {
+                    0 as $t
+                }
+
+                fn add(self, other: Self) -> Self {
+                    self + other
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+            }
+
+            impl Measure for Saturating<$t> {
+                fn zero() -> Self {
+                    Saturating(0 as $t)
+                }
+
+                fn add(self, other: Self) -> Self {
+                    self + other
+                }
+            }
+        )*
+    };
+}
+
+impl_measure!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl Measure for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn add(self, other: Self) -> Self {
+        self + other
+    }
+}
+
+impl Measure for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn add(self, other: Self) -> Self {
+        self + other
+    }
+}
+
+impl Measure for Duration {
+    fn zero() -> Self {
+        Duration::ZERO
+    }
+
+    fn add(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Duration::checked_add(self, other)
+    }
+}
+
+impl<A: Measure, B: Measure> Measure for (A, B) {
+    fn zero() -> Self {
+        (A::zero(), B::zero())
+    }
+
+    fn add(self, other: Self) -> Self {
+        (self.0.add(other.0), self.1.add(other.1))
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some((self.0.checked_add(other.0)?, self.1.checked_add(other.1)?))
+    }
+}
+
+impl<A: Measure, B: Measure, C: Measure> Measure for (A, B, C) {
+    fn zero() -> Self {
+        (A::zero(), B::zero(), C::zero())
+    }
+
+    fn add(self, other: Self) -> Self {
+        (
+            self.0.add(other.0),
+            self.1.add(other.1),
+            self.2.add(other.2),
+        )
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some((
+            self.0.checked_add(other.0)?,
+            self.1.checked_add(other.1)?,
+            self.2.checked_add(other.2)?,
+        ))
+    }
+}
+
+// `BinaryHeap` entry that pops the smallest `score` first, ties go to the
+// smallest node index. Scores are only `PartialOrd`, incomparable ones count
+// as equal.
+pub(crate) struct MinScored<K>(pub K, pub NodeIndex);
+
+impl<K: PartialOrd> PartialEq for MinScored<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: PartialOrd> Eq for MinScored<K> {}
+
+impl<K: PartialOrd> PartialOrd for MinScored<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: PartialOrd> Ord for MinScored<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .partial_cmp(&self.0)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.1.cmp(&self.1))
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+
+use crate::graph::{Edge, EdgeIndex, Graph, NodeIndex};
+use crate::measure::Measure;
+
+// What to do with the weight when an edge shows up twice, e.g. a CSV export
+// with the same row repeated or a DOT file listing `a -> b` more than once.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MergePolicy {
+    // What `add_edge` does.
+    #[default]
+    KeepFirst,
+    // What `add_weighted_edge` does.
+    KeepLast,
+    Sum,
+    Max,
+    // Refuse the input with a `DuplicateEdge`.
+    Error,
+}
+
+impl MergePolicy {
+    // `None` for `Error`.
+    fn merge<E: Measure>(self, old: E, new: E) -> Option<E> {
+        match self {
+            MergePolicy::KeepFirst => Some(old),
+            MergePolicy::KeepLast => Some(new),
+            MergePolicy::Sum => Some(old.add(new)),
+            MergePolicy::Max => Some(if new > old { new } else { old }),
+            MergePolicy::Error => None,
+        }
+    }
+}
+
+// The edge `MergePolicy::Error` found twice.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DuplicateEdge {
+    pub from: NodeIndex,
+    pub to: NodeIndex,
+}
+
+impl fmt::Display for DuplicateEdge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "duplicate edge {} -> {}", self.from, self.to)
+    }
+}
+
+impl std::error::Error for DuplicateEdge {}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Measure,
+{
+    // `add_weighted_edge`, combining the weights with `policy` if the edge
+    // already exists. The graph is left untouched on error.
+    pub fn add_weighted_edge_merging(
+        &mut self,
+        new_edge: Edge,
+        weight: E,
+        policy: MergePolicy,
+    ) -> Result<EdgeIndex, DuplicateEdge> {
+        match self.find_edge(new_edge.from, new_edge.to) {
+            Some(idx) => {
+                self.edge_weights[idx] =
+                    policy
+                        .merge(self.edge_weights[idx], weight)
+                        .ok_or(DuplicateEdge {
+                            from: new_edge.from,
+                            to: new_edge.to,
+                        })?;
+                Ok(idx)
+            }
+            None => Ok(self.add_weighted_edge(new_edge, weight)),
+        }
+    }
+
+    /*
+    Merge parallel edges (see `aggregate_parallel_edges`) in place, for graphs
+    that came from an importer keeping them, like `read_graphml`. Weights are
+    combined in edge order, the merged edge takes the place of the first one.
+    Turn restrictions follow their edge, the ones on a merged-away edge are
+    dropped. With `MergePolicy::Error` the graph is left untouched if any edge
+    is doubled.
+    */
+    pub fn merge_parallel_edges(&mut self, policy: MergePolicy) -> Result<(), DuplicateEdge> {
+        let groups = self.parallel_edge_groups();
+        if groups.len() == self.edges.len() {
+            return Ok(());
+        }
+        if policy == MergePolicy::Error {
+            let group = groups.iter().find(|group| group.len() > 1).unwrap();
+            let Edge { from, to } = self.edges[group[1]];
+            return Err(DuplicateEdge { from, to });
+        }
+
+        let mut edge_map = vec![None; self.edges.len()];
+        let mut edges = Vec::with_capacity(groups.len());
+        let mut edge_weights = Vec::with_capacity(groups.len());
+        for group in &groups {
+            let mut weight = self.edge_weights[group[0]];
+            for edge_idx in &group[1..] {
+                weight = policy.merge(weight, self.edge_weights[*edge_idx]).unwrap();
+            }
+            let Edge { from, to } = self.edges[group[0]];
+            edge_map[group[0]] = Some(edges.len());
+            edges.push(Edge { from, to });
+            edge_weights.push(weight);
+        }
+
+        self.edges = edges;
+        self.edge_weights = edge_weights;
+        self.remap_turn_restrictions(&edge_map);
+        self.rebuild_adjacency();
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt::Display;
+
+use crate::graph::{EdgeIndex, Graph, GraphKind};
+
+// Which way `flowchart` lays out the graph.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MermaidDirection {
+    #[default]
+    TopDown,
+    LeftRight,
+    BottomUp,
+    RightLeft,
+}
+
+impl MermaidDirection {
+    fn code(self) -> &'static str {
+        match self {
+            MermaidDirection::TopDown => "TD",
+            MermaidDirection::LeftRight => "LR",
+            MermaidDirection::BottomUp => "BT",
+            MermaidDirection::RightLeft => "RL",
+        }
+    }
+}
+
+type EdgeLabel<'a, E> = &'a dyn Fn(EdgeIndex, &E) -> String;
+
+/*
+Extras for `to_mermaid_with`:
+- `direction` of the flowchart
+- `edge_label`: text on every edge, e.g. `&|_, w| w.to_string()`, an empty
+  string leaves the edge bare
+- `bundle_parallel_edges`: like in `DotOptions`, parallel edges are drawn
+  once and labeled with how many there are ("x3"), after the label of the
+  first one
+*/
+pub struct MermaidOptions<'a, E> {
+    pub direction: MermaidDirection,
+    pub edge_label: Option<EdgeLabel<'a, E>>,
+    pub bundle_parallel_edges: bool,
+}
+
+impl<E> Default for MermaidOptions<'_, E> {
+    fn default() -> Self {
+        MermaidOptions {
+            direction: MermaidDirection::default(),
+            edge_label: None,
+            bundle_parallel_edges: false,
+        }
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Display,
+{
+    /*
+    The graph as a Mermaid `flowchart`, to paste into Markdown (GitHub
+    renders ```mermaid blocks). Nodes are named `n<index>` and labeled with
+    `Display`, undirected edges are drawn without arrowheads.
+    */
+    pub fn to_mermaid(&self) -> String {
+        self.to_mermaid_with(&MermaidOptions::default())
+    }
+
+    pub fn to_mermaid_with(&self, options: &MermaidOptions<'_, E>) -> String {
+        let mut out = format!("flowchart {}\n", options.direction.code());
+        for (node_idx, node) in self.nodes.iter().enumerate() {
+            out.push_str(&format!(
+                "    n{}[\"{}\"]\n",
+                node_idx,
+                sanitize(&node.to_string())
+            ));
+        }
+
+        let arrow = match self.kind {
+            GraphKind::Directed => "-->",
+            GraphKind::Undirected => "---",
+        };
+        let groups: Vec<Vec<EdgeIndex>> = if options.bundle_parallel_edges {
+            self.parallel_edge_groups()
+        } else {
+            (0..self.edges.len())
+                .map(|edge_idx| vec![edge_idx])
+                .collect()
+        };
+        for group in groups {
+            let edge_idx = group[0];
+            let edge = &self.edges[edge_idx];
+            let mut label = options
+                .edge_label
+                .map(|label| label(edge_idx, &self.edge_weights[edge_idx]))
+                .unwrap_or_default();
+            if group.len() > 1 {
+                if !label.is_empty() {
+                    label.push(' ');
+                }
+                label.push_str(&format!("x{}", group.len()));
+            }
+
+            if label.is_empty() {
+                out.push_str(&format!("    n{} {} n{}\n", edge.from, arrow, edge.to));
+            } else {
+                out.push_str(&format!(
+                    "    n{} {}|\"{}\"| n{}\n",
+                    edge.from,
+                    arrow,
+                    sanitize(&label),
+                    edge.to
+                ));
+            }
+        }
+        out
+    }
+}
+
+// Text safe inside a quoted Mermaid label: quotes, `#` and angle brackets as
+// Mermaid entity codes, newlines as line breaks.
+fn sanitize(text: &str) -> String {
+    let mut sanitized = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '#' => sanitized.push_str("#35;"),
+            '"' => sanitized.push_str("#quot;"),
+            '<' => sanitized.push_str("#lt;"),
+            '>' => sanitized.push_str("#gt;"),
+            '\n' => sanitized.push_str("<br>"),
+            '\r' => {}
+            c => sanitized.push(c),
+        }
+    }
+    sanitized
+}
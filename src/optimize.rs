@@ -0,0 +1,258 @@
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+use crate::graph::{Adjacency, Edge, EdgeIndex, EdgeList, Graph, NodeIndex};
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct OptimizeReport {
+    // estimated heap + inline bytes, see `Graph::memory_usage`
+    pub memory_before: usize,
+    pub memory_after: usize,
+    // max |from - to| over all the edges
+    pub bandwidth_before: usize,
+    pub bandwidth_after: usize,
+    // `permutation[new_idx] == old_idx`, use it to translate indexes you were
+    // holding before calling `optimize()`
+    pub permutation: Vec<NodeIndex>,
+    // `edge_map[old_edge_idx] == new_edge_idx`, the same for edge indexes
+    pub edge_map: Vec<EdgeIndex>,
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Long-lived graphs that went through a lot of insertions/removals end up
+    with neighbours scattered all over the node array, and with vectors that
+    are way bigger than they need to be.
+
+    `optimize()`:
+    1. Renumbers the nodes in BFS order (ignoring edge direction), so nodes
+       that are close in the graph are also close in memory.
+    2. Sorts the edges by (from, to), so the edge array is grouped by source.
+    3. Rebuilds the adjacency lists and shrinks every vector to fit.
+
+    This invalidates all the node and edge indexes, the returned report contains
+    the permutation that was applied and where every edge went.
+    */
+    pub fn optimize(&mut self) -> OptimizeReport {
+        let memory_before = self.memory_usage();
+        let bandwidth_before = self.bandwidth();
+
+        let permutation = self.bfs_ordering();
+        let edge_map = self.apply_permutation(&permutation);
+
+        OptimizeReport {
+            memory_before,
+            memory_after: self.memory_usage(),
+            bandwidth_before,
+            bandwidth_after: self.bandwidth(),
+            permutation,
+            edge_map,
+        }
+    }
+
+    // Rough estimate (in bytes) of the memory used by the graph, including
+    // vector capacity that is allocated but not used. Heap memory owned by the
+    // node/edge payloads themselves (e.g. the contents of a `String`) is not
+    // included.
+    pub fn memory_usage(&self) -> usize {
+        let adjacency: usize = self
+            .outgoing
+            .iter()
+            .chain(self.incoming.iter())
+            .chain(self.out_edges.iter())
+            .filter(|adj| adj.spilled())
+            .map(|adj| adj.capacity() * size_of::<NodeIndex>())
+            .sum();
+
+        size_of::<Self>()
+            + self.nodes.capacity() * size_of::<N>()
+            + self.edges.capacity() * size_of::<Edge>()
+            + self.edge_weights.capacity() * size_of::<E>()
+            + (self.outgoing.capacity() + self.incoming.capacity()) * size_of::<Adjacency<A>>()
+            + self.out_edges.capacity() * size_of::<EdgeList<A>>()
+            + adjacency
+    }
+
+    pub fn bandwidth(&self) -> usize {
+        self.edges
+            .iter()
+            .map(|e| e.from.abs_diff(e.to))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /*
+    Node orderings. All of them return a permutation where
+    `ordering[new_idx] == old_idx`, which can be applied with `permute()`.
+    Edge direction is ignored, the degree of a node is in + out degree.
+    */
+
+    // Nodes sorted by increasing degree, ties broken by index.
+    pub fn degree_ordering(&self) -> Vec<NodeIndex> {
+        let mut ordering: Vec<NodeIndex> = (0..self.nodes.len()).collect();
+        ordering.sort_by_key(|node_idx| self.undirected_degree(*node_idx));
+        ordering
+    }
+
+    /*
+    Reverse Cuthill-McKee. For every disconnected piece of the graph:
+    1. Start from its node with the lowest degree.
+    2. BFS, enqueueing the unvisited neighbours of each node by increasing degree.
+    Then reverse the whole thing. This tends to produce a small bandwidth, which
+    means the adjacency matrix has all its non-zero entries close to the diagonal.
+    */
+    pub fn rcm_ordering(&self) -> Vec<NodeIndex> {
+        let mut ordering = Vec::with_capacity(self.nodes.len());
+        let mut visited = vec![false; self.nodes.len()];
+        let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+
+        for root in self.degree_ordering() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            queue.push_back(root);
+
+            while let Some(node_idx) = queue.pop_front() {
+                ordering.push(node_idx);
+
+                let mut neighbours: Vec<NodeIndex> = self.outgoing[node_idx]
+                    .iter()
+                    .chain(self.incoming[node_idx].iter())
+                    .copied()
+                    .filter(|n| !visited[*n])
+                    .collect();
+                neighbours.sort_by_key(|n| (self.undirected_degree(*n), *n));
+                neighbours.dedup();
+
+                for neighbour in neighbours {
+                    visited[neighbour] = true;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        ordering.reverse();
+        ordering
+    }
+
+    // Renumber the nodes so that the node at `ordering[i]` ends up at index `i`.
+    // Edges are rewritten (and sorted) accordingly, so all the node and edge
+    // indexes held outside of the graph become invalid. Returns the new index
+    // of every edge, `edge_map[old_edge_idx] == new_edge_idx`.
+    //
+    // Panics if `ordering` is not a permutation of the node indexes.
+    pub fn permute(&mut self, ordering: &[NodeIndex]) -> Vec<EdgeIndex> {
+        self.apply_permutation(ordering)
+    }
+
+    fn undirected_degree(&self, node_idx: NodeIndex) -> usize {
+        self.outgoing[node_idx].len() + self.incoming[node_idx].len()
+    }
+
+    // BFS over the undirected version of the graph, restarting from the
+    // lowest unvisited index for every disconnected piece.
+    fn bfs_ordering(&self) -> Vec<NodeIndex> {
+        let mut ordering = Vec::with_capacity(self.nodes.len());
+        let mut visited = vec![false; self.nodes.len()];
+        let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+
+        for root in 0..self.nodes.len() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            queue.push_back(root);
+
+            while let Some(node_idx) = queue.pop_front() {
+                ordering.push(node_idx);
+
+                for neighbour in self.outgoing[node_idx]
+                    .iter()
+                    .chain(self.incoming[node_idx].iter())
+                {
+                    if !visited[*neighbour] {
+                        visited[*neighbour] = true;
+                        queue.push_back(*neighbour);
+                    }
+                }
+            }
+        }
+
+        ordering
+    }
+
+    fn apply_permutation(&mut self, ordering: &[NodeIndex]) -> Vec<EdgeIndex> {
+        assert_eq!(
+            ordering.len(),
+            self.nodes.len(),
+            "ordering must contain every node exactly once"
+        );
+
+        let mut old_to_new = vec![usize::MAX; self.nodes.len()];
+        for (new_idx, old_idx) in ordering.iter().enumerate() {
+            assert!(
+                *old_idx < self.nodes.len(),
+                "node {} doesn't exist",
+                old_idx
+            );
+            assert!(
+                old_to_new[*old_idx] == usize::MAX,
+                "node {} appears twice in the ordering",
+                old_idx
+            );
+            old_to_new[*old_idx] = new_idx;
+        }
+
+        let mut old_nodes: Vec<Option<N>> = self.nodes.drain(..).map(Some).collect();
+        let mut nodes = Vec::with_capacity(old_nodes.len());
+        for old_idx in ordering {
+            nodes.push(old_nodes[*old_idx].take().unwrap());
+        }
+        self.nodes = nodes;
+        self.node_lookup.truncate(0);
+
+        let edges = std::mem::take(&mut self.edges);
+        let weights = std::mem::take(&mut self.edge_weights);
+        let mut edges: Vec<(Edge, E, EdgeIndex)> = edges
+            .into_iter()
+            .zip(weights)
+            .enumerate()
+            .map(|(old_edge_idx, (e, weight))| {
+                let edge = Edge {
+                    from: old_to_new[e.from],
+                    to: old_to_new[e.to],
+                };
+                (edge, weight, old_edge_idx)
+            })
+            .collect();
+        edges.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut edge_map: Vec<Option<EdgeIndex>> = vec![None; edges.len()];
+        for (new_edge_idx, (_, _, old_edge_idx)) in edges.iter().enumerate() {
+            edge_map[*old_edge_idx] = Some(new_edge_idx);
+        }
+        (self.edges, self.edge_weights) = edges
+            .into_iter()
+            .map(|(edge, weight, _)| (edge, weight))
+            .unzip();
+        self.remap_turn_restrictions(&edge_map);
+        self.edges.shrink_to_fit();
+        self.edge_weights.shrink_to_fit();
+
+        self.rebuild_adjacency();
+        self.outgoing.shrink_to_fit();
+        self.incoming.shrink_to_fit();
+        self.out_edges.shrink_to_fit();
+        for adj in self
+            .outgoing
+            .iter_mut()
+            .chain(self.incoming.iter_mut())
+            .chain(self.out_edges.iter_mut())
+        {
+            adj.shrink_to_fit();
+        }
+
+        edge_map.into_iter().map(Option::unwrap).collect()
+    }
+}
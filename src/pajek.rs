@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+use crate::graph::{Edge, EdgeWeight, Graph, GraphKind, Node, NodeIndex};
+
+/*
+Pajek `.net` files, the format much of the classic social network analysis
+data comes in:
+
+    *Vertices 3
+    1 "Alice"
+    2 "Bob"
+    3 "Carol"
+    *Arcs
+    1 2 1.5
+    *Edges
+    2 3
+
+Vertices are numbered from 1. `*Arcs` are directed, `*Edges` undirected,
+both take an optional weight after the two ends.
+*/
+
+// Larger `*Vertices` counts are an error, rather than allocating whatever a
+// corrupted count asks for.
+const MAX_VERTICES: usize = 1 << 28;
+
+// A Pajek file `read_pajek` couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct PajekError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PajekError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Pajek line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PajekError {}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Display,
+{
+    // Nodes labeled with `Display`, edges in `*Arcs` or `*Edges` depending on
+    // the kind, without weights. Pajek has no escapes, so `"` in labels is
+    // written as `'`.
+    pub fn write_pajek(&self, out: &mut impl Write) -> io::Result<()> {
+        self.write_pajek_edges(out, None)
+    }
+
+    // `write_pajek`, with the weight of every edge, e.g. `&|_, w| *w as f64`.
+    pub fn write_pajek_weighted(
+        &self,
+        out: &mut impl Write,
+        edge_weight: EdgeWeight<'_, E>,
+    ) -> io::Result<()> {
+        self.write_pajek_edges(out, Some(edge_weight))
+    }
+
+    fn write_pajek_edges(
+        &self,
+        out: &mut impl Write,
+        edge_weight: Option<EdgeWeight<'_, E>>,
+    ) -> io::Result<()> {
+        writeln!(out, "*Vertices {}", self.nodes.len())?;
+        for (node_idx, node) in self.nodes.iter().enumerate() {
+            writeln!(
+                out,
+                "{} \"{}\"",
+                node_idx + 1,
+                node.to_string().replace('"', "'")
+            )?;
+        }
+
+        match self.kind {
+            GraphKind::Directed => writeln!(out, "*Arcs")?,
+            GraphKind::Undirected => writeln!(out, "*Edges")?,
+        }
+        for (edge_idx, edge) in self.edges.iter().enumerate() {
+            match edge_weight {
+                Some(weight) => writeln!(
+                    out,
+                    "{} {} {}",
+                    edge.from + 1,
+                    edge.to + 1,
+                    weight(edge_idx, &self.edge_weights[edge_idx])
+                )?,
+                None => writeln!(out, "{} {}", edge.from + 1, edge.to + 1)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Graph<Node, f64> {
+    /*
+    Read a Pajek network. Nodes are `DataTypes::Text` with their label, or
+    their number when the file gives none, and edges keep their weight (1
+    when missing). Parallel edges are kept, `merge_parallel_edges` combines
+    them.
+
+    A file with only `*Edges` makes an undirected graph. With both `*Arcs` and
+    `*Edges` the graph is directed and every `*Edges` line becomes two edges.
+    `*Arcslist`/`*Edgeslist` sections are read too, `*Network` ends up in the
+    `name` attribute, coordinates and drawing attributes are skipped.
+    `*Matrix` sections, two-mode networks and more than 2^28 vertices are
+    errors.
+    */
+    pub fn read_pajek(net: &str) -> Result<Self, PajekError> {
+        let mut graph = Graph::default();
+        let mut section = Section::None;
+        // nodes are only created at the end, once the rest of the file is
+        // known to be valid
+        let mut vertex_count = None;
+        let mut labels: HashMap<NodeIndex, Node> = HashMap::new();
+        // (from, to, weight, undirected)
+        let mut links: Vec<(NodeIndex, NodeIndex, f64, bool)> = Vec::new();
+
+        for (line_idx, line) in net.lines().enumerate() {
+            let error = |message: String| PajekError {
+                line: line_idx + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('%') {
+                continue;
+            }
+            let tokens = split_line(line).map_err(|message| error(message.to_string()))?;
+
+            if let Some(keyword) = tokens[0].strip_prefix('*') {
+                section = match keyword.to_ascii_lowercase().as_str() {
+                    "network" => {
+                        if tokens.len() > 1 {
+                            graph.set_attr("name", tokens[1..].join(" "));
+                        }
+                        Section::None
+                    }
+                    "vertices" => {
+                        if vertex_count.is_some() {
+                            return Err(error("more than one *Vertices section".to_string()));
+                        }
+                        if tokens.len() > 2 {
+                            return Err(error("two-mode networks aren't supported".to_string()));
+                        }
+                        let count: usize = tokens
+                            .get(1)
+                            .and_then(|count| count.parse().ok())
+                            .ok_or_else(|| error("*Vertices without a count".to_string()))?;
+                        if count > MAX_VERTICES {
+                            return Err(error(format!(
+                                "{} vertices, the most supported is {}",
+                                count, MAX_VERTICES
+                            )));
+                        }
+                        vertex_count = Some(count);
+                        Section::Vertices
+                    }
+                    "arcs" => Section::Links {
+                        undirected: false,
+                        list: false,
+                    },
+                    "edges" => Section::Links {
+                        undirected: true,
+                        list: false,
+                    },
+                    "arcslist" => Section::Links {
+                        undirected: false,
+                        list: true,
+                    },
+                    "edgeslist" => Section::Links {
+                        undirected: true,
+                        list: true,
+                    },
+                    _ => return Err(error(format!("unsupported section *{}", keyword))),
+                };
+                continue;
+            }
+
+            let node_count = vertex_count.unwrap_or(0);
+            let node = |token: &String| {
+                token
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|number| (1..=node_count).contains(number))
+                    .map(|number| number - 1)
+                    .ok_or_else(|| error(format!("no vertex {}", token)))
+            };
+            match section {
+                Section::None => return Err(error("data outside of a section".to_string())),
+                Section::Vertices => {
+                    let node_idx = node(&tokens[0])?;
+                    if let Some(label) = tokens.get(1) {
+                        labels.insert(node_idx, Node::from(label.as_str()));
+                    }
+                }
+                Section::Links {
+                    undirected,
+                    list: false,
+                } => {
+                    if tokens.len() < 2 {
+                        return Err(error("expected two vertices".to_string()));
+                    }
+                    let weight = match tokens.get(2) {
+                        Some(weight) => weight
+                            .parse()
+                            .map_err(|_| error(format!("invalid weight {}", weight)))?,
+                        None => 1.0,
+                    };
+                    links.push((node(&tokens[0])?, node(&tokens[1])?, weight, undirected));
+                }
+                Section::Links {
+                    undirected,
+                    list: true,
+                } => {
+                    let from = node(&tokens[0])?;
+                    for token in &tokens[1..] {
+                        links.push((from, node(token)?, 1.0, undirected));
+                    }
+                }
+            }
+        }
+
+        graph.nodes = (0..vertex_count.unwrap_or(0))
+            .map(|node_idx| match labels.remove(&node_idx) {
+                Some(label) => label,
+                None => Node::from((node_idx + 1).to_string()),
+            })
+            .collect();
+        let has_arcs = links.iter().any(|(_, _, _, undirected)| !undirected);
+        if !has_arcs && !links.is_empty() {
+            graph.kind = GraphKind::Undirected;
+        }
+        for (from, to, weight, undirected) in links {
+            graph.edges.push(Edge { from, to });
+            graph.edge_weights.push(weight);
+            if has_arcs && undirected && from != to {
+                graph.edges.push(Edge { from: to, to: from });
+                graph.edge_weights.push(weight);
+            }
+        }
+        graph.node_lookup.catch_up(&graph.nodes);
+        graph.rebuild_adjacency();
+        Ok(graph)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Section {
+    None,
+    Vertices,
+    Links { undirected: bool, list: bool },
+}
+
+// Whitespace separated tokens, `"quoted labels"` being one token.
+fn split_line(line: &str) -> Result<Vec<String>, &'static str> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut token = String::new();
+        if c == '"' {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => token.push(c),
+                    None => return Err("unterminated quote"),
+                }
+            }
+        } else {
+            token.push(c);
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                token.push(c);
+            }
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
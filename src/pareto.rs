@@ -0,0 +1,120 @@
+use std::collections::VecDeque;
+
+use crate::graph::{Edge, Graph, NodeIndex};
+use crate::measure::Measure;
+
+// A partial path ending at `node`, `parent` is the label it was extended from.
+struct Label<K, const D: usize> {
+    node: NodeIndex,
+    cost: [K; D],
+    parent: Option<usize>,
+    // set once a better label reaches the same node
+    dominated: bool,
+}
+
+// `a` is at least as good as `b` in every dimension.
+fn covers<K: Measure, const D: usize>(a: &[K; D], b: &[K; D]) -> bool {
+    a.iter().zip(b.iter()).all(|(x, y)| x <= y)
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Multi-criteria shortest paths: every path from `start` to `end` that isn't
+    dominated by another one, i.e. there's no other path that is at least as
+    good in every cost dimension and better in one (e.g. fastest, cheapest,
+    and every trade-off in between).
+
+    `costs` gives the `D` costs of going through an edge, none of them can be
+    negative. Paths with exactly the same costs are only returned once.
+
+    Label-correcting search: every node keeps the costs of the non-dominated
+    partial paths that reach it, and a partial path is only extended while
+    no label at that node (or at `end`) dominates it. The front can grow
+    exponentially with the size of the graph in the worst case.
+
+    Returns (costs, path) pairs sorted by cost, `start` and `end` included in
+    every path.
+    */
+    pub fn pareto_paths<K, const D: usize>(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+        costs: impl Fn(&Edge, &E) -> [K; D],
+    ) -> Vec<([K; D], Vec<NodeIndex>)>
+    where
+        K: Measure,
+    {
+        let n = self.nodes.len();
+        if start >= n || end >= n {
+            return Vec::new();
+        }
+
+        let mut successors: Vec<Vec<(NodeIndex, [K; D])>> = vec![Vec::new(); n];
+        for (edge_idx, arc) in self.arcs() {
+            successors[arc.from].push((arc.to, costs(&arc, &self.edge_weights[edge_idx])));
+        }
+
+        let mut labels: Vec<Label<K, D>> = vec![Label {
+            node: start,
+            cost: [K::zero(); D],
+            parent: None,
+            dominated: false,
+        }];
+        // live labels of every node
+        let mut at_node: Vec<Vec<usize>> = vec![Vec::new(); n];
+        at_node[start].push(0);
+        let mut queue: VecDeque<usize> = VecDeque::from([0]);
+
+        while let Some(label_idx) = queue.pop_front() {
+            if labels[label_idx].dominated || labels[label_idx].node == end {
+                continue;
+            }
+
+            let node_idx = labels[label_idx].node;
+            for (succ, edge_cost) in successors[node_idx].iter() {
+                let mut cost = labels[label_idx].cost;
+                for (c, e) in cost.iter_mut().zip(edge_cost.iter()) {
+                    *c = c.add(*e);
+                }
+
+                let beaten = |idx: &usize| covers(&labels[*idx].cost, &cost);
+                if at_node[*succ].iter().any(beaten) || at_node[end].iter().any(beaten) {
+                    continue;
+                }
+
+                at_node[*succ].retain(|idx| {
+                    let worse = covers(&cost, &labels[*idx].cost);
+                    if worse {
+                        labels[*idx].dominated = true;
+                    }
+                    !worse
+                });
+
+                labels.push(Label {
+                    node: *succ,
+                    cost,
+                    parent: Some(label_idx),
+                    dominated: false,
+                });
+                at_node[*succ].push(labels.len() - 1);
+                queue.push_back(labels.len() - 1);
+            }
+        }
+
+        let mut front: Vec<([K; D], Vec<NodeIndex>)> = at_node[end]
+            .iter()
+            .map(|label_idx| {
+                let mut path = Vec::new();
+                let mut current = Some(*label_idx);
+                while let Some(idx) = current {
+                    path.push(labels[idx].node);
+                    current = labels[idx].parent;
+                }
+                path.reverse();
+                (labels[*label_idx].cost, path)
+            })
+            .collect();
+        front.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        front
+    }
+}
@@ -0,0 +1,76 @@
+use crate::graph::{Graph, NodeIndex};
+use crate::measure::Measure;
+use crate::topo::CycleError;
+
+/*
+Traversal and path results with the node payloads next to the indexes,
+`(&N, NodeIndex)` pairs, for when the next thing the caller would do is look
+every node up:
+
+    for (node, _) in graph.shortest_path_nodes(start, end).unwrap_or_default() {
+        println!("{}", node);
+    }
+
+The `_nodes` variants return exactly what the plain ones return, in the same
+order.
+*/
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // Pair any list of node indexes with their payloads. Panics on an index
+    // that doesn't exist.
+    pub fn with_payloads(
+        &self,
+        nodes: impl IntoIterator<Item = NodeIndex>,
+    ) -> impl Iterator<Item = (&N, NodeIndex)> {
+        nodes
+            .into_iter()
+            .map(|node_idx| (&self.nodes[node_idx], node_idx))
+    }
+
+    // `shortest_path` with payloads.
+    pub fn shortest_path_nodes(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+    ) -> Option<Vec<(&N, NodeIndex)>> {
+        let path = self.shortest_path(start, end)?;
+        Some(self.with_payloads(path).collect())
+    }
+
+    // `topological_sort` with payloads.
+    pub fn topological_sort_nodes(&self) -> Result<Vec<(&N, NodeIndex)>, CycleError> {
+        let order = self.topological_sort()?;
+        Ok(self.with_payloads(order).collect())
+    }
+
+    // `bfs` with payloads, as lazy as `bfs`.
+    pub fn bfs_nodes(&self, start: NodeIndex) -> impl Iterator<Item = (&N, NodeIndex)> {
+        self.with_payloads(self.bfs(start))
+    }
+
+    // `reachable_within` with payloads: (node, index, hops).
+    pub fn reachable_within_nodes(
+        &self,
+        start: NodeIndex,
+        max_hops: usize,
+    ) -> Vec<(&N, NodeIndex, usize)> {
+        self.reachable_within(start, max_hops)
+            .into_iter()
+            .map(|(node_idx, hops)| (&self.nodes[node_idx], node_idx, hops))
+            .collect()
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Measure,
+{
+    // `dijkstra` with payloads.
+    pub fn dijkstra_nodes(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+    ) -> Option<(E, Vec<(&N, NodeIndex)>)> {
+        let (cost, path) = self.dijkstra(start, end)?;
+        Some((cost, self.with_payloads(path).collect()))
+    }
+}
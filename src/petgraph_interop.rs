@@ -0,0 +1,91 @@
+use std::hash::Hash;
+
+use petgraph::graph::IndexType;
+use petgraph::{Directed, EdgeType, Undirected};
+
+use crate::graph::{Edge, Graph, GraphKind};
+
+/*
+Conversions to and from `petgraph::Graph` (the `petgraph` feature), to use
+petgraph's algorithms on a graph built here and the other way around.
+
+Node and edge payloads are cloned as they are, a `Graph` (of `Node`s) gives
+a `petgraph::Graph<Node, ()>` and back, so nothing about `DataTypes` gets
+lost. Node and edge indexes are kept on both sides, so are parallel edges.
+Graph attributes, turn restrictions, limits and the node validator have no
+petgraph equivalent and are dropped.
+
+Edges keep the orientation they were added with: an `Undirected` graph
+converted into a `petgraph::Graph<_, _, Directed>` has every edge one way
+only, convert it into an `Undirected` one instead.
+*/
+
+impl<N, E, const A: usize> From<&Graph<N, E, A>> for petgraph::Graph<N, E, Directed>
+where
+    N: Clone,
+    E: Clone,
+{
+    fn from(graph: &Graph<N, E, A>) -> Self {
+        to_petgraph(graph)
+    }
+}
+
+impl<N, E, const A: usize> From<&Graph<N, E, A>> for petgraph::Graph<N, E, Undirected>
+where
+    N: Clone,
+    E: Clone,
+{
+    fn from(graph: &Graph<N, E, A>) -> Self {
+        to_petgraph(graph)
+    }
+}
+
+fn to_petgraph<N, E, Ty, const A: usize>(graph: &Graph<N, E, A>) -> petgraph::Graph<N, E, Ty>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+{
+    let mut converted = petgraph::Graph::with_capacity(graph.nodes.len(), graph.edges.len());
+    for node in graph.nodes.iter() {
+        converted.add_node(node.clone());
+    }
+    for (edge, weight) in graph.edges.iter().zip(graph.edge_weights.iter()) {
+        converted.add_edge(
+            petgraph::graph::NodeIndex::new(edge.from),
+            petgraph::graph::NodeIndex::new(edge.to),
+            weight.clone(),
+        );
+    }
+    converted
+}
+
+// Directed petgraph graphs give `GraphKind::Directed` graphs, undirected ones
+// `GraphKind::Undirected`.
+impl<N, E, Ty, Ix, const A: usize> From<&petgraph::Graph<N, E, Ty, Ix>> for Graph<N, E, A>
+where
+    N: Clone + Hash,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn from(graph: &petgraph::Graph<N, E, Ty, Ix>) -> Self {
+        let kind = if graph.is_directed() {
+            GraphKind::Directed
+        } else {
+            GraphKind::Undirected
+        };
+        let mut converted = Graph::with_kind(kind);
+        converted.nodes = graph.node_weights().cloned().collect();
+        converted.node_lookup.catch_up(&converted.nodes);
+        for edge in graph.raw_edges() {
+            converted.edges.push(Edge {
+                from: edge.source().index(),
+                to: edge.target().index(),
+            });
+            converted.edge_weights.push(edge.weight.clone());
+        }
+        converted.rebuild_adjacency();
+        converted
+    }
+}
@@ -0,0 +1,51 @@
+use std::hash::Hash;
+use std::ops::AddAssign;
+
+use crate::graph::{Edge, Graph};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // Collapse all the nodes that share the same `key` into a single node,
+    // edges between groups are counted. See `quotient_with`.
+    pub fn quotient<K, F>(&self, key: F) -> Graph<K, usize>
+    where
+        K: Hash + PartialEq,
+        F: Fn(&N) -> K,
+    {
+        self.quotient_with(key, |_| 1)
+    }
+
+    /*
+    Quotient (a.k.a. super) graph: every distinct `key(node)` becomes a single
+    node, and all the edges going from one group to another are merged into a
+    single edge whose weight is the sum of `weight(edge)`.
+
+    Edges between two nodes of the same group become a self-loop on that
+    group, so the amount of "internal" traffic isn't lost. Groups appear in
+    the order their first node appears in this graph.
+    */
+    pub fn quotient_with<K, W, F, G>(&self, key: F, weight: G) -> Graph<K, W>
+    where
+        K: Hash + PartialEq,
+        W: AddAssign + Default,
+        F: Fn(&N) -> K,
+        G: Fn(&E) -> W,
+    {
+        let mut quotient: Graph<K, W> = Graph::default();
+
+        let mapping: Vec<usize> = self
+            .nodes
+            .iter()
+            .map(|node| quotient.add_node(key(node)))
+            .collect();
+
+        for (edge, edge_weight) in self.edges.iter().zip(self.edge_weights.iter()) {
+            let edge_idx = quotient.add_edge(Edge {
+                from: mapping[edge.from],
+                to: mapping[edge.to],
+            });
+            quotient.edge_weights[edge_idx] += weight(edge_weight);
+        }
+
+        quotient
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+use crate::graph::{Edge, Graph, NodeIndex};
+use crate::rng::Rng;
+
+/*
+Randomized null models: graphs with the same degree sequence as the one being
+studied but otherwise random, to tell apart what is a property of the graph
+(clustering, motifs...) from what is just a consequence of its degrees.
+*/
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Randomize the edges while keeping the in and out degree of every node.
+
+    Every iteration picks 2 edges a -> b and c -> d and swaps their targets
+    into a -> d and c -> b. Swaps that would create a self-loop or a duplicate
+    edge are skipped, so no new ones are introduced. Edge payloads stay with
+    their edge index (i.e. with the source node).
+
+    Returns the amount of swaps that were actually performed. A common choice
+    is ~10 iterations per edge.
+    */
+    pub fn rewire_preserving_degrees(&mut self, iterations: usize, seed: u64) -> usize {
+        if self.edges.len() < 2 {
+            return 0;
+        }
+
+        let mut rng = Rng::new(seed);
+        let mut existing: HashSet<(NodeIndex, NodeIndex)> =
+            self.edges.iter().map(|e| (e.from, e.to)).collect();
+        let mut swaps = 0;
+
+        for _ in 0..iterations {
+            let i = rng.below(self.edges.len());
+            let j = rng.below(self.edges.len());
+            let (a, b) = (self.edges[i].from, self.edges[i].to);
+            let (c, d) = (self.edges[j].from, self.edges[j].to);
+
+            if a == d || c == b || existing.contains(&(a, d)) || existing.contains(&(c, b)) {
+                continue;
+            }
+
+            existing.remove(&(a, b));
+            existing.remove(&(c, d));
+            existing.insert((a, d));
+            existing.insert((c, b));
+            self.edges[i].to = d;
+            self.edges[j].to = b;
+            swaps += 1;
+        }
+
+        // rewired edges may not meet at the restricted turn anymore
+        let edges = &self.edges;
+        self.turn_restrictions
+            .retain(|(in_edge, out_edge)| edges[*in_edge].to == edges[*out_edge].from);
+        self.rebuild_adjacency();
+        swaps
+    }
+}
+
+impl<E: Default, const A: usize> Graph<NodeIndex, E, A> {
+    /*
+    Configuration model: a random graph where node `i` has `out_degrees[i]`
+    outgoing and `in_degrees[i]` incoming edges. Node payloads are their own
+    index. Returns `None` if the sequences have different lengths or don't
+    add up to the same amount of edges.
+
+    Every node gets one "stub" per edge end, and outgoing stubs are randomly
+    paired with incoming ones. Pairings that would create a self-loop or a
+    duplicate edge are fixed at the end by swapping targets with an already
+    placed edge (searched from a random starting point). If no placed edge
+    works the pairing is dropped, so on very dense sequences some degrees may
+    come out lower than asked for.
+    */
+    pub fn from_degree_sequence(
+        in_degrees: &[usize],
+        out_degrees: &[usize],
+        seed: u64,
+    ) -> Option<Self> {
+        if in_degrees.len() != out_degrees.len()
+            || in_degrees.iter().sum::<usize>() != out_degrees.iter().sum::<usize>()
+        {
+            return None;
+        }
+
+        let mut rng = Rng::new(seed);
+        let mut targets: Vec<NodeIndex> = Vec::new();
+        for (node_idx, degree) in in_degrees.iter().enumerate() {
+            targets.extend(std::iter::repeat_n(node_idx, *degree));
+        }
+        // Fisher-Yates
+        for i in (1..targets.len()).rev() {
+            targets.swap(i, rng.below(i + 1));
+        }
+
+        let mut pairs: Vec<(NodeIndex, NodeIndex)> = Vec::with_capacity(targets.len());
+        let mut existing: HashSet<(NodeIndex, NodeIndex)> = HashSet::new();
+        let mut targets = targets.into_iter();
+
+        let mut rejected: Vec<(NodeIndex, NodeIndex)> = Vec::new();
+
+        for (from, degree) in out_degrees.iter().enumerate() {
+            for to in targets.by_ref().take(*degree) {
+                if from != to && existing.insert((from, to)) {
+                    pairs.push((from, to));
+                } else {
+                    rejected.push((from, to));
+                }
+            }
+        }
+
+        // look for a placed edge c -> d such that from -> d and c -> to are
+        // both valid
+        for (from, to) in rejected {
+            let offset = rng.below(pairs.len().max(1));
+            for k in (0..pairs.len()).map(|i| (i + offset) % pairs.len()) {
+                let (c, d) = pairs[k];
+                if from != d
+                    && c != to
+                    && !existing.contains(&(from, d))
+                    && !existing.contains(&(c, to))
+                {
+                    existing.remove(&(c, d));
+                    existing.insert((c, to));
+                    existing.insert((from, d));
+                    pairs[k] = (c, to);
+                    pairs.push((from, d));
+                    break;
+                }
+            }
+        }
+
+        let mut g = Graph::with_inline_adjacency();
+        g.nodes = (0..in_degrees.len()).collect();
+        for (from, to) in pairs {
+            g.edges.push(Edge { from, to });
+            g.edge_weights.push(E::default());
+        }
+        g.rebuild_adjacency();
+        Some(g)
+    }
+}
@@ -0,0 +1,126 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::graph::{Graph, NodeIndex};
+
+/*
+A row of `rank_nodes`. `rank` starts at 1 and is shared by tied scores
+("1224" ranking: two nodes tied for second are both 2, the next one is 4),
+`percentile` is the share of the other nodes with a strictly lower score,
+from 0 (the lowest score) to 100 (the highest, or the only node).
+*/
+#[derive(Clone, PartialEq, Debug)]
+pub struct RankedNode {
+    pub node: NodeIndex,
+    pub score: f64,
+    pub rank: usize,
+    pub percentile: f64,
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Ord,
+{
+    /*
+    Nodes sorted by `scores[node]` (a centrality, `stationary_distribution`,
+    degrees...), highest first. Ties are broken by payload, then by index,
+    so the order only depends on the graph and the scores, never on how the
+    scores were computed or sorted before: the same inputs always give the
+    same report. NaN scores come last, ranked below everything else.
+
+    Panics if there isn't a score for every node.
+    */
+    pub fn rank_nodes(&self, scores: &[f64]) -> Vec<RankedNode> {
+        assert_eq!(
+            scores.len(),
+            self.nodes.len(),
+            "expected a score for each of the {} nodes",
+            self.nodes.len()
+        );
+
+        let mut order: Vec<NodeIndex> = (0..self.nodes.len()).collect();
+        order.sort_by(|a, b| {
+            compare_scores(scores[*b], scores[*a])
+                .then_with(|| self.nodes[*a].cmp(&self.nodes[*b]))
+                .then(a.cmp(b))
+        });
+
+        let n = order.len();
+        let mut ranking: Vec<RankedNode> = Vec::with_capacity(n);
+        for (position, node) in order.into_iter().enumerate() {
+            let score = scores[node];
+            let rank = match ranking.last() {
+                Some(previous) if compare_scores(previous.score, score) == Ordering::Equal => {
+                    previous.rank
+                }
+                _ => position + 1,
+            };
+            ranking.push(RankedNode {
+                node,
+                score,
+                rank,
+                percentile: 0.0,
+            });
+        }
+
+        // nodes below = nodes after the last one tied with this one
+        let mut below = 0;
+        for position in (0..n).rev() {
+            if position + 1 < n && ranking[position + 1].rank != ranking[position].rank {
+                below = n - position - 1;
+            }
+            ranking[position].percentile = match n {
+                1 => 100.0,
+                _ => 100.0 * below as f64 / (n - 1) as f64,
+            };
+        }
+        ranking
+    }
+
+    /*
+    `rank_nodes` as CSV, with a header:
+
+        rank,node,score,percentile
+        1,hub,0.42,100
+        2,"Smith, J.",0.17,50
+
+    Nodes are written with `Display`, quoted when they contain a comma, a
+    quote or a line break.
+    */
+    pub fn write_ranking_csv(&self, out: &mut impl Write, scores: &[f64]) -> io::Result<()>
+    where
+        N: Display,
+    {
+        writeln!(out, "rank,node,score,percentile")?;
+        for ranked in self.rank_nodes(scores) {
+            writeln!(
+                out,
+                "{},{},{},{}",
+                ranked.rank,
+                csv_field(&self.nodes[ranked.node].to_string()),
+                ranked.score,
+                ranked.percentile
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Equal, or both NaN. NaN is lower than everything else.
+fn compare_scores(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,209 @@
+use std::io::{self, Read, Write};
+
+use crate::graph::{Graph, NodeIndex};
+
+const MAGIC: &[u8; 4] = b"RGRI";
+const VERSION: u8 = 1;
+const UNREACHABLE: u32 = u32::MAX;
+
+/*
+Precomputed reachability for a static DAG, so `can_reach(a, b)` is a couple of
+array lookups instead of a traversal.
+
+Built with a chain decomposition:
+1. Split the nodes into chains (paths of the DAG), greedily following
+   unassigned successors in topological order.
+2. Every node gets `(chain, position inside the chain)`. Reaching a node of a
+   chain means reaching every node after it in the same chain.
+3. For every node and every chain, store the lowest position of that chain it
+   can reach (reverse topological order, taking the minimum of the successors).
+
+`can_reach(a, b)` is then `reach[a][chain(b)] <= position(b)`. Memory is
+`nodes * chains`, which is fine for DAGs that are "narrow" (few chains)
+compared to their size, like most dependency graphs.
+
+The index is a snapshot, it doesn't see changes made to the graph after it
+was built.
+*/
+#[derive(PartialEq, Eq, Debug)]
+pub struct ReachIndex {
+    chain: Vec<u32>,
+    position: Vec<u32>,
+    chain_count: usize,
+    // reach[node * chain_count + chain]
+    reach: Vec<u32>,
+}
+
+impl ReachIndex {
+    // `None` if the graph has a cycle.
+    pub fn build<N, E, const A: usize>(graph: &Graph<N, E, A>) -> Option<Self> {
+        let n = graph.nodes.len();
+        let order = graph.kahn_order()?;
+
+        let mut chain = vec![UNREACHABLE; n];
+        let mut position = vec![0; n];
+        let mut chain_count = 0;
+
+        for start in order.iter().copied() {
+            if chain[start] != UNREACHABLE {
+                continue;
+            }
+
+            let mut current = Some(start);
+            let mut pos = 0;
+            while let Some(node_idx) = current {
+                chain[node_idx] = chain_count as u32;
+                position[node_idx] = pos;
+                pos += 1;
+                current = graph.outgoing[node_idx]
+                    .iter()
+                    .copied()
+                    .find(|next| chain[*next] == UNREACHABLE);
+            }
+            chain_count += 1;
+        }
+
+        let mut reach = vec![UNREACHABLE; n * chain_count];
+        for node_idx in order.iter().rev().copied() {
+            let row = node_idx * chain_count;
+            reach[row + chain[node_idx] as usize] = position[node_idx];
+
+            for succ in graph.outgoing[node_idx].iter().copied() {
+                let succ_row = succ * chain_count;
+                for c in 0..chain_count {
+                    reach[row + c] = reach[row + c].min(reach[succ_row + c]);
+                }
+            }
+        }
+
+        Some(ReachIndex {
+            chain,
+            position,
+            chain_count,
+            reach,
+        })
+    }
+
+    pub fn can_reach(&self, from: NodeIndex, to: NodeIndex) -> bool {
+        if from >= self.chain.len() || to >= self.chain.len() {
+            return false;
+        }
+        let c = self.chain[to] as usize;
+        self.reach[from * self.chain_count + c] <= self.position[to]
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.chain.len()
+    }
+
+    pub fn chain_count(&self) -> usize {
+        self.chain_count
+    }
+
+    /*
+    Binary format, all integers little-endian:
+
+        "RGRI" | version: u8 | nodes: u64 | chains: u64
+        | chain: [u32; nodes] | position: [u32; nodes] | reach: [u32; nodes * chains]
+    */
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&(self.chain.len() as u64).to_le_bytes())?;
+        writer.write_all(&(self.chain_count as u64).to_le_bytes())?;
+
+        for v in self
+            .chain
+            .iter()
+            .chain(self.position.iter())
+            .chain(self.reach.iter())
+        {
+            writer.write_all(&v.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a reachability index"));
+        }
+
+        let mut version = [0u8];
+        reader.read_exact(&mut version)?;
+        if version[0] != VERSION {
+            return Err(invalid_data("unsupported reachability index version"));
+        }
+
+        let n = read_u64(reader)? as usize;
+        let chain_count = read_u64(reader)? as usize;
+        let reach_len = n
+            .checked_mul(chain_count)
+            .ok_or_else(|| invalid_data("reachability index is too big"))?;
+
+        let chain = read_u32s(reader, n)?;
+        let position = read_u32s(reader, n)?;
+        let reach = read_u32s(reader, reach_len)?;
+
+        if chain.iter().any(|c| *c as usize >= chain_count) {
+            return Err(invalid_data("corrupted reachability index"));
+        }
+
+        Ok(ReachIndex {
+            chain,
+            position,
+            chain_count,
+            reach,
+        })
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // Topological order using Kahn's algorithm, `None` if there's a cycle.
+    pub(crate) fn kahn_order(&self) -> Option<Vec<NodeIndex>> {
+        let mut in_degree: Vec<usize> = self.incoming.iter().map(|adj| adj.len()).collect();
+        let mut ready: Vec<NodeIndex> = (0..self.nodes.len())
+            .filter(|n| in_degree[*n] == 0)
+            .rev()
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        while let Some(node_idx) = ready.pop() {
+            order.push(node_idx);
+            for succ in self.outgoing[node_idx].iter().rev().copied() {
+                in_degree[succ] -= 1;
+                if in_degree[succ] == 0 {
+                    ready.push(succ);
+                }
+            }
+        }
+
+        if order.len() == self.nodes.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_u32s<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<u32>> {
+    let mut values = Vec::new();
+    let mut buf = [0u8; 4];
+    for _ in 0..len {
+        reader.read_exact(&mut buf)?;
+        values.push(u32::from_le_bytes(buf));
+    }
+    Ok(values)
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::hash::Hash;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::graph::{Edge, EdgeIndex, Graph, GraphKind};
+
+/*
+`serde` support (the `serde` feature). A graph is written as its node, edge
+and edge payload arrays in index order, so indexes survive a round trip,
+plus the kind, the attributes and the turn restrictions. Derived data
+(adjacency lists, the dedup index) is rebuilt on load, the node validator,
+limits and live view aren't part of the data and start unset. `Blob`
+payloads are copied out of the input, `Graph::from_bytes_borrowed` is the
+way to load them without a copy.
+*/
+#[derive(Serialize)]
+struct GraphRef<'g, N, E> {
+    kind: GraphKind,
+    attrs: &'g BTreeMap<String, String>,
+    nodes: &'g [N],
+    edges: &'g [Edge],
+    edge_weights: &'g [E],
+    // sorted, so the output doesn't depend on hash order
+    turn_restrictions: Vec<(EdgeIndex, EdgeIndex)>,
+}
+
+#[derive(Deserialize)]
+struct GraphData<N, E> {
+    kind: GraphKind,
+    #[serde(default)]
+    attrs: BTreeMap<String, String>,
+    nodes: Vec<N>,
+    edges: Vec<Edge>,
+    edge_weights: Vec<E>,
+    #[serde(default)]
+    turn_restrictions: Vec<(EdgeIndex, EdgeIndex)>,
+}
+
+impl<N, E, const A: usize> Serialize for Graph<N, E, A>
+where
+    N: Serialize,
+    E: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut turn_restrictions: Vec<(EdgeIndex, EdgeIndex)> =
+            self.turn_restrictions.iter().copied().collect();
+        turn_restrictions.sort_unstable();
+
+        GraphRef {
+            kind: self.kind,
+            attrs: &self.attrs,
+            nodes: &self.nodes,
+            edges: &self.edges,
+            edge_weights: &self.edge_weights,
+            turn_restrictions,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, N, E, const A: usize> Deserialize<'de> for Graph<N, E, A>
+where
+    N: Deserialize<'de> + Hash,
+    E: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data: GraphData<N, E> = GraphData::deserialize(deserializer)?;
+
+        if data.edge_weights.len() != data.edges.len() {
+            return Err(D::Error::custom(format!(
+                "{} edges but {} edge weights",
+                data.edges.len(),
+                data.edge_weights.len()
+            )));
+        }
+        if let Some(edge) = data
+            .edges
+            .iter()
+            .find(|edge| edge.from >= data.nodes.len() || edge.to >= data.nodes.len())
+        {
+            return Err(D::Error::custom(format!(
+                "edge {:?} points to a node that doesn't exist",
+                edge
+            )));
+        }
+        if let Some((in_edge, out_edge)) =
+            data.turn_restrictions.iter().find(|(in_edge, out_edge)| {
+                data.edges
+                    .get(*in_edge)
+                    .zip(data.edges.get(*out_edge))
+                    .is_none_or(|(in_edge, out_edge)| in_edge.to != out_edge.from)
+            })
+        {
+            return Err(D::Error::custom(format!(
+                "invalid turn restriction from edge {} to edge {}",
+                in_edge, out_edge
+            )));
+        }
+
+        let mut graph = Graph::with_kind(data.kind);
+        graph.attrs = data.attrs;
+        graph.nodes = data.nodes;
+        graph.node_lookup.catch_up(&graph.nodes);
+        graph.edges = data.edges;
+        graph.edge_weights = data.edge_weights;
+        graph.turn_restrictions = data.turn_restrictions.into_iter().collect();
+        graph.rebuild_adjacency();
+        Ok(graph)
+    }
+}
@@ -0,0 +1,313 @@
+use std::f64::consts::PI;
+use std::fmt::{self, Debug, Write as _};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use crate::graph::Graph;
+
+/*
+Live view of a graph in the browser, for debugging algorithms that modify a
+graph step by step.
+
+`watch_and_serve` starts a small HTTP server on a background thread:
+
+- `/` is a page showing the graph, updated in place as it changes
+- `/graph.svg` is the current drawing
+- `/events` is a server-sent events stream with a new drawing every time the
+  graph changes (the page listens to it)
+
+The graph re-renders itself after every mutation (adding nodes and edges,
+removals, reorderings...) while it's being served, which costs O(V + E) per
+change, so don't leave it on for big batch loads. Clients that fall behind
+skip straight to the latest drawing. Changes made through `edge_weight_mut`
+aren't seen (only the structure and the node payloads are drawn anyway).
+
+The server stops when the graph is dropped or on `stop_serving`.
+*/
+
+const INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>rust-graph</title></head>
+<body>
+<div id="graph"></div>
+<script>
+const view = document.getElementById("graph");
+new EventSource("/events").onmessage = (event) => { view.innerHTML = event.data; };
+</script>
+</body>
+</html>
+"#;
+
+const SIZE: f64 = 600.0;
+const NODE_RADIUS: f64 = 18.0;
+const MAX_LABEL: usize = 24;
+
+// The latest drawing, `version` goes up by one on every change.
+struct Frame {
+    version: u64,
+    svg: String,
+    closed: bool,
+}
+
+type Shared = Arc<(Mutex<Frame>, Condvar)>;
+
+pub(crate) struct LiveView<N, E, const A: usize> {
+    addr: SocketAddr,
+    shared: Shared,
+    // `render_svg` with the `N: Debug` bound already resolved, so mutating
+    // methods don't need it
+    render: fn(&Graph<N, E, A>) -> String,
+}
+
+impl<N, E, const A: usize> LiveView<N, E, A> {
+    pub(crate) fn publish(&self, graph: &Graph<N, E, A>) {
+        let svg = (self.render)(graph);
+        let (frame, changed) = &*self.shared;
+        let mut frame = frame.lock().unwrap();
+        frame.version += 1;
+        frame.svg = svg;
+        changed.notify_all();
+    }
+}
+
+impl<N, E, const A: usize> Debug for LiveView<N, E, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LiveView")
+            .field("addr", &self.addr)
+            .finish()
+    }
+}
+
+impl<N, E, const A: usize> Drop for LiveView<N, E, A> {
+    fn drop(&mut self) {
+        let (frame, changed) = &*self.shared;
+        frame.lock().unwrap().closed = true;
+        changed.notify_all();
+
+        // the accept loop is blocked until someone connects
+        let mut wake = self.addr;
+        if wake.ip().is_unspecified() {
+            wake.set_ip(match wake.ip() {
+                IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            });
+        }
+        let _ = TcpStream::connect(wake);
+    }
+}
+
+impl<N: Debug, E, const A: usize> Graph<N, E, A> {
+    /*
+    Serve a live view of the graph on `addr` (see the module docs), returning
+    the address the server is listening on, handy with port 0. Serving again
+    replaces the previous server.
+    */
+    pub fn watch_and_serve(&mut self, addr: impl ToSocketAddrs) -> io::Result<SocketAddr> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let shared: Shared = Arc::new((
+            Mutex::new(Frame {
+                version: 0,
+                svg: render_svg(self),
+                closed: false,
+            }),
+            Condvar::new(),
+        ));
+
+        let accept_shared = shared.clone();
+        thread::spawn(move || accept_loop(listener, accept_shared));
+
+        self.live_view = Some(LiveView {
+            addr: local_addr,
+            shared,
+            render: render_svg::<N, E, A>,
+        });
+        Ok(local_addr)
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    pub fn stop_serving(&mut self) {
+        self.live_view = None;
+    }
+}
+
+fn accept_loop(listener: TcpListener, shared: Shared) {
+    for stream in listener.incoming() {
+        if shared.0.lock().unwrap().closed {
+            break;
+        }
+        if let Ok(stream) = stream {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                let _ = handle(stream, &shared);
+            });
+        }
+    }
+}
+
+fn handle(mut stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // skip the headers, nothing in them matters here
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    match path {
+        "/" => respond(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            INDEX_HTML,
+        ),
+        "/graph.svg" => {
+            let svg = shared.0.lock().unwrap().svg.clone();
+            respond(&mut stream, "200 OK", "image/svg+xml", &svg)
+        }
+        "/events" => stream_events(&mut stream, shared),
+        _ => respond(&mut stream, "404 Not Found", "text/plain", "not found\n"),
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+// Send the current drawing, then a new one after every change, until the
+// client goes away or the server stops.
+fn stream_events(stream: &mut TcpStream, shared: &Shared) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n"
+    )?;
+
+    let (frame, changed) = &**shared;
+    let mut sent: Option<u64> = None;
+    loop {
+        let (version, svg) = {
+            let mut frame = frame.lock().unwrap();
+            while !frame.closed && sent == Some(frame.version) {
+                frame = changed.wait(frame).unwrap();
+            }
+            if frame.closed {
+                return Ok(());
+            }
+            (frame.version, frame.svg.clone())
+        };
+
+        // the drawing never contains newlines, so it fits in one `data:` line
+        write!(stream, "id: {}\ndata: {}\n\n", version, svg)?;
+        stream.flush()?;
+        sent = Some(version);
+    }
+}
+
+// Nodes on a circle, in index order, edges as arrows between them.
+fn render_svg<N: Debug, E, const A: usize>(graph: &Graph<N, E, A>) -> String {
+    let n = graph.nodes.len();
+    let center = SIZE / 2.0;
+    let ring = center - 3.0 * NODE_RADIUS;
+    let position = |node_idx: usize| {
+        let angle = 2.0 * PI * node_idx as f64 / n.max(1) as f64 - PI / 2.0;
+        (center + ring * angle.cos(), center + ring * angle.sin())
+    };
+
+    let mut svg = String::new();
+    write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}" font-family="sans-serif" font-size="11">"#,
+        SIZE
+    )
+    .unwrap();
+    svg.push_str(
+        r##"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="#555"/></marker></defs>"##,
+    );
+    if let Some(name) = graph.attr("name") {
+        write!(
+            svg,
+            r#"<text x="10" y="20" font-size="14">{}</text>"#,
+            escape(name)
+        )
+        .unwrap();
+    }
+
+    for edge in graph.edges.iter() {
+        let (x1, y1) = position(edge.from);
+        if edge.from == edge.to {
+            write!(
+                svg,
+                r##"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="none" stroke="#555"/>"##,
+                x1,
+                y1 - NODE_RADIUS,
+                NODE_RADIUS / 1.5
+            )
+            .unwrap();
+            continue;
+        }
+
+        // stop at the border of the circles so the arrow heads are visible
+        let (x2, y2) = position(edge.to);
+        let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(1.0);
+        let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+        write!(
+            svg,
+            r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#555" marker-end="url(#arrow)"/>"##,
+            x1 + dx * NODE_RADIUS,
+            y1 + dy * NODE_RADIUS,
+            x2 - dx * NODE_RADIUS,
+            y2 - dy * NODE_RADIUS
+        )
+        .unwrap();
+    }
+
+    for (node_idx, node) in graph.nodes.iter().enumerate() {
+        let (x, y) = position(node_idx);
+        let mut label = format!("{:?}", node);
+        if label.chars().count() > MAX_LABEL {
+            label = label.chars().take(MAX_LABEL - 1).collect::<String>() + "…";
+        }
+        write!(
+            svg,
+            r##"<g><title>{0}: {1}</title><circle cx="{2:.1}" cy="{3:.1}" r="{4}" fill="#dde8f7" stroke="#3a6ea5"/><text x="{2:.1}" y="{3:.1}" text-anchor="middle" dominant-baseline="central">{0}</text><text x="{2:.1}" y="{5:.1}" text-anchor="middle">{1}</text></g>"##,
+            node_idx,
+            escape(&label),
+            x,
+            y,
+            NODE_RADIUS,
+            y + NODE_RADIUS + 12.0
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' | '\r' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use crate::graph::{Graph, NodeIndex};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    SimRank: two nodes are similar if their in-neighbours are similar.
+
+        s(a, a) = 1
+        s(a, b) = decay / (|I(a)| * |I(b)|) * sum of s(i, j) for i in I(a), j in I(b)
+
+    where I(x) are the predecessors of x (the neighbours in undirected
+    graphs) and nodes without any have a similarity of 0 with everyone else.
+    `decay` is usually 0.6 to 0.8, scores converge quickly (the error after
+    k iterations is at most decay^(k + 1)), 5 or so iterations is typical.
+
+    Returns the full `n x n` matrix, `result[a][b]`. Every iteration sums the
+    rows of predecessors first, so it costs O(n^2 * average degree) instead
+    of O(n^2 * degree^2), but the matrix still takes n^2 floats, see
+    `simrank_top_k` for big graphs.
+    */
+    pub fn simrank(&self, iterations: usize, decay: f64) -> Vec<Vec<f64>> {
+        let n = self.nodes.len();
+        let mut scores = identity(n);
+        for _ in 0..iterations {
+            // partial[a][j] = sum of s(i, j) for i in I(a)
+            let partial: Vec<Vec<f64>> = (0..n)
+                .map(|a| {
+                    let mut row = vec![0.0; n];
+                    for i in self.incoming[a].iter() {
+                        for (sum, score) in row.iter_mut().zip(&scores[*i]) {
+                            *sum += score;
+                        }
+                    }
+                    row
+                })
+                .collect();
+
+            let mut next = identity(n);
+            for a in 0..n {
+                let in_a = self.incoming[a].len();
+                for b in (0..n).filter(|b| *b != a) {
+                    let in_b = self.incoming[b].len();
+                    if in_a == 0 || in_b == 0 {
+                        continue;
+                    }
+                    let sum: f64 = self.incoming[b].iter().map(|j| partial[a][*j]).sum();
+                    next[a][b] = decay * sum / (in_a * in_b) as f64;
+                }
+            }
+            scores = next;
+        }
+        scores
+    }
+
+    /*
+    The `k` most similar nodes of every node (itself excluded, best first,
+    only positive scores), keeping no more than `k` scores per node in
+    between iterations: O(n * k) memory instead of O(n^2).
+
+    It's an approximation, the pairs dropped along the way count as 0 in
+    the next iterations, so scores come out a bit lower than with `simrank`.
+    The larger `k`, the closer they get, with `k >= n - 1` nothing is dropped
+    and the results are the same.
+    */
+    pub fn simrank_top_k(
+        &self,
+        iterations: usize,
+        decay: f64,
+        k: usize,
+    ) -> Vec<Vec<(NodeIndex, f64)>> {
+        let n = self.nodes.len();
+        // off-diagonal scores, s(a, a) = 1 is implied
+        let mut scores: Vec<Vec<(NodeIndex, f64)>> = vec![Vec::new(); n];
+        for _ in 0..iterations {
+            let mut next = Vec::with_capacity(n);
+            for a in 0..n {
+                let in_a = self.incoming[a].len();
+                // sum of s(i, j) for i in I(a), by j
+                let mut partial: HashMap<NodeIndex, f64> = HashMap::new();
+                for i in self.incoming[a].iter() {
+                    *partial.entry(*i).or_insert(0.0) += 1.0;
+                    for (j, score) in scores[*i].iter() {
+                        *partial.entry(*j).or_insert(0.0) += score;
+                    }
+                }
+
+                // j in I(b) means b is a successor of j
+                let mut sums: HashMap<NodeIndex, f64> = HashMap::new();
+                for (j, sum) in partial {
+                    for b in self.outgoing[j].iter().filter(|b| **b != a) {
+                        *sums.entry(*b).or_insert(0.0) += sum;
+                    }
+                }
+
+                let mut row: Vec<(NodeIndex, f64)> = sums
+                    .into_iter()
+                    .map(|(b, sum)| {
+                        let in_b = self.incoming[b].len();
+                        (b, decay * sum / (in_a * in_b) as f64)
+                    })
+                    .filter(|(_, score)| *score > 0.0)
+                    .collect();
+                row.sort_by(|x, y| y.1.total_cmp(&x.1).then(x.0.cmp(&y.0)));
+                row.truncate(k);
+                next.push(row);
+            }
+            scores = next;
+        }
+        scores
+    }
+}
+
+fn identity(n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|a| {
+            let mut row = vec![0.0; n];
+            row[a] = 1.0;
+            row
+        })
+        .collect()
+}
@@ -0,0 +1,272 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::graph::{Graph, NodeIndex};
+use crate::rng::Rng;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SpreadModel {
+    // Every newly activated node gets a single chance to activate each of its
+    // inactive successors, succeeding with `probability`.
+    IndependentCascade {
+        probability: f64,
+    },
+    // Discrete-time SIR epidemic: at every step each infected node infects
+    // each susceptible successor with probability `infection`, and then
+    // recovers with probability `recovery`. Recovered nodes can't be infected
+    // again. Stops when nobody is infected or after `max_steps`.
+    Sir {
+        infection: f64,
+        recovery: f64,
+        max_steps: usize,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum State {
+    Susceptible,
+    Infected,
+    Recovered,
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Run one simulation of `model` starting from the `seeds` (infected at time 0)
+    and return the time at which every node got infected/activated, `None` for
+    the nodes that never did. Spreading follows edge direction.
+
+    The same `rng_seed` always gives the same result. To estimate expected
+    spread, run it with several seeds and average.
+    */
+    pub fn simulate(
+        &self,
+        model: SpreadModel,
+        seeds: &[NodeIndex],
+        rng_seed: u64,
+    ) -> Vec<Option<usize>> {
+        let mut rng = Rng::new(rng_seed);
+
+        match model {
+            SpreadModel::IndependentCascade { probability } => {
+                self.independent_cascade(probability, seeds, &mut rng)
+            }
+            SpreadModel::Sir {
+                infection,
+                recovery,
+                max_steps,
+            } => self.sir(infection, recovery, max_steps, seeds, &mut rng),
+        }
+    }
+
+    // Amount of nodes that end up infected/activated in a single simulation.
+    pub fn spread(&self, model: SpreadModel, seeds: &[NodeIndex], rng_seed: u64) -> usize {
+        self.simulate(model, seeds, rng_seed)
+            .iter()
+            .filter(|t| t.is_some())
+            .count()
+    }
+
+    /*
+    Greedy influence maximization: pick `k` seed nodes, one at a time, each
+    time taking the node that adds the most expected spread (estimated with
+    `samples` simulations of `model`).
+
+    Uses CELF's lazy evaluation: spread is submodular, so the gain of a node
+    can only go down as more seeds are picked. Gains are kept in a max-heap,
+    and only the top one is recomputed until it stays at the top.
+
+    For independent cascades every sample is a live-edge world drawn up
+    front (one coin per edge, sample `i` with rng seed `i`) and the spread of
+    a seed set is what it reaches through live edges, so every seed set is
+    evaluated on the same worlds and gains never go negative. SIR isn't
+    equivalent to any single draw of edges, sample `i` is simply run with
+    rng seed `i` there. Either way the result is deterministic. Ties go to
+    the lowest node index. Returns fewer than `k` nodes if the graph is
+    smaller than that.
+    */
+    pub fn greedy_influence_maximization(
+        &self,
+        k: usize,
+        model: SpreadModel,
+        samples: usize,
+    ) -> Vec<NodeIndex> {
+        let mut seeds: Vec<NodeIndex> = Vec::with_capacity(k);
+        // spreads are summed over all the samples instead of averaged, so
+        // gains are exact integers
+        let mut current = 0;
+        let worlds = match model {
+            SpreadModel::IndependentCascade { probability } => {
+                Some(self.live_edge_worlds(probability, samples))
+            }
+            SpreadModel::Sir { .. } => None,
+        };
+        let total_spread = |seeds: &[NodeIndex]| match &worlds {
+            Some(worlds) => worlds.iter().map(|live| reached_count(live, seeds)).sum(),
+            None => self.total_spread(model, seeds, samples),
+        };
+
+        // (gain, node, amount of seeds when the gain was computed)
+        let mut heap: BinaryHeap<(usize, Reverse<NodeIndex>, usize)> = BinaryHeap::new();
+        for node_idx in 0..self.nodes.len() {
+            let gain = total_spread(&[node_idx]);
+            heap.push((gain, Reverse(node_idx), 0));
+        }
+
+        while seeds.len() < k {
+            let Some((gain, Reverse(node_idx), round)) = heap.pop() else {
+                break;
+            };
+
+            if round == seeds.len() {
+                seeds.push(node_idx);
+                current += gain;
+                continue;
+            }
+
+            seeds.push(node_idx);
+            // can't go negative on live-edge worlds, but SIR runs aren't
+            // monotone in the seeds
+            let gain = total_spread(&seeds).saturating_sub(current);
+            seeds.pop();
+            heap.push((gain, Reverse(node_idx), seeds.len()));
+        }
+
+        seeds
+    }
+
+    fn total_spread(&self, model: SpreadModel, seeds: &[NodeIndex], samples: usize) -> usize {
+        (0..samples as u64)
+            .map(|rng_seed| self.spread(model, seeds, rng_seed))
+            .sum()
+    }
+
+    // `samples` random subgraphs keeping every edge with `probability`, as
+    // successor lists. Running a cascade on one is the same as reaching
+    // through it.
+    fn live_edge_worlds(&self, probability: f64, samples: usize) -> Vec<Vec<Vec<NodeIndex>>> {
+        (0..samples as u64)
+            .map(|rng_seed| {
+                let mut rng = Rng::new(rng_seed);
+                self.outgoing
+                    .iter()
+                    .map(|successors| {
+                        successors
+                            .iter()
+                            .copied()
+                            .filter(|_| rng.chance(probability))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn independent_cascade(
+        &self,
+        probability: f64,
+        seeds: &[NodeIndex],
+        rng: &mut Rng,
+    ) -> Vec<Option<usize>> {
+        let mut times: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut frontier: Vec<NodeIndex> = Vec::new();
+
+        for seed in seeds.iter().copied() {
+            if seed < self.nodes.len() && times[seed].is_none() {
+                times[seed] = Some(0);
+                frontier.push(seed);
+            }
+        }
+
+        let mut t = 0;
+        while !frontier.is_empty() {
+            t += 1;
+            let mut next = Vec::new();
+
+            for node_idx in frontier {
+                for succ in self.outgoing[node_idx].iter().copied() {
+                    if times[succ].is_none() && rng.chance(probability) {
+                        times[succ] = Some(t);
+                        next.push(succ);
+                    }
+                }
+            }
+
+            frontier = next;
+        }
+
+        times
+    }
+
+    fn sir(
+        &self,
+        infection: f64,
+        recovery: f64,
+        max_steps: usize,
+        seeds: &[NodeIndex],
+        rng: &mut Rng,
+    ) -> Vec<Option<usize>> {
+        let mut times: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut states = vec![State::Susceptible; self.nodes.len()];
+        let mut infected: Vec<NodeIndex> = Vec::new();
+
+        for seed in seeds.iter().copied() {
+            if seed < self.nodes.len() && states[seed] == State::Susceptible {
+                states[seed] = State::Infected;
+                times[seed] = Some(0);
+                infected.push(seed);
+            }
+        }
+
+        let mut t = 0;
+        while !infected.is_empty() && t < max_steps {
+            t += 1;
+            let mut still_infected = Vec::new();
+            let mut newly_infected = Vec::new();
+
+            for node_idx in infected {
+                for succ in self.outgoing[node_idx].iter().copied() {
+                    if states[succ] == State::Susceptible && rng.chance(infection) {
+                        states[succ] = State::Infected;
+                        times[succ] = Some(t);
+                        newly_infected.push(succ);
+                    }
+                }
+
+                if rng.chance(recovery) {
+                    states[node_idx] = State::Recovered;
+                } else {
+                    still_infected.push(node_idx);
+                }
+            }
+
+            still_infected.extend(newly_infected);
+            infected = still_infected;
+        }
+
+        times
+    }
+}
+
+// Amount of nodes reachable from `seeds` through `live` successor lists.
+fn reached_count(live: &[Vec<NodeIndex>], seeds: &[NodeIndex]) -> usize {
+    let mut reached = vec![false; live.len()];
+    let mut stack: Vec<NodeIndex> = Vec::new();
+    for seed in seeds.iter().copied() {
+        if seed < live.len() && !reached[seed] {
+            reached[seed] = true;
+            stack.push(seed);
+        }
+    }
+
+    let mut count = stack.len();
+    while let Some(node_idx) = stack.pop() {
+        for succ in live[node_idx].iter().copied() {
+            if !reached[succ] {
+                reached[succ] = true;
+                count += 1;
+                stack.push(succ);
+            }
+        }
+    }
+    count
+}
@@ -0,0 +1,56 @@
+use crate::graph::{Adjacency, Edge, Graph, NodeIndex};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    The slice between `sources` and `sinks`: the nodes on some path from a
+    source to a sink (reachable from a source and reaching a sink, both
+    included), with every edge between them. Those edges are on such a path
+    too, so nothing else from the graph is kept.
+
+    The payload of every node of the result is its index in this graph,
+    nodes keep their relative order and edges keep their payload. Indexes
+    out of bounds are ignored.
+    */
+    pub fn slice(&self, sources: &[NodeIndex], sinks: &[NodeIndex]) -> Graph<NodeIndex, E, A>
+    where
+        E: Clone,
+    {
+        let forward = self.closure(sources, &self.outgoing);
+        let backward = self.closure(sinks, &self.incoming);
+
+        let mut sliced = Graph::with_kind(self.kind);
+        let mut new_idx: Vec<Option<NodeIndex>> = vec![None; self.nodes.len()];
+        for node_idx in (0..self.nodes.len()).filter(|v| forward[*v] && backward[*v]) {
+            new_idx[node_idx] = Some(sliced.nodes.len());
+            sliced.nodes.push(node_idx);
+        }
+
+        for (edge, weight) in self.edges.iter().zip(self.edge_weights.iter()) {
+            if let (Some(from), Some(to)) = (new_idx[edge.from], new_idx[edge.to]) {
+                sliced.edges.push(Edge { from, to });
+                sliced.edge_weights.push(weight.clone());
+            }
+        }
+
+        sliced.rebuild_adjacency();
+        sliced
+    }
+
+    // Every node reachable from `starts` following `adjacency`.
+    fn closure(&self, starts: &[NodeIndex], adjacency: &[Adjacency<A>]) -> Vec<bool> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack: Vec<NodeIndex> = starts
+            .iter()
+            .copied()
+            .filter(|v| *v < self.nodes.len())
+            .collect();
+        while let Some(node_idx) = stack.pop() {
+            if seen[node_idx] {
+                continue;
+            }
+            seen[node_idx] = true;
+            stack.extend(adjacency[node_idx].iter().filter(|v| !seen[**v]));
+        }
+        seen
+    }
+}
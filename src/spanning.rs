@@ -0,0 +1,86 @@
+use std::collections::VecDeque;
+
+use crate::graph::{Edge, Graph, NodeIndex};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    The tree a breadth-first search from `root` explores: every node reachable
+    from `root` once, with an edge from the node it was first discovered
+    from. Tree paths from the root are shortest paths (in edges).
+
+    The payload of every tree node is its index in this graph, tree node 0 is
+    `root`. Nodes appear in the order they were discovered.
+    */
+    pub fn bfs_tree(&self, root: NodeIndex) -> Graph<NodeIndex> {
+        let mut tree = Graph::new();
+        if root >= self.nodes.len() {
+            return tree;
+        }
+
+        let mut tree_idx: Vec<Option<NodeIndex>> = vec![None; self.nodes.len()];
+        tree_idx[root] = Some(0);
+        tree.nodes.push(root);
+        let mut queue = VecDeque::from([root]);
+
+        while let Some(node_idx) = queue.pop_front() {
+            for succ in self.outgoing[node_idx].iter().copied() {
+                if tree_idx[succ].is_none() {
+                    tree_idx[succ] = Some(tree.nodes.len());
+                    tree.edges.push(Edge {
+                        from: tree_idx[node_idx].unwrap(),
+                        to: tree.nodes.len(),
+                    });
+                    tree.edge_weights.push(());
+                    tree.nodes.push(succ);
+                    queue.push_back(succ);
+                }
+            }
+        }
+
+        tree.rebuild_adjacency();
+        tree
+    }
+
+    /*
+    The tree a depth-first search from `root` explores, same layout as
+    `bfs_tree`. Successors are followed in adjacency order and every branch
+    is explored to the end before the next one, so nodes appear in preorder.
+    */
+    pub fn dfs_tree(&self, root: NodeIndex) -> Graph<NodeIndex> {
+        let mut tree = Graph::new();
+        if root >= self.nodes.len() {
+            return tree;
+        }
+
+        let mut tree_idx: Vec<Option<NodeIndex>> = vec![None; self.nodes.len()];
+        tree_idx[root] = Some(0);
+        tree.nodes.push(root);
+        // (node, position of the next child to look at)
+        let mut stack: Vec<(NodeIndex, usize)> = vec![(root, 0)];
+
+        while let Some((node_idx, child_pos)) = stack.last_mut() {
+            let node_idx = *node_idx;
+            match self.outgoing[node_idx].get(*child_pos).copied() {
+                Some(succ) => {
+                    *child_pos += 1;
+                    if tree_idx[succ].is_none() {
+                        tree_idx[succ] = Some(tree.nodes.len());
+                        tree.edges.push(Edge {
+                            from: tree_idx[node_idx].unwrap(),
+                            to: tree.nodes.len(),
+                        });
+                        tree.edge_weights.push(());
+                        tree.nodes.push(succ);
+                        stack.push((succ, 0));
+                    }
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+
+        tree.rebuild_adjacency();
+        tree
+    }
+}
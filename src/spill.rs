@@ -0,0 +1,286 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::mem::size_of;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::graph::{Adjacency, Graph, NodeIndex};
+
+/*
+Breadth-first traversal that keeps its memory usage under a budget by
+spilling the visited set and the frontier to a `SpillStore` (a temporary file
+by default).
+
+The store is split in 2 regions:
+
+    [0, bitmap_len)  -> visited bitmap, 1 bit per node
+    [bitmap_len, ..) -> frontier, an append-only log of u64 node indexes
+
+Visited nodes are kept in a `HashSet` until the in-memory state goes over the
+budget, then they are flushed into the bitmap. The frontier is a FIFO: the
+oldest entries live in memory and, once the in-memory queue is full, new
+entries are appended to the log and read back in chunks when the in-memory
+queue runs dry.
+*/
+
+pub trait SpillStore {
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()>;
+
+    // Fill `buf` starting from `offset`. Anything past the end of the store
+    // must be read as zeros.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<()>;
+}
+
+// Anything that looks like a file works as a store, `Cursor<Vec<u8>>` included.
+impl<T: Read + Write + Seek> SpillStore for T {
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        self.seek(SeekFrom::Start(offset))?;
+        self.write_all(buf)
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        self.seek(SeekFrom::Start(offset))?;
+
+        let mut read = 0;
+        while read < buf.len() {
+            match self.read(&mut buf[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        buf[read..].fill(0);
+
+        Ok(())
+    }
+}
+
+// A file in `std::env::temp_dir()` that is deleted when dropped.
+pub struct TempFileStore {
+    file: File,
+    path: PathBuf,
+}
+
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl TempFileStore {
+    pub fn new() -> io::Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "rust-graph-spill-{}-{}",
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+
+        Ok(TempFileStore { file, path })
+    }
+}
+
+impl SpillStore for TempFileStore {
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        self.file.write_at(offset, buf)
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        self.file.read_at(offset, buf)
+    }
+}
+
+impl Drop for TempFileStore {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub struct SpillingBfs<'g, S: SpillStore, const A: usize> {
+    outgoing: &'g [Adjacency<A>],
+    store: S,
+    // max amount of node indexes kept in memory (visited + frontier)
+    max_entries: usize,
+
+    visited: HashSet<NodeIndex>,
+    visited_spilled: bool,
+    bitmap_len: u64,
+
+    frontier: VecDeque<NodeIndex>,
+    // byte offsets inside the frontier log
+    frontier_read: u64,
+    frontier_write: u64,
+}
+
+impl<'g, S: SpillStore, const A: usize> SpillingBfs<'g, S, A> {
+    fn new(outgoing: &'g [Adjacency<A>], start: NodeIndex, memory_budget: usize, store: S) -> Self {
+        let mut bfs = SpillingBfs {
+            outgoing,
+            store,
+            max_entries: (memory_budget / size_of::<NodeIndex>()).max(2),
+            visited: HashSet::new(),
+            visited_spilled: false,
+            bitmap_len: outgoing.len().div_ceil(8) as u64,
+            frontier: VecDeque::new(),
+            frontier_read: 0,
+            frontier_write: 0,
+        };
+
+        if start < outgoing.len() {
+            bfs.visited.insert(start);
+            bfs.frontier.push_back(start);
+        }
+
+        bfs
+    }
+
+    // Did the traversal ever need to touch the store?
+    pub fn spilled(&self) -> bool {
+        self.visited_spilled || self.frontier_write > 0
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    fn next_node(&mut self) -> io::Result<Option<NodeIndex>> {
+        let node_idx = match self.pop_frontier()? {
+            Some(node_idx) => node_idx,
+            None => return Ok(None),
+        };
+
+        for neighbour in self.outgoing[node_idx].iter() {
+            if !self.is_visited(*neighbour)? {
+                self.mark_visited(*neighbour)?;
+                self.push_frontier(*neighbour)?;
+            }
+        }
+
+        Ok(Some(node_idx))
+    }
+
+    fn is_visited(&mut self, node_idx: NodeIndex) -> io::Result<bool> {
+        if self.visited.contains(&node_idx) {
+            return Ok(true);
+        }
+        if !self.visited_spilled {
+            return Ok(false);
+        }
+
+        let mut byte = [0u8];
+        self.store.read_at((node_idx / 8) as u64, &mut byte)?;
+        Ok(byte[0] & (1 << (node_idx % 8)) != 0)
+    }
+
+    fn mark_visited(&mut self, node_idx: NodeIndex) -> io::Result<()> {
+        self.visited.insert(node_idx);
+
+        if self.visited.len() + self.frontier.len() > self.max_entries {
+            self.flush_visited()?;
+        }
+
+        Ok(())
+    }
+
+    // Write all the in-memory visited nodes into the bitmap, one
+    // read-modify-write per touched byte.
+    fn flush_visited(&mut self) -> io::Result<()> {
+        let mut pending: Vec<NodeIndex> = self.visited.drain().collect();
+        pending.sort_unstable();
+
+        let mut i = 0;
+        while i < pending.len() {
+            let offset = pending[i] / 8;
+            let mut byte = [0u8];
+            self.store.read_at(offset as u64, &mut byte)?;
+
+            while i < pending.len() && pending[i] / 8 == offset {
+                byte[0] |= 1 << (pending[i] % 8);
+                i += 1;
+            }
+
+            self.store.write_at(offset as u64, &byte)?;
+        }
+
+        self.visited_spilled = true;
+        Ok(())
+    }
+
+    fn push_frontier(&mut self, node_idx: NodeIndex) -> io::Result<()> {
+        // once something is in the log, everything newer must go there too,
+        // otherwise we would break the FIFO order
+        let log_pending = self.frontier_read < self.frontier_write;
+
+        if log_pending || self.frontier.len() >= self.max_entries / 2 {
+            let offset = self.bitmap_len + self.frontier_write;
+            self.store
+                .write_at(offset, &(node_idx as u64).to_le_bytes())?;
+            self.frontier_write += size_of::<u64>() as u64;
+        } else {
+            self.frontier.push_back(node_idx);
+        }
+
+        Ok(())
+    }
+
+    fn pop_frontier(&mut self) -> io::Result<Option<NodeIndex>> {
+        if self.frontier.is_empty() && self.frontier_read < self.frontier_write {
+            let pending = ((self.frontier_write - self.frontier_read) / 8) as usize;
+            let chunk = pending.min((self.max_entries / 2).max(1));
+
+            let mut buf = vec![0u8; chunk * size_of::<u64>()];
+            self.store
+                .read_at(self.bitmap_len + self.frontier_read, &mut buf)?;
+            self.frontier_read += buf.len() as u64;
+
+            for bytes in buf.chunks_exact(size_of::<u64>()) {
+                let node_idx = u64::from_le_bytes(bytes.try_into().unwrap());
+                self.frontier.push_back(node_idx as NodeIndex);
+            }
+        }
+
+        Ok(self.frontier.pop_front())
+    }
+}
+
+impl<'g, S: SpillStore, const A: usize> Iterator for SpillingBfs<'g, S, A> {
+    type Item = io::Result<NodeIndex>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_node().transpose()
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // BFS from `start` (included) that keeps at most `memory_budget` bytes of
+    // node indexes in memory, the rest goes to `store`.
+    pub fn bfs_with_budget<S: SpillStore>(
+        &self,
+        start: NodeIndex,
+        memory_budget: usize,
+        store: S,
+    ) -> SpillingBfs<'_, S, A> {
+        SpillingBfs::new(&self.outgoing, start, memory_budget, store)
+    }
+
+    // Same as walking `bfs_with_budget` until `end` shows up, spilling to a
+    // temporary file.
+    pub fn can_reach_with_budget(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+        memory_budget: usize,
+    ) -> io::Result<bool> {
+        let store = TempFileStore::new()?;
+
+        for node_idx in self.bfs_with_budget(start, memory_budget, store) {
+            if node_idx? == end {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt;
+use std::hash::Hash;
+
+use crate::graph::{Edge, EdgeIndex, Graph, NodeIndex, DEFAULT_INLINE_ADJACENCY};
+
+/*
+Handle to a node of a `StableGraph`. Unlike a `NodeIndex` it stays valid no
+matter what else gets removed, and once its node is removed it stays invalid
+even if the slot is reused by a new node (the generation changes), so an old
+handle can't silently point to somebody else.
+*/
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct NodeKey {
+    slot: u32,
+    generation: u32,
+}
+
+// The node behind a `NodeKey` has been removed.
+#[derive(PartialEq, Eq, Debug)]
+pub struct StaleKey {
+    pub key: NodeKey,
+}
+
+impl fmt::Display for StaleKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stale node key (slot {}, generation {})",
+            self.key.slot, self.key.generation
+        )
+    }
+}
+
+impl std::error::Error for StaleKey {}
+
+#[derive(Debug)]
+struct Slot {
+    generation: u32,
+    // index in the inner graph, `None` while the slot is free
+    node_idx: Option<NodeIndex>,
+}
+
+/*
+A `Graph` whose nodes are addressed with `NodeKey`s that survive removals.
+
+`Graph::remove_node` moves the last node into the hole, changing its index.
+This keeps a slotmap-style table next to the graph: every node has a slot,
+removed slots go to a freelist and get reused with a bumped generation, and
+the table is patched whenever the inner graph moves a node around.
+
+The inner graph is still dense, so every algorithm runs on `graph()` as
+usual, use `index` to turn keys into the `NodeIndex`es it expects and `key`
+to turn the results back into keys. Those indexes are only valid until the
+next removal.
+*/
+#[derive(Debug)]
+pub struct StableGraph<N, E = (), const A: usize = DEFAULT_INLINE_ADJACENCY> {
+    graph: Graph<N, E, A>,
+    slots: Vec<Slot>,
+    // slot of every node of the inner graph
+    slot_of: Vec<u32>,
+    free: Vec<u32>,
+}
+
+impl<N, E, const A: usize> StableGraph<N, E, A> {
+    pub fn new() -> Self {
+        StableGraph {
+            graph: Graph::with_inline_adjacency(),
+            slots: Vec::new(),
+            slot_of: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    // The dense graph, for running algorithms on.
+    pub fn graph(&self) -> &Graph<N, E, A> {
+        &self.graph
+    }
+
+    // Nodes are deduplicated by value like in `Graph`, adding an existing
+    // payload returns the key it already has.
+    pub fn add_node(&mut self, node: N) -> NodeKey
+    where
+        N: Hash + PartialEq,
+    {
+        let node_count = self.graph.nodes.len();
+        let node_idx = self.graph.add_node(node);
+        if node_idx < node_count {
+            return self.key(node_idx);
+        }
+
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot as usize].node_idx = Some(node_idx);
+                slot
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node_idx: Some(node_idx),
+                });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        self.slot_of.push(slot);
+        self.key(node_idx)
+    }
+
+    pub fn remove_node(&mut self, key: NodeKey) -> Result<N, StaleKey> {
+        let node_idx = self.index(key)?;
+        let removed = self.graph.remove_node(node_idx).unwrap();
+
+        // the last node now lives at `node_idx`
+        self.slot_of.swap_remove(node_idx);
+        if let Some(moved) = self.slot_of.get(node_idx) {
+            self.slots[*moved as usize].node_idx = Some(node_idx);
+        }
+
+        let slot = &mut self.slots[key.slot as usize];
+        slot.node_idx = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.slot);
+        Ok(removed)
+    }
+
+    pub fn add_edge(&mut self, from: NodeKey, to: NodeKey) -> Result<EdgeIndex, StaleKey>
+    where
+        E: Default,
+    {
+        let edge = Edge {
+            from: self.index(from)?,
+            to: self.index(to)?,
+        };
+        Ok(self.graph.add_edge(edge))
+    }
+
+    pub fn add_weighted_edge(
+        &mut self,
+        from: NodeKey,
+        to: NodeKey,
+        weight: E,
+    ) -> Result<EdgeIndex, StaleKey> {
+        let edge = Edge {
+            from: self.index(from)?,
+            to: self.index(to)?,
+        };
+        Ok(self.graph.add_weighted_edge(edge, weight))
+    }
+
+    pub fn remove_edge_between(
+        &mut self,
+        from: NodeKey,
+        to: NodeKey,
+    ) -> Result<Option<(Edge, E)>, StaleKey> {
+        let from = self.index(from)?;
+        let to = self.index(to)?;
+        Ok(self.graph.remove_edge_between(from, to))
+    }
+
+    pub fn node(&self, key: NodeKey) -> Result<&N, StaleKey> {
+        Ok(&self.graph.nodes[self.index(key)?])
+    }
+
+    pub fn contains(&self, key: NodeKey) -> bool {
+        self.index(key).is_ok()
+    }
+
+    pub fn reachable_nodes_from(&self, key: NodeKey) -> Result<Vec<NodeKey>, StaleKey> {
+        let node_idx = self.index(key)?;
+        Ok(self.graph.outgoing[node_idx]
+            .iter()
+            .map(|succ| self.key(*succ))
+            .collect())
+    }
+
+    // Current index of the node in `graph()`.
+    pub fn index(&self, key: NodeKey) -> Result<NodeIndex, StaleKey> {
+        self.slots
+            .get(key.slot as usize)
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.node_idx)
+            .ok_or(StaleKey { key })
+    }
+
+    // Key of the node currently at `node_idx` in `graph()`.
+    pub fn key(&self, node_idx: NodeIndex) -> NodeKey {
+        let slot = self.slot_of[node_idx];
+        NodeKey {
+            slot,
+            generation: self.slots[slot as usize].generation,
+        }
+    }
+}
+
+impl<N, E, const A: usize> Default for StableGraph<N, E, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,126 @@
+use crate::graph::{Edge, Graph, NodeIndex};
+use crate::traits::{GraphBase, IntoNodeIdentifiers, Neighbors};
+
+/*
+Read-only graph over borrowed slices, for small topologies that are known at
+compile time (lookup tables, state machines...). `new` is a `const fn`, so the
+whole graph can live in a `static` and nothing gets built or allocated at
+runtime:
+
+    static PIPELINE: StaticGraph<&str> = StaticGraph::new(
+        &["fetch", "decode", "execute"],
+        &[Edge { from: 0, to: 1 }, Edge { from: 1, to: 2 }],
+    );
+
+There are no adjacency lists, neighbour queries scan the edge slice (like
+`ArenaGraph`), and they return iterators instead of `Vec`s so they don't
+allocate either. Nodes aren't deduplicated, every slot is its own node. Use
+`to_graph()` for the algorithms that only exist on `Graph`.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct StaticGraph<'a, N> {
+    nodes: &'a [N],
+    edges: &'a [Edge],
+}
+
+impl<'a, N> StaticGraph<'a, N> {
+    // Panics (at compile time, for a `const` or `static`) if an edge points
+    // to a node that doesn't exist.
+    pub const fn new(nodes: &'a [N], edges: &'a [Edge]) -> Self {
+        let mut i = 0;
+        while i < edges.len() {
+            assert!(
+                edges[i].from < nodes.len() && edges[i].to < nodes.len(),
+                "edge points to a node that doesn't exist"
+            );
+            i += 1;
+        }
+
+        StaticGraph { nodes, edges }
+    }
+
+    pub const fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub const fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn node(&self, node_idx: NodeIndex) -> Option<&'a N> {
+        self.nodes.get(node_idx)
+    }
+
+    pub fn nodes(&self) -> &'a [N] {
+        self.nodes
+    }
+
+    pub fn edges(&self) -> &'a [Edge] {
+        self.edges
+    }
+
+    pub fn find_node_idx(&self, node: &N) -> Option<NodeIndex>
+    where
+        N: PartialEq,
+    {
+        self.nodes.iter().position(|current| current == node)
+    }
+
+    pub fn reachable_nodes_from(
+        &self,
+        node_idx: NodeIndex,
+    ) -> impl Iterator<Item = NodeIndex> + 'a {
+        self.edges
+            .iter()
+            .filter(move |e| e.from == node_idx)
+            .map(|e| e.to)
+    }
+
+    pub fn nodes_that_can_reach(
+        &self,
+        node_idx: NodeIndex,
+    ) -> impl Iterator<Item = NodeIndex> + 'a {
+        self.edges
+            .iter()
+            .filter(move |e| e.to == node_idx)
+            .map(|e| e.from)
+    }
+
+    // Nodes without outgoing edges, see `Graph::boundary`.
+    pub fn boundary(&self) -> impl Iterator<Item = NodeIndex> + 'a {
+        let edges = self.edges;
+        (0..self.nodes.len()).filter(move |node_idx| edges.iter().all(|e| e.from != *node_idx))
+    }
+
+    // Copy into a heap-allocated `Graph`. Node and edge indexes are preserved.
+    pub fn to_graph(&self) -> Graph<N>
+    where
+        N: Clone,
+    {
+        let mut g = Graph::new();
+        g.nodes = self.nodes.to_vec();
+        for edge in self.edges.iter() {
+            g.edges.push(Edge {
+                from: edge.from,
+                to: edge.to,
+            });
+            g.edge_weights.push(());
+        }
+        g.rebuild_adjacency();
+        g
+    }
+}
+
+impl<N> GraphBase for StaticGraph<'_, N> {
+    fn node_bound(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+impl<N> IntoNodeIdentifiers for StaticGraph<'_, N> {}
+
+impl<N> Neighbors for StaticGraph<'_, N> {
+    fn neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.reachable_nodes_from(node_idx)
+    }
+}
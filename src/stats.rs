@@ -0,0 +1,59 @@
+use crate::graph::Graph;
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Shannon entropy (in bits) of the degree distribution: 0 when every node
+    has the same degree, higher the more spread out degrees are (a ring of 8
+    nodes has 0, a star of 8 nodes about 0.54). Degrees are `degree()`, in
+    plus out in directed graphs.
+    */
+    pub fn degree_entropy(&self) -> f64 {
+        let n = self.nodes.len();
+        let mut counts: Vec<usize> = Vec::new();
+        for node_idx in 0..n {
+            let degree = self.degree(node_idx);
+            if degree >= counts.len() {
+                counts.resize(degree + 1, 0);
+            }
+            counts[degree] += 1;
+        }
+
+        counts
+            .iter()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let p = *count as f64 / n as f64;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /*
+    Von Neumann entropy of the graph (the entropy of its normalized
+    Laplacian seen as a density matrix), with the quadratic approximation of
+    Han et al. (2012) so it takes O(n + m) instead of an eigendecomposition:
+
+        S ~ 1 - 1/n - 1/n^2 * sum over edges u-v of 1 / (d(u) * d(v))
+
+    Between 0 and 1, edges between low degree nodes lower it the most.
+    Direction is ignored and self-loops or edges in both directions count
+    once, like in `triangle_count`. 0 for the empty graph.
+    */
+    pub fn von_neumann_entropy(&self) -> f64 {
+        let n = self.nodes.len();
+        if n == 0 {
+            return 0.0;
+        }
+
+        let neighbours = self.undirected_neighbours();
+        let mut sum = 0.0;
+        for (u, adjacent) in neighbours.iter().enumerate() {
+            for v in adjacent.iter().filter(|v| **v > u) {
+                sum += 1.0 / (adjacent.len() * neighbours[*v].len()) as f64;
+            }
+        }
+
+        let n = n as f64;
+        1.0 - 1.0 / n - sum / (n * n)
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BinaryHeap;
+
+use crate::graph::{Edge, Graph, NodeIndex};
+use crate::measure::{Measure, MinScored};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Dijkstra where the cost of an edge depends on when it's taken, e.g. a
+    transit network where the wait for the next departure depends on the time
+    you get to the stop.
+
+    `cost(edge, payload, t)` is how long it takes to go through `edge` when
+    arriving at its start at time `t` (waiting included), `None` if it can't
+    be taken from `t` on (no more departures that day). Times and costs are
+    the same `Measure`, e.g. minutes since midnight or `Duration`s.
+
+    Leaving `start` at `departure`, returns the earliest arrival time at `end`
+    and the path, `start` and `end` included. Assumes waiting never pays off
+    (FIFO: arriving later at an edge never gets you to its end earlier), which
+    holds for schedules where you can always take a later connection.
+    */
+    pub fn time_dependent_dijkstra<K: Measure>(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+        departure: K,
+        cost: impl Fn(&Edge, &E, K) -> Option<K>,
+    ) -> Option<(K, Vec<NodeIndex>)> {
+        let n = self.nodes.len();
+        if start >= n || end >= n {
+            return None;
+        }
+
+        let mut successors: Vec<Vec<(Edge, &E)>> = (0..n).map(|_| Vec::new()).collect();
+        for (edge_idx, arc) in self.arcs() {
+            successors[arc.from].push((arc, &self.edge_weights[edge_idx]));
+        }
+
+        let mut arrivals: Vec<Option<K>> = vec![None; n];
+        let mut predecessors: Vec<Option<NodeIndex>> = vec![None; n];
+        let mut settled = vec![false; n];
+        let mut heap = BinaryHeap::new();
+
+        arrivals[start] = Some(departure);
+        heap.push(MinScored(departure, start));
+
+        while let Some(MinScored(time, node_idx)) = heap.pop() {
+            if settled[node_idx] {
+                continue;
+            }
+            settled[node_idx] = true;
+
+            if node_idx == end {
+                let mut path = vec![end];
+                let mut current = end;
+                while let Some(pred) = predecessors[current] {
+                    path.push(pred);
+                    current = pred;
+                }
+                path.reverse();
+                return Some((time, path));
+            }
+
+            for (edge, weight) in successors[node_idx].iter() {
+                if settled[edge.to] {
+                    continue;
+                }
+                let Some(duration) = cost(edge, weight, time) else {
+                    continue;
+                };
+                let arrival = time.add(duration);
+                if arrivals[edge.to].is_none_or(|current| arrival < current) {
+                    arrivals[edge.to] = Some(arrival);
+                    predecessors[edge.to] = Some(node_idx);
+                    heap.push(MinScored(arrival, edge.to));
+                }
+            }
+        }
+
+        None
+    }
+}
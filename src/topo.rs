@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::graph::{Graph, NodeIndex};
+
+// The graph isn't a DAG. `cycle` lists the nodes of one of its cycles in edge
+// order, starting from its lowest node index. The last one has an edge back to
+// the first (a self-loop is `[n]`).
+#[derive(PartialEq, Eq, Debug)]
+pub struct CycleError {
+    pub cycle: Vec<NodeIndex>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle through nodes {:?}", self.cycle)
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Kahn's algorithm. Every node comes before all the nodes it has edges to,
+    ties are broken by node index so the order is stable.
+
+    Repeatedly take a node nothing points to anymore and "remove" its outgoing
+    edges. If nodes are left over when there's nothing to take, every one of
+    them still has an incoming edge from another leftover node, so walking
+    those edges backwards has to go around a cycle, that's the error.
+    */
+    pub fn topological_sort(&self) -> Result<Vec<NodeIndex>, CycleError> {
+        let n = self.nodes.len();
+        let mut in_degrees: Vec<usize> = self.incoming.iter().map(|preds| preds.len()).collect();
+        let mut ready: VecDeque<NodeIndex> = (0..n).filter(|idx| in_degrees[*idx] == 0).collect();
+        let mut order = Vec::with_capacity(n);
+
+        while let Some(node_idx) = ready.pop_front() {
+            order.push(node_idx);
+            for succ in self.outgoing[node_idx].iter().copied() {
+                in_degrees[succ] -= 1;
+                if in_degrees[succ] == 0 {
+                    ready.push_back(succ);
+                }
+            }
+        }
+
+        if order.len() == n {
+            return Ok(order);
+        }
+
+        let leftover = (0..n).find(|idx| in_degrees[*idx] > 0).unwrap();
+        Err(CycleError {
+            cycle: self.cycle_behind(leftover, &in_degrees),
+        })
+    }
+
+    /*
+    One cycle of the graph, `None` if it's a DAG. The nodes are in edge order
+    and the last one has an edge back to the first, so the cycle can be
+    printed as is ("a -> b -> c -> a"). Use `simple_cycles` to get all of
+    them.
+    */
+    pub fn find_cycle(&self) -> Option<Vec<NodeIndex>> {
+        self.topological_sort().err().map(|err| err.cycle)
+    }
+
+    // Walk backwards from `start` through nodes with a leftover in-degree
+    // until one repeats, the nodes since its first visit are a cycle.
+    fn cycle_behind(&self, start: NodeIndex, in_degrees: &[usize]) -> Vec<NodeIndex> {
+        let mut position: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut walk = Vec::new();
+        let mut current = start;
+
+        while position[current].is_none() {
+            position[current] = Some(walk.len());
+            walk.push(current);
+            current = self.incoming[current]
+                .iter()
+                .copied()
+                .find(|pred| in_degrees[*pred] > 0)
+                .expect("leftover nodes have a leftover predecessor");
+        }
+
+        let mut cycle = walk.split_off(position[current].unwrap());
+        cycle.reverse();
+        // same rotation as `simple_cycles`
+        let lowest = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap();
+        cycle.rotate_left(lowest);
+        cycle
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::dijkstra::dijkstra_search;
+use crate::graph::{Graph, NodeIndex};
+use crate::measure::Measure;
+
+/*
+Step by step record of a traversal, to see why it ended up with the path it
+did. The `_traced` variants of the traversals (`shortest_path_traced`,
+`dijkstra_traced`) return one next to their usual result, the untraced ones
+don't pay for building it.
+
+A traversal emits, in order:
+
+- `Expand` every time it takes a node out of its frontier, with the frontier
+  and the visited set as they were at that point
+- `Edge` for every edge it looks at while expanding that node, `chosen` if it
+  was followed (it reached a new node, or found a cheaper way to one)
+- `Done` once, with the path it returns
+
+`Trace::write_json_lines` exports the events, one JSON object per line, for
+replaying them somewhere else.
+*/
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum TraceEvent {
+    Expand {
+        node: NodeIndex,
+        // sorted, without duplicates
+        frontier: Vec<NodeIndex>,
+        visited: Vec<NodeIndex>,
+    },
+    Edge {
+        from: NodeIndex,
+        to: NodeIndex,
+        chosen: bool,
+    },
+    Done {
+        path: Option<Vec<NodeIndex>>,
+    },
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::Expand {
+                node,
+                frontier,
+                visited,
+            } => write!(
+                f,
+                "expand {} (frontier {:?}, visited {:?})",
+                node, frontier, visited
+            ),
+            TraceEvent::Edge { from, to, chosen } => {
+                let verdict = if *chosen { "chosen" } else { "skipped" };
+                write!(f, "edge {} -> {} {}", from, to, verdict)
+            }
+            TraceEvent::Done { path: Some(path) } => write!(f, "done, path {:?}", path),
+            TraceEvent::Done { path: None } => write!(f, "done, no path"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Trace {
+    pub events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    // The events in the order they happened.
+    pub fn replay(&self) -> impl Iterator<Item = &TraceEvent> {
+        self.events.iter()
+    }
+
+    // The edges that were followed, in order.
+    pub fn chosen_edges(&self) -> Vec<(NodeIndex, NodeIndex)> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                TraceEvent::Edge {
+                    from,
+                    to,
+                    chosen: true,
+                } => Some((*from, *to)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn write_json_lines(&self, mut out: impl Write) -> io::Result<()> {
+        for event in self.events.iter() {
+            match event {
+                TraceEvent::Expand {
+                    node,
+                    frontier,
+                    visited,
+                } => writeln!(
+                    out,
+                    r#"{{"event":"expand","node":{},"frontier":{:?},"visited":{:?}}}"#,
+                    node, frontier, visited
+                )?,
+                TraceEvent::Edge { from, to, chosen } => writeln!(
+                    out,
+                    r#"{{"event":"edge","from":{},"to":{},"chosen":{}}}"#,
+                    from, to, chosen
+                )?,
+                TraceEvent::Done { path: Some(path) } => {
+                    writeln!(out, r#"{{"event":"done","path":{:?}}}"#, path)?
+                }
+                TraceEvent::Done { path: None } => {
+                    writeln!(out, r#"{{"event":"done","path":null}}"#)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn push(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+}
+
+// Sorted and deduplicated, for the snapshots in `TraceEvent::Expand`.
+pub(crate) fn snapshot(nodes: impl Iterator<Item = NodeIndex>) -> Vec<NodeIndex> {
+    let mut nodes: Vec<NodeIndex> = nodes.collect();
+    nodes.sort_unstable();
+    nodes.dedup();
+    nodes
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    // `shortest_path`, recording every step.
+    pub fn shortest_path_traced(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+    ) -> (Option<Vec<NodeIndex>>, Trace) {
+        let mut trace = Trace::default();
+        let path = self
+            .shortest_path_search(start, end, None, Some(&mut trace))
+            .unwrap_or(None);
+        trace.push(TraceEvent::Done { path: path.clone() });
+        (path, trace)
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Measure,
+{
+    // `dijkstra`, recording every step.
+    pub fn dijkstra_traced(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+    ) -> (Option<(E, Vec<NodeIndex>)>, Trace) {
+        let mut trace = Trace::default();
+        let paths = dijkstra_search(
+            &self.weighted_successors(),
+            start,
+            Some(end),
+            Some(&mut trace),
+        );
+
+        let found = paths
+            .distances
+            .get(end)
+            .copied()
+            .flatten()
+            .and_then(|cost| Some((cost, paths.path_to(end)?)));
+        trace.push(TraceEvent::Done {
+            path: found.as_ref().map(|(_, path)| path.clone()),
+        });
+        (found, trace)
+    }
+}
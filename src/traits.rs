@@ -0,0 +1,236 @@
+use std::collections::VecDeque;
+
+use crate::dijkstra::{dijkstra_search, ShortestPaths};
+use crate::graph::{Graph, NodeIndex};
+use crate::measure::Measure;
+use crate::visit::{Control, DfsEvent};
+
+/*
+What an algorithm needs to know about a graph, so the same code runs on
+`Graph`, `StaticGraph`, `ArenaGraph` or an adjacency structure of your own.
+Nodes are `NodeIndex`es in `0..node_bound()`, the rest is up to the
+backend. Plain adjacency lists work as they are:
+
+    let successors: Vec<Vec<NodeIndex>> = vec![vec![1, 2], vec![2], vec![]];
+    let distances = successors.bfs_distances(0);
+
+    let weighted: Vec<Vec<(NodeIndex, u32)>> = vec![vec![(1, 4)], vec![]];
+    let paths = weighted.shortest_paths(0);
+
+Implement `GraphBase`, `IntoNodeIdentifiers` and `Neighbors` and/or
+`WeightedNeighbors`, and `Algorithms` comes for free.
+*/
+
+pub trait GraphBase {
+    // One past the largest node index.
+    fn node_bound(&self) -> usize;
+}
+
+pub trait IntoNodeIdentifiers: GraphBase {
+    // Every node of the graph. Backends with holes in their indexes (removed
+    // nodes) should skip them.
+    fn node_identifiers(&self) -> impl Iterator<Item = NodeIndex> + '_ {
+        0..self.node_bound()
+    }
+}
+
+pub trait Neighbors: GraphBase {
+    // The nodes an edge leads to from `node_idx`, nothing for nodes that
+    // don't exist.
+    fn neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_;
+}
+
+pub trait WeightedNeighbors: GraphBase {
+    type Weight: Copy;
+
+    // Like `Neighbors::neighbors`, with the payload of the edge.
+    fn weighted_neighbors(
+        &self,
+        node_idx: NodeIndex,
+    ) -> impl Iterator<Item = (NodeIndex, Self::Weight)> + '_;
+}
+
+/*
+The algorithms that only need the traits above. `Graph` has inherent
+methods for these (`depth_first_search`, `dijkstra_all`...) that call into
+here, so it doesn't need the trait in scope.
+*/
+pub trait Algorithms: GraphBase {
+    // See `Graph::depth_first_search`.
+    fn depth_first_search<B>(
+        &self,
+        starts: impl IntoIterator<Item = NodeIndex>,
+        mut visitor: impl FnMut(DfsEvent) -> Control<B>,
+    ) -> Option<B>
+    where
+        Self: Neighbors,
+    {
+        let n = self.node_bound();
+        let mut discovered = vec![false; n];
+        let mut finished = vec![false; n];
+        // (node, the children not looked at yet)
+        let mut stack = Vec::new();
+
+        for start in starts {
+            if start >= n || discovered[start] {
+                continue;
+            }
+
+            discovered[start] = true;
+            match visitor(DfsEvent::Discover(start)) {
+                Control::Break(value) => return Some(value),
+                Control::Prune => {
+                    finished[start] = true;
+                    if let Control::Break(value) = visitor(DfsEvent::Finish(start)) {
+                        return Some(value);
+                    }
+                    continue;
+                }
+                Control::Continue => stack.push((start, self.neighbors(start))),
+            }
+
+            while let Some((node_idx, children)) = stack.last_mut() {
+                let node_idx = *node_idx;
+                let Some(succ) = children.next() else {
+                    stack.pop();
+                    finished[node_idx] = true;
+                    if let Control::Break(value) = visitor(DfsEvent::Finish(node_idx)) {
+                        return Some(value);
+                    }
+                    continue;
+                };
+
+                let event = if !discovered[succ] {
+                    DfsEvent::TreeEdge(node_idx, succ)
+                } else if !finished[succ] {
+                    DfsEvent::BackEdge(node_idx, succ)
+                } else {
+                    DfsEvent::CrossForwardEdge(node_idx, succ)
+                };
+                match visitor(event) {
+                    Control::Break(value) => return Some(value),
+                    Control::Prune => continue,
+                    Control::Continue => {}
+                }
+                if discovered[succ] {
+                    continue;
+                }
+
+                discovered[succ] = true;
+                match visitor(DfsEvent::Discover(succ)) {
+                    Control::Break(value) => return Some(value),
+                    Control::Prune => {
+                        finished[succ] = true;
+                        if let Control::Break(value) = visitor(DfsEvent::Finish(succ)) {
+                            return Some(value);
+                        }
+                    }
+                    Control::Continue => stack.push((succ, self.neighbors(succ))),
+                }
+            }
+        }
+
+        None
+    }
+
+    // Number of edges on the shortest path from `start` to every node,
+    // `None` for the ones it can't reach.
+    fn bfs_distances(&self, start: NodeIndex) -> Vec<Option<usize>>
+    where
+        Self: Neighbors,
+    {
+        let mut distances = vec![None; self.node_bound()];
+        if start >= distances.len() {
+            return distances;
+        }
+
+        distances[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node_idx) = queue.pop_front() {
+            let next = distances[node_idx].map(|distance| distance + 1);
+            for succ in self.neighbors(node_idx) {
+                if distances[succ].is_none() {
+                    distances[succ] = next;
+                    queue.push_back(succ);
+                }
+            }
+        }
+        distances
+    }
+
+    // Dijkstra from `start` to every node, see `Graph::dijkstra_all`.
+    fn shortest_paths(&self, start: NodeIndex) -> ShortestPaths<Self::Weight>
+    where
+        Self: WeightedNeighbors,
+        Self::Weight: Measure,
+    {
+        dijkstra_search(self, start, None, None)
+    }
+}
+
+impl<G: GraphBase + ?Sized> Algorithms for G {}
+
+/*
+`Graph` only implements `Neighbors`: the adjacency lists don't know about
+edge indexes, the payloads are behind the crate's own per-node edge lists.
+Its `dijkstra` and `dijkstra_all` build weighted lists first.
+*/
+impl<N, E, const A: usize> GraphBase for Graph<N, E, A> {
+    fn node_bound(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+impl<N, E, const A: usize> IntoNodeIdentifiers for Graph<N, E, A> {}
+
+impl<N, E, const A: usize> Neighbors for Graph<N, E, A> {
+    fn neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        Graph::neighbors(self, node_idx)
+    }
+}
+
+// `successors[node]` lists, of `NodeIndex`es or of `(NodeIndex, weight)`
+// pairs.
+impl<T> GraphBase for [Vec<T>] {
+    fn node_bound(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> IntoNodeIdentifiers for [Vec<T>] {}
+
+impl Neighbors for [Vec<NodeIndex>] {
+    fn neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.get(node_idx).into_iter().flatten().copied()
+    }
+}
+
+impl<K: Copy> WeightedNeighbors for [Vec<(NodeIndex, K)>] {
+    type Weight = K;
+
+    fn weighted_neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = (NodeIndex, K)> + '_ {
+        self.get(node_idx).into_iter().flatten().copied()
+    }
+}
+
+impl<T> GraphBase for Vec<Vec<T>> {
+    fn node_bound(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> IntoNodeIdentifiers for Vec<Vec<T>> {}
+
+impl Neighbors for Vec<Vec<NodeIndex>> {
+    fn neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.as_slice().neighbors(node_idx)
+    }
+}
+
+impl<K: Copy> WeightedNeighbors for Vec<Vec<(NodeIndex, K)>> {
+    type Weight = K;
+
+    fn weighted_neighbors(&self, node_idx: NodeIndex) -> impl Iterator<Item = (NodeIndex, K)> + '_ {
+        self.as_slice().weighted_neighbors(node_idx)
+    }
+}
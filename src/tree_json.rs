@@ -0,0 +1,153 @@
+use std::fmt::{self, Display};
+
+use serde_json::{json, Value};
+
+use crate::graph::{Edge, Graph, GraphKind, Node, NodeIndex};
+
+/*
+Trees as nested JSON (the `json` feature), the way org charts, file trees
+and d3's `hierarchy` come:
+
+    {
+        "name": "root",
+        "children": [
+            { "name": "a", "children": [{ "name": "a1" }] },
+            { "name": "b" }
+        ]
+    }
+
+Leaves have no `children`. Every level of the tree is two levels of JSON
+(the node and its `children` array) and serde_json refuses documents nested
+128 levels deep, so trees can only be `MAX_DEPTH` edges deep.
+*/
+
+// Deepest a node can be below the root, in edges.
+const MAX_DEPTH: usize = 63;
+
+// A graph `to_nested_json` couldn't write or a document `from_nested_json`
+// couldn't read.
+#[derive(PartialEq, Eq, Debug)]
+pub struct TreeJsonError {
+    pub message: String,
+}
+
+impl fmt::Display for TreeJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid nested JSON tree: {}", self.message)
+    }
+}
+
+impl std::error::Error for TreeJsonError {}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    N: Display,
+{
+    /*
+    The tree below `root`, names from `Display`, children in edge order.
+    Children are the successors of a node, or its neighbours but the parent
+    in undirected graphs. Nodes that can't be reached from `root` are left
+    out, reaching a node twice (a cycle, a node with two parents) or a tree
+    deeper than `from_nested_json` can read back is an error.
+    */
+    pub fn to_nested_json(&self, root: NodeIndex) -> Result<String, TreeJsonError> {
+        let error = |message: String| TreeJsonError { message };
+        if root >= self.nodes.len() {
+            return Err(error(format!("root {} doesn't exist", root)));
+        }
+
+        // preorder, so children come after their parent
+        let mut order: Vec<(NodeIndex, Option<NodeIndex>)> = Vec::new();
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack = vec![(root, None, 0)];
+        while let Some((node_idx, parent, depth)) = stack.pop() {
+            if seen[node_idx] {
+                return Err(error(format!(
+                    "node {} is reached twice from the root, not a tree",
+                    node_idx
+                )));
+            }
+            if depth > MAX_DEPTH {
+                return Err(error(format!(
+                    "node {} is more than {} levels below the root",
+                    node_idx, MAX_DEPTH
+                )));
+            }
+            seen[node_idx] = true;
+            order.push((node_idx, parent));
+            let children = self.outgoing[node_idx]
+                .iter()
+                .filter(|child| self.kind == GraphKind::Directed || Some(**child) != parent);
+            for child in children.rev() {
+                stack.push((*child, Some(node_idx), depth + 1));
+            }
+        }
+
+        // bottom-up, every node is built once all of its children are
+        let mut children: Vec<Vec<Value>> = vec![Vec::new(); self.nodes.len()];
+        let mut tree = Value::Null;
+        for (node_idx, parent) in order.into_iter().rev() {
+            let mut node_json = json!({ "name": self.nodes[node_idx].to_string() });
+            let mut node_children = std::mem::take(&mut children[node_idx]);
+            if !node_children.is_empty() {
+                node_children.reverse();
+                node_json["children"] = Value::Array(node_children);
+            }
+            match parent {
+                Some(parent) => children[parent].push(node_json),
+                None => tree = node_json,
+            }
+        }
+        Ok(tree.to_string())
+    }
+}
+
+impl Graph {
+    /*
+    Read a nested JSON tree, or an array of them (a forest). Nodes are
+    `DataTypes::Text` with their `name` (numbers and booleans as text, an
+    empty string when missing) in preorder, so the first root is node 0.
+    Every node gets an edge to each of its children, in order. Names don't
+    have to be unique, two `README.md` in different folders stay apart.
+    */
+    pub fn from_nested_json(json: &str) -> Result<Graph, TreeJsonError> {
+        let error = |message: String| TreeJsonError { message };
+        let document: Value = serde_json::from_str(json).map_err(|err| error(err.to_string()))?;
+
+        let mut graph = Graph::new();
+        let roots: Vec<&Value> = match &document {
+            Value::Array(roots) => roots.iter().collect(),
+            root => vec![root],
+        };
+        let mut stack: Vec<(&Value, Option<NodeIndex>)> =
+            roots.into_iter().rev().map(|root| (root, None)).collect();
+        while let Some((node_json, parent)) = stack.pop() {
+            let Value::Object(fields) = node_json else {
+                return Err(error(format!("expected a node object, got {}", node_json)));
+            };
+            let name = match fields.get("name") {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(name)) => name.clone(),
+                Some(Value::Number(name)) => name.to_string(),
+                Some(Value::Bool(name)) => name.to_string(),
+                Some(other) => return Err(error(format!("invalid name {}", other))),
+            };
+            let node_idx = graph.add_node_unchecked(Node::from(name));
+            if let Some(parent) = parent {
+                graph.add_edge(Edge {
+                    from: parent,
+                    to: node_idx,
+                });
+            }
+
+            match fields.get("children") {
+                None | Some(Value::Null) => {}
+                Some(Value::Array(children)) => {
+                    stack.extend(children.iter().rev().map(|child| (child, Some(node_idx))));
+                }
+                Some(_) => return Err(error("`children` should be an array".to_string())),
+            }
+        }
+        Ok(graph)
+    }
+}
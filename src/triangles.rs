@@ -0,0 +1,227 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::graph::{Edge, Graph, NodeIndex};
+use crate::rng::Rng;
+
+// Normal quantile for a two-sided 95% interval.
+const Z_95: f64 = 1.96;
+
+/*
+Result of `approximate_triangle_count`: the estimate and a 95% confidence
+interval around it (normal approximation, so only meaningful with a decent
+amount of samples, a few hundred or more).
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TriangleEstimate {
+    pub estimate: f64,
+    pub low: f64,
+    pub high: f64,
+    pub samples: usize,
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Number of triangles, ignoring edge direction: three nodes that are all
+    connected to each other, no matter which way. Self-loops and edges in
+    both directions don't count twice.
+
+    Every edge `u - v` (u < v) counts the common neighbours `w > v`, so each
+    triangle is found once, from its two lowest nodes.
+    */
+    pub fn triangle_count(&self) -> usize {
+        let neighbours = self.undirected_neighbours();
+        let mut triangles = 0;
+        for (u, adjacent) in neighbours.iter().enumerate() {
+            for v in adjacent.iter().copied().filter(|v| *v > u) {
+                triangles += common_neighbours(&neighbours[u], &neighbours[v])
+                    .filter(|w| *w > v)
+                    .count();
+            }
+        }
+        triangles
+    }
+
+    /*
+    Estimate `triangle_count` from `samples` edges picked at random (with
+    replacement), for graphs where intersecting the neighbourhoods of every
+    edge is too slow.
+
+    Every triangle has 3 edges, so the count is `m / 3` times the average
+    amount of common neighbours of an edge, which is estimated from the
+    sampled edges. The interval shrinks with the square root of `samples`.
+    The same `seed` always gives the same estimate.
+    */
+    pub fn approximate_triangle_count(&self, samples: usize, seed: u64) -> TriangleEstimate {
+        assert!(samples > 0, "at least one edge has to be sampled");
+
+        let neighbours = self.undirected_neighbours();
+        let edges: Vec<(NodeIndex, NodeIndex)> = neighbours
+            .iter()
+            .enumerate()
+            .flat_map(|(u, adjacent)| {
+                adjacent
+                    .iter()
+                    .filter(move |v| **v > u)
+                    .map(move |v| (u, *v))
+            })
+            .collect();
+        if edges.is_empty() {
+            return TriangleEstimate {
+                estimate: 0.0,
+                low: 0.0,
+                high: 0.0,
+                samples,
+            };
+        }
+
+        let mut rng = Rng::new(seed);
+        let counts: Vec<f64> = (0..samples)
+            .map(|_| {
+                let (u, v) = edges[rng.below(edges.len())];
+                common_neighbours(&neighbours[u], &neighbours[v]).count() as f64
+            })
+            .collect();
+
+        let mean = counts.iter().sum::<f64>() / samples as f64;
+        let variance = if samples > 1 {
+            counts.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / (samples - 1) as f64
+        } else {
+            0.0
+        };
+
+        let scale = edges.len() as f64 / 3.0;
+        let margin = Z_95 * scale * (variance / samples as f64).sqrt();
+        let estimate = scale * mean;
+        TriangleEstimate {
+            estimate,
+            low: (estimate - margin).max(0.0),
+            high: estimate + margin,
+            samples,
+        }
+    }
+
+    /*
+    The k-truss: the largest subgraph where every edge is in at least `k - 2`
+    triangles of the subgraph itself (direction ignored, like
+    `triangle_count`). Tighter than a k-core, a node with many neighbours
+    that don't know each other doesn't make it.
+
+    Edges below the threshold are peeled one at a time, each removal taking
+    a triangle away from the two other edges of every triangle it was in,
+    until all that's left has enough support. The result keeps the nodes
+    that still have an edge, with their index in this graph as payload (like
+    `slice`), and every edge between two of them whose pair survived, payload
+    included. Self-loops are never part of a truss.
+    */
+    pub fn k_truss(&self, k: usize) -> Graph<NodeIndex, E, A>
+    where
+        E: Clone,
+    {
+        let min_support = k.saturating_sub(2);
+        let mut neighbours: Vec<HashSet<NodeIndex>> = self
+            .undirected_neighbours()
+            .into_iter()
+            .map(|adjacent| adjacent.into_iter().collect())
+            .collect();
+
+        let pair = |u: NodeIndex, v: NodeIndex| (u.min(v), u.max(v));
+        let mut support: HashMap<(NodeIndex, NodeIndex), usize> = HashMap::new();
+        for (u, adjacent) in neighbours.iter().enumerate() {
+            for v in adjacent.iter().copied().filter(|v| *v > u) {
+                let common = adjacent
+                    .iter()
+                    .filter(|w| neighbours[v].contains(w))
+                    .count();
+                support.insert((u, v), common);
+            }
+        }
+
+        let mut weak: Vec<(NodeIndex, NodeIndex)> = support
+            .iter()
+            .filter(|(_, count)| **count < min_support)
+            .map(|(edge, _)| *edge)
+            .collect();
+        while let Some((u, v)) = weak.pop() {
+            if !neighbours[u].remove(&v) {
+                // already peeled
+                continue;
+            }
+            neighbours[v].remove(&u);
+            support.remove(&(u, v));
+
+            let common: Vec<NodeIndex> = neighbours[u]
+                .iter()
+                .copied()
+                .filter(|w| neighbours[v].contains(w))
+                .collect();
+            for w in common {
+                for other in [pair(u, w), pair(v, w)] {
+                    let count = support.get_mut(&other).unwrap();
+                    if *count == min_support {
+                        weak.push(other);
+                    }
+                    *count = count.saturating_sub(1);
+                }
+            }
+        }
+
+        let mut truss = Graph::with_kind(self.kind);
+        let mut new_idx: Vec<Option<NodeIndex>> = vec![None; self.nodes.len()];
+        for node_idx in (0..self.nodes.len()).filter(|v| !neighbours[*v].is_empty()) {
+            new_idx[node_idx] = Some(truss.nodes.len());
+            truss.nodes.push(node_idx);
+        }
+        for (edge, weight) in self.edges.iter().zip(self.edge_weights.iter()) {
+            if edge.from != edge.to && support.contains_key(&pair(edge.from, edge.to)) {
+                truss.edges.push(Edge {
+                    from: new_idx[edge.from].unwrap(),
+                    to: new_idx[edge.to].unwrap(),
+                });
+                truss.edge_weights.push(weight.clone());
+            }
+        }
+
+        truss.rebuild_adjacency();
+        truss
+    }
+
+    // Sorted neighbours of every node in both directions, without the node
+    // itself.
+    pub(crate) fn undirected_neighbours(&self) -> Vec<Vec<NodeIndex>> {
+        (0..self.nodes.len())
+            .map(|node_idx| {
+                let mut adjacent: Vec<NodeIndex> = self.outgoing[node_idx]
+                    .iter()
+                    .chain(self.incoming[node_idx].iter())
+                    .copied()
+                    .filter(|other| *other != node_idx)
+                    .collect();
+                adjacent.sort_unstable();
+                adjacent.dedup();
+                adjacent
+            })
+            .collect()
+    }
+}
+
+// Intersection of two sorted lists.
+fn common_neighbours<'a>(
+    a: &'a [NodeIndex],
+    b: &'a [NodeIndex],
+) -> impl Iterator<Item = NodeIndex> + 'a {
+    let (mut i, mut j) = (0, 0);
+    std::iter::from_fn(move || {
+        while i < a.len() && j < b.len() {
+            match a[i].cmp(&b[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    i += 1;
+                    j += 1;
+                    return Some(a[i - 1]);
+                }
+            }
+        }
+        None
+    })
+}
@@ -0,0 +1,140 @@
+use std::collections::BinaryHeap;
+
+use crate::graph::{Edge, EdgeIndex, Graph, NodeIndex};
+use crate::measure::{Measure, MinScored};
+
+/*
+Turn restrictions: forbidden transitions from one edge to the next at the node
+they share ("no left turn from Main St onto 5th Ave", "this valve can't feed
+that pipe"). The graph only stores them, `dijkstra_with_turn_restrictions` is
+the only search that honors them, every other algorithm ignores them.
+
+Restrictions follow their edges when edge indexes change (`remove_node`,
+`apply_permutation`...), and are dropped with them.
+*/
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Forbid going from `in_edge` straight to `out_edge`. `in_edge` has to end
+    where `out_edge` starts. Returns `false` if the turn was already
+    restricted.
+    */
+    pub fn add_turn_restriction(&mut self, in_edge: EdgeIndex, out_edge: EdgeIndex) -> bool {
+        assert!(
+            in_edge < self.edges.len() && out_edge < self.edges.len(),
+            "edge index out of bounds"
+        );
+        assert!(
+            self.edges[in_edge].to == self.edges[out_edge].from,
+            "edge {} doesn't end where edge {} starts",
+            in_edge,
+            out_edge
+        );
+        self.turn_restrictions.insert((in_edge, out_edge))
+    }
+
+    // Returns `false` if the turn wasn't restricted.
+    pub fn remove_turn_restriction(&mut self, in_edge: EdgeIndex, out_edge: EdgeIndex) -> bool {
+        self.turn_restrictions.remove(&(in_edge, out_edge))
+    }
+
+    pub fn is_turn_allowed(&self, in_edge: EdgeIndex, out_edge: EdgeIndex) -> bool {
+        !self.turn_restrictions.contains(&(in_edge, out_edge))
+    }
+
+    // `map[old edge index]` is the new index of that edge, `None` if it was
+    // removed. Restrictions that lost one of their edges are dropped.
+    pub(crate) fn remap_turn_restrictions(&mut self, map: &[Option<EdgeIndex>]) {
+        if self.turn_restrictions.is_empty() {
+            return;
+        }
+        self.turn_restrictions = self
+            .turn_restrictions
+            .iter()
+            .filter_map(|(in_edge, out_edge)| Some((map[*in_edge]?, map[*out_edge]?)))
+            .collect();
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Measure,
+{
+    /*
+    `dijkstra`, but never taking a restricted turn (see `add_turn_restriction`).
+
+    With restrictions, the best way to reach a node depends on the edge it was
+    reached through, so the search runs over edges instead of nodes: the cost
+    of an edge is the cheapest way to get to its end through it. A path may go
+    through the same node more than once (e.g. around the block to avoid a
+    forbidden left turn).
+
+    Returns the total cost and the nodes of the path, `start` and `end`
+    included.
+    */
+    pub fn dijkstra_with_turn_restrictions(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+    ) -> Option<(E, Vec<NodeIndex>)> {
+        let n = self.nodes.len();
+        if start >= n || end >= n {
+            return None;
+        }
+        if start == end {
+            return Some((E::zero(), vec![start]));
+        }
+
+        // undirected edges can be walked both ways, so the states are arcs
+        // (an edge and a direction), restrictions still apply to edges
+        let arcs: Vec<(EdgeIndex, Edge)> = self.arcs().collect();
+        let m = arcs.len();
+        let mut outgoing_arcs: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (arc_idx, (_, arc)) in arcs.iter().enumerate() {
+            outgoing_arcs[arc.from].push(arc_idx);
+        }
+
+        let mut costs: Vec<Option<E>> = vec![None; m];
+        let mut pred_arc: Vec<Option<usize>> = vec![None; m];
+        let mut settled = vec![false; m];
+        let mut heap = BinaryHeap::new();
+
+        for arc_idx in outgoing_arcs[start].iter().copied() {
+            let cost = self.edge_weights[arcs[arc_idx].0];
+            costs[arc_idx] = Some(cost);
+            heap.push(MinScored(cost, arc_idx));
+        }
+
+        while let Some(MinScored(cost, arc_idx)) = heap.pop() {
+            if settled[arc_idx] {
+                continue;
+            }
+            settled[arc_idx] = true;
+
+            let node_idx = arcs[arc_idx].1.to;
+            if node_idx == end {
+                let mut path = vec![end];
+                let mut current = Some(arc_idx);
+                while let Some(idx) = current {
+                    path.push(arcs[idx].1.from);
+                    current = pred_arc[idx];
+                }
+                path.reverse();
+                return Some((cost, path));
+            }
+
+            for next in outgoing_arcs[node_idx].iter().copied() {
+                if settled[next] || !self.is_turn_allowed(arcs[arc_idx].0, arcs[next].0) {
+                    continue;
+                }
+                let next_cost = cost.add(self.edge_weights[arcs[next].0]);
+                if costs[next].is_none_or(|current| next_cost < current) {
+                    costs[next] = Some(next_cost);
+                    pred_arc[next] = Some(arc_idx);
+                    heap.push(MinScored(next_cost, next));
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,55 @@
+use crate::graph::{Graph, NodeIndex};
+use crate::traits::Algorithms;
+
+// What `depth_first_search` tells its visitor.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DfsEvent {
+    // first time the node is seen
+    Discover(NodeIndex),
+    // `to` is discovered through this edge
+    TreeEdge(NodeIndex, NodeIndex),
+    // `to` is still being explored (it's an ancestor), the edge closes a cycle
+    BackEdge(NodeIndex, NodeIndex),
+    // `to` was already finished: a forward edge to a descendant, or a cross
+    // edge to another branch or an earlier tree
+    CrossForwardEdge(NodeIndex, NodeIndex),
+    // every node reachable from here has been explored
+    Finish(NodeIndex),
+}
+
+// What the visitor wants the search to do next.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Control<B = ()> {
+    Continue,
+    // On `Discover`, don't explore the node's edges. On `TreeEdge`, don't
+    // follow it (the target stays undiscovered). Same as `Continue` on the
+    // other events.
+    Prune,
+    // Stop the whole search, `depth_first_search` returns the value.
+    Break(B),
+}
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Depth-first search that reports every step to `visitor`, which can prune
+    subtrees or stop the search. Useful when iterators aren't enough, e.g.
+    stopping at the first back edge (a cycle), or not exploring past some
+    nodes.
+
+    Starts from every node of `starts` that hasn't been discovered yet, in
+    order (pass `0..node_count()` to cover the whole graph). Edges are
+    looked at in adjacency order, and every one of them is classified
+    (tree, back, cross/forward). Iterative, so deep graphs don't overflow
+    the stack.
+
+    Returns the value of the `Break` that stopped the search, `None` if it
+    ran to the end.
+    */
+    pub fn depth_first_search<B>(
+        &self,
+        starts: impl IntoIterator<Item = NodeIndex>,
+        visitor: impl FnMut(DfsEvent) -> Control<B>,
+    ) -> Option<B> {
+        Algorithms::depth_first_search(self, starts, visitor)
+    }
+}
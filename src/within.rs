@@ -0,0 +1,98 @@
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::graph::{Graph, NodeIndex};
+use crate::measure::{Measure, MinScored};
+
+impl<N, E, const A: usize> Graph<N, E, A> {
+    /*
+    Every node at most `max_hops` edges away from `start`, with the fewest
+    edges it takes to get there. Sorted by node index, `start` included with
+    0 hops.
+    */
+    pub fn reachable_within(&self, start: NodeIndex, max_hops: usize) -> Vec<(NodeIndex, usize)> {
+        let n = self.nodes.len();
+        if start >= n {
+            return Vec::new();
+        }
+
+        let mut hops: Vec<Option<usize>> = vec![None; n];
+        hops[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(node_idx) = queue.pop_front() {
+            let depth = hops[node_idx].unwrap();
+            if depth == max_hops {
+                continue;
+            }
+            for succ in self.outgoing[node_idx].iter().copied() {
+                if hops[succ].is_none() {
+                    hops[succ] = Some(depth + 1);
+                    queue.push_back(succ);
+                }
+            }
+        }
+
+        hops.into_iter()
+            .enumerate()
+            .filter_map(|(node_idx, depth)| Some((node_idx, depth?)))
+            .collect()
+    }
+}
+
+impl<N, E, const A: usize> Graph<N, E, A>
+where
+    E: Measure,
+{
+    /*
+    Isochrone: every node that can be reached from `start` with a total cost
+    of at most `budget` ("what's within 30 minutes of here"), with the cost of
+    the cheapest way there. Sorted by node index, `start` included with a
+    zero cost.
+
+    Dijkstra that stops expanding once the cheapest node left is over budget,
+    reading the edges of every node it expands from the per-node edge lists,
+    so apart from the O(V) distance arrays the work depends on the size of
+    the area, not of the graph. Follows undirected edges both ways, like
+    `reachable_within`.
+    */
+    pub fn reachable_within_cost(&self, start: NodeIndex, budget: E) -> Vec<(NodeIndex, E)> {
+        let n = self.nodes.len();
+        if start >= n {
+            return Vec::new();
+        }
+
+        let mut costs: Vec<Option<E>> = vec![None; n];
+        let mut settled = vec![false; n];
+        let mut heap = BinaryHeap::new();
+
+        costs[start] = Some(E::zero());
+        heap.push(MinScored(E::zero(), start));
+
+        while let Some(MinScored(cost, node_idx)) = heap.pop() {
+            if cost > budget {
+                break;
+            }
+            if settled[node_idx] {
+                continue;
+            }
+            settled[node_idx] = true;
+
+            for (edge_idx, succ) in self.arcs_from(node_idx) {
+                let candidate = cost.add(self.edge_weights[edge_idx]);
+                if !settled[succ]
+                    && candidate <= budget
+                    && costs[succ].is_none_or(|current| candidate < current)
+                {
+                    costs[succ] = Some(candidate);
+                    heap.push(MinScored(candidate, succ));
+                }
+            }
+        }
+
+        costs
+            .into_iter()
+            .enumerate()
+            .filter_map(|(node_idx, cost)| Some((node_idx, cost?)))
+            .collect()
+    }
+}
@@ -0,0 +1,273 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+/*
+Just enough XML for the formats that need it (GraphML): elements,
+attributes, text and the predefined and numeric entities. Comments,
+processing instructions and the doctype are skipped, CDATA is read as text.
+Namespace prefixes are kept in the names.
+*/
+#[derive(Debug, Default)]
+pub(crate) struct Element {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    // the text directly inside the element, children's text not included
+    pub text: String,
+}
+
+impl Element {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// Elements nested deeper than this are an error, parsing recurses once per
+// level.
+const MAX_DEPTH: usize = 128;
+
+// The root element, errors say on which line things went wrong.
+pub(crate) fn parse(xml: &str) -> Result<Element, String> {
+    let mut reader = Reader {
+        chars: xml.chars().peekable(),
+        line: 1,
+    };
+    reader.skip_misc()?;
+    if reader.chars.peek() != Some(&'<') {
+        return Err(reader.error("expected the root element"));
+    }
+    reader.chars.next();
+    let root = reader.element(0)?;
+    reader.skip_misc()?;
+    if reader.chars.peek().is_some() {
+        return Err(reader.error("content after the root element"));
+    }
+    Ok(root)
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+struct Reader<'x> {
+    chars: Peekable<Chars<'x>>,
+    line: usize,
+}
+
+impl Reader<'_> {
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("line {}: {}", self.line, message)
+    }
+
+    // Whitespace, comments, processing instructions and doctypes around the
+    // root element.
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            let mut ahead = self.chars.clone();
+            if ahead.next() != Some('<') {
+                return Ok(());
+            }
+            match ahead.next() {
+                Some('?') | Some('!') => {
+                    self.next();
+                    self.skip_markup()?;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    // After a `<`, skip a `<?...?>`, `<!--...-->` or `<!...>`.
+    fn skip_markup(&mut self) -> Result<(), String> {
+        let end = match self.next() {
+            Some('?') => "?>",
+            Some('!') if self.chars.clone().take(2).eq("--".chars()) => "-->",
+            Some('!') => ">",
+            _ => return Err(self.error("unexpected markup")),
+        };
+        self.skip_until(end)
+    }
+
+    fn skip_until(&mut self, end: &str) -> Result<(), String> {
+        let mut tail = String::new();
+        loop {
+            match self.next() {
+                Some(c) => {
+                    tail.push(c);
+                    if tail.ends_with(end) {
+                        return Ok(());
+                    }
+                }
+                None => return Err(self.error(&format!("missing `{}`", end))),
+            }
+        }
+    }
+
+    // After the `<` of a start tag, `depth` elements deep.
+    fn element(&mut self, depth: usize) -> Result<Element, String> {
+        if depth == MAX_DEPTH {
+            return Err(self.error(&format!("elements nested deeper than {}", MAX_DEPTH)));
+        }
+        let mut element = Element {
+            name: self.name()?,
+            ..Element::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('/') => {
+                    self.next();
+                    return match self.next() {
+                        Some('>') => Ok(element),
+                        _ => Err(self.error("expected `>`")),
+                    };
+                }
+                Some('>') => {
+                    self.next();
+                    break;
+                }
+                Some(_) => {
+                    let key = self.name()?;
+                    self.skip_whitespace();
+                    if self.next() != Some('=') {
+                        return Err(self.error(&format!("expected `=` after `{}`", key)));
+                    }
+                    self.skip_whitespace();
+                    let quote = match self.next() {
+                        Some(c @ ('"' | '\'')) => c,
+                        _ => return Err(self.error("expected a quoted attribute value")),
+                    };
+                    let value = self.text_until(quote)?;
+                    self.next();
+                    element.attrs.push((key, value));
+                }
+                None => return Err(self.error("unterminated start tag")),
+            }
+        }
+
+        loop {
+            element.text.push_str(&self.text_until('<')?);
+            self.next();
+            if self.chars.peek() == Some(&'/') {
+                self.next();
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(self.error(&format!("`</{}>` closes `<{}>`", name, element.name)));
+                }
+                self.skip_whitespace();
+                return match self.next() {
+                    Some('>') => Ok(element),
+                    _ => Err(self.error("expected `>`")),
+                };
+            }
+            if self.chars.clone().take(8).eq("![CDATA[".chars()) {
+                for _ in 0..8 {
+                    self.next();
+                }
+                let mut cdata = String::new();
+                while !cdata.ends_with("]]>") {
+                    match self.next() {
+                        Some(c) => cdata.push(c),
+                        None => return Err(self.error("unterminated CDATA")),
+                    }
+                }
+                cdata.truncate(cdata.len() - 3);
+                element.text.push_str(&cdata);
+                continue;
+            }
+            if matches!(self.chars.peek(), Some('?') | Some('!')) {
+                self.skip_markup()?;
+                continue;
+            }
+            element.children.push(self.element(depth + 1)?);
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let mut name = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+        {
+            name.push(c);
+        }
+        if name.is_empty() {
+            return Err(self.error("expected a name"));
+        }
+        Ok(name)
+    }
+
+    // Text up to (not including) `end`, entities decoded.
+    fn text_until(&mut self, end: char) -> Result<String, String> {
+        let mut text = String::new();
+        loop {
+            match self.chars.peek() {
+                Some(c) if *c == end => return Ok(text),
+                Some('&') => {
+                    self.next();
+                    let mut entity = String::new();
+                    loop {
+                        match self.next() {
+                            Some(';') => break,
+                            Some(c) if entity.len() < 10 => entity.push(c),
+                            _ => return Err(self.error("unterminated entity")),
+                        }
+                    }
+                    text.push(self.entity(&entity)?);
+                }
+                Some(_) => text.extend(self.next()),
+                None if end == '<' => return Err(self.error("unclosed element")),
+                None => return Err(self.error("unterminated attribute value")),
+            }
+        }
+    }
+
+    fn entity(&self, entity: &str) -> Result<char, String> {
+        let code = match entity {
+            "lt" => return Ok('<'),
+            "gt" => return Ok('>'),
+            "amp" => return Ok('&'),
+            "quot" => return Ok('"'),
+            "apos" => return Ok('\''),
+            _ => match entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => entity.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+            },
+        };
+        code.and_then(char::from_u32)
+            .ok_or_else(|| self.error(&format!("unknown entity `&{};`", entity)))
+    }
+}